@@ -1,11 +1,18 @@
 use dashmap::DashMap;
 use jni::JNIEnv;
 use jni::objects::{JObject, JString};
-use jni::sys::{jbyteArray, jint, jlong, jlongArray};
-use lsm_storage::types::VId;
+use jni::sys::{
+    JNI_TRUE, jboolean, jbyteArray, jdouble, jint, jlong, jlongArray, jobjectArray, jstring,
+};
+use lsm_storage::algorithms::path::{EdgeWeight, ShortestPathAlgorithm};
+use lsm_storage::change_log::ChangeSubscription;
+use lsm_storage::types::{Direction, VId};
+use lsm_storage::{GraphCatalog, LsmCommunity, LsmCommunityStorageOptions, OpenMode};
 use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Duration;
 
 use crate::mapper::EdgeIdMapper;
 use crate::wrapper::LsmCommunityWrapper;
@@ -1115,4 +1122,875 @@ pub extern "system" fn Java_com_graph_rocks_RustJNI_getCommunities(
             std::ptr::null_mut()
         }
     }
-}
\ No newline at end of file
+}
+
+/// Get all vertices within k hops of a starting vertex
+///
+/// # Arguments
+/// * `graph_handle` - Handle to the graph instance
+/// * `vertex_handle` - Starting vertex of the expansion
+/// * `k` - Maximum number of hops
+/// * `direction` - Edges to follow: 0 = out, 1 = in, 2 = both
+/// * `limit` - Maximum number of returned vertices, <= 0 for no limit
+///
+/// # Returns
+/// Array format: [vh1, hops1, vh2, hops2, ..., vhm, hopsm]
+/// where vhi is the i-th vertex handle (the start vertex excluded) and hopsi its hop distance
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_getKHopVertices(
+    mut env: JNIEnv,
+    _class: JObject,
+    graph_handle: jlong,
+    vertex_handle: jlong,
+    k: jint,
+    direction: jint,
+    limit: jlong,
+) -> jlongArray {
+    // Get graph instance from global registry
+    let graph = match GRAPH_REGISTRY.get(&graph_handle) {
+        Some(entry) => Arc::clone(entry.value()),
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid graph handle: {}", graph_handle),
+            );
+            return std::ptr::null_mut();
+        }
+    };
+
+    // Validate vertex handle
+    if vertex_handle < 0 || vertex_handle >= graph.vertex_count() as jlong {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Invalid vertex handle: {}", vertex_handle),
+        );
+        return std::ptr::null_mut();
+    }
+
+    // Validate hop count and direction
+    if k < 0 {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Invalid hop count: {}", k),
+        );
+        return std::ptr::null_mut();
+    }
+    let direction = match direction {
+        0 => Direction::Out,
+        1 => Direction::In,
+        2 => Direction::Both,
+        _ => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid direction: {}", direction),
+            );
+            return std::ptr::null_mut();
+        }
+    };
+    let limit = (limit > 0).then_some(limit as usize);
+
+    // Run k-hop expansion - returns Vec<(VId, u32)>
+    let khop_result = graph
+        .lsm_community
+        .k_hop(vertex_handle as VId, k as u32, direction, limit);
+
+    // Convert Vec<(VId, u32)> to interleaved array [vh1, hops1, vh2, hops2, ...]
+    let mut result = Vec::with_capacity(khop_result.len() * 2);
+    for (vertex_id, hops) in khop_result {
+        result.push(vertex_id as jlong);
+        result.push(hops as jlong);
+    }
+
+    // Convert Vec<jlong> to jlongArray
+    match env.new_long_array(result.len() as i32) {
+        Ok(array) => {
+            if let Err(e) = env.set_long_array_region(&array, 0, &result) {
+                let _ = env.throw_new(
+                    "java/lang/RuntimeException",
+                    format!("Failed to set array region: {}", e),
+                );
+                return std::ptr::null_mut();
+            }
+            array.into_raw()
+        }
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/OutOfMemoryError",
+                format!("Failed to allocate array: {}", e),
+            );
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Get the edges of the ego network around a vertex
+///
+/// # Arguments
+/// * `graph_handle` - Handle to the graph instance
+/// * `vertex_handle` - Center vertex of the ego network
+/// * `k` - Radius of the ego network in hops (edges followed in both directions)
+///
+/// # Returns
+/// Array of packed edge handles of all edges among the vertices within k hops
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_getEgoNetworkEdges(
+    mut env: JNIEnv,
+    _class: JObject,
+    graph_handle: jlong,
+    vertex_handle: jlong,
+    k: jint,
+) -> jlongArray {
+    // Get graph instance from global registry
+    let graph = match GRAPH_REGISTRY.get(&graph_handle) {
+        Some(entry) => Arc::clone(entry.value()),
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid graph handle: {}", graph_handle),
+            );
+            return std::ptr::null_mut();
+        }
+    };
+
+    // Validate vertex handle and hop count
+    if vertex_handle < 0 || vertex_handle >= graph.vertex_count() as jlong {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Invalid vertex handle: {}", vertex_handle),
+        );
+        return std::ptr::null_mut();
+    }
+    if k < 0 {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Invalid hop count: {}", k),
+        );
+        return std::ptr::null_mut();
+    }
+
+    // Convert edge pairs to packed i64 handles using EdgeIdMapper
+    let edge_handles: Vec<jlong> = graph
+        .lsm_community
+        .ego_network(vertex_handle as VId, k as u32)
+        .into_iter()
+        .map(|(src, dst)| EdgeIdMapper::pack_edge_handle(src, dst) as jlong)
+        .collect();
+
+    // Convert Vec<jlong> to jlongArray
+    match env.new_long_array(edge_handles.len() as i32) {
+        Ok(array) => {
+            if let Err(e) = env.set_long_array_region(&array, 0, &edge_handles) {
+                let _ = env.throw_new(
+                    "java/lang/RuntimeException",
+                    format!("Failed to set array region: {}", e),
+                );
+                return std::ptr::null_mut();
+            }
+            array.into_raw()
+        }
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/OutOfMemoryError",
+                format!("Failed to allocate array: {}", e),
+            );
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Store the weight of an edge as a named edge property, used by getShortestPath
+///
+/// # Arguments
+/// * `graph_handle` - Handle to the graph instance
+/// * `edge_handle` - Packed handle of the edge
+/// * `property_name` - Name of the weight property
+/// * `weight` - Non-negative weight of the edge
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_putEdgeWeight(
+    mut env: JNIEnv,
+    _class: JObject,
+    graph_handle: jlong,
+    edge_handle: jlong,
+    property_name: JString,
+    weight: jdouble,
+) {
+    // Get graph instance from global registry
+    let graph = match GRAPH_REGISTRY.get(&graph_handle) {
+        Some(entry) => Arc::clone(entry.value()),
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid graph handle: {}", graph_handle),
+            );
+            return;
+        }
+    };
+
+    // Convert JString to Rust String
+    let property_name: String = match env.get_string(&property_name) {
+        Ok(java_str) => java_str.into(),
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid property name: {}", e),
+            );
+            return;
+        }
+    };
+
+    // Unpack edge handle to get (src, dst)
+    let (src, dst) = EdgeIdMapper::unpack_edge_handle(edge_handle);
+
+    if let Err(e) = graph
+        .lsm_community
+        .put_edge_weight(src, dst, &property_name, weight)
+    {
+        let _ = env.throw_new(
+            "java/lang/RuntimeException",
+            format!("Failed to put edge weight: {}", e),
+        );
+    }
+}
+
+/// Find the weighted shortest path between two vertices
+///
+/// # Arguments
+/// * `graph_handle` - Handle to the graph instance
+/// * `src_vertex_handle` - Vertex where the path starts
+/// * `dst_vertex_handle` - Vertex where the path ends
+/// * `weight_property` - Name of the edge weight property, null or empty for unit weights
+/// * `algorithm` - 0 = Dijkstra, 1 = bidirectional Dijkstra (builds the in-neighbor lists
+///   of the graph again after an edge update)
+///
+/// # Returns
+/// Array format: [cost_bits, vh1, vh2, ..., vhn]
+/// where cost_bits is the total cost as `Double.doubleToLongBits` and vh1..vhn the path
+/// from source to target. Returns an empty array if the target is unreachable.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_getShortestPath(
+    mut env: JNIEnv,
+    _class: JObject,
+    graph_handle: jlong,
+    src_vertex_handle: jlong,
+    dst_vertex_handle: jlong,
+    weight_property: JString,
+    algorithm: jint,
+) -> jlongArray {
+    // Get graph instance from global registry
+    let graph = match GRAPH_REGISTRY.get(&graph_handle) {
+        Some(entry) => Arc::clone(entry.value()),
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid graph handle: {}", graph_handle),
+            );
+            return std::ptr::null_mut();
+        }
+    };
+
+    // Validate vertex handles
+    for vertex_handle in [src_vertex_handle, dst_vertex_handle] {
+        if vertex_handle < 0 || vertex_handle >= graph.vertex_count() as jlong {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid vertex handle: {}", vertex_handle),
+            );
+            return std::ptr::null_mut();
+        }
+    }
+
+    // Resolve the weight source and the algorithm
+    let weight = if weight_property.is_null() {
+        EdgeWeight::Unit
+    } else {
+        match env.get_string(&weight_property) {
+            Ok(java_str) => {
+                let property_name: String = java_str.into();
+                if property_name.is_empty() {
+                    EdgeWeight::Unit
+                } else {
+                    EdgeWeight::Property(property_name)
+                }
+            }
+            Err(e) => {
+                let _ = env.throw_new(
+                    "java/lang/IllegalArgumentException",
+                    format!("Invalid weight property: {}", e),
+                );
+                return std::ptr::null_mut();
+            }
+        }
+    };
+    let algorithm = match algorithm {
+        0 => ShortestPathAlgorithm::Dijkstra,
+        1 => ShortestPathAlgorithm::Bidirectional,
+        _ => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid shortest path algorithm: {}", algorithm),
+            );
+            return std::ptr::null_mut();
+        }
+    };
+
+    // Run the search
+    let shortest_path = match graph.lsm_community.shortest_path_with_algorithm(
+        src_vertex_handle as VId,
+        dst_vertex_handle as VId,
+        &weight,
+        algorithm,
+    ) {
+        Ok(shortest_path) => shortest_path,
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/RuntimeException",
+                format!("Failed to compute shortest path: {}", e),
+            );
+            return std::ptr::null_mut();
+        }
+    };
+
+    // Convert the path to [cost_bits, vh1, ..., vhn]
+    let result: Vec<jlong> = match shortest_path {
+        Some(shortest_path) => std::iter::once(shortest_path.cost.to_bits() as jlong)
+            .chain(shortest_path.path.into_iter().map(|vid| vid as jlong))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    // Convert Vec<jlong> to jlongArray
+    match env.new_long_array(result.len() as i32) {
+        Ok(array) => {
+            if let Err(e) = env.set_long_array_region(&array, 0, &result) {
+                let _ = env.throw_new(
+                    "java/lang/RuntimeException",
+                    format!("Failed to set array region: {}", e),
+                );
+                return std::ptr::null_mut();
+            }
+            array.into_raw()
+        }
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/OutOfMemoryError",
+                format!("Failed to allocate array: {}", e),
+            );
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Global registry to store GraphCatalog instances
+/// Key: catalog_handle (jlong), Value: Arc<GraphCatalog>
+static CATALOG_REGISTRY: Lazy<DashMap<i64, Arc<GraphCatalog>>> = Lazy::new(DashMap::new);
+
+/// Global counter for generating unique catalog handles
+static NEXT_CATALOG_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+/// Helper function to convert JString to String
+fn jstring_to_string(
+    env: &mut JNIEnv,
+    string: &JString,
+    error_context: &str,
+) -> Result<String, ()> {
+    match env.get_string(string) {
+        Ok(java_str) => Ok(java_str.into()),
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid {}: {}", error_context, e),
+            );
+            Err(())
+        }
+    }
+}
+
+/// Helper function to look up a catalog, throws if the handle is unknown
+fn get_catalog(env: &mut JNIEnv, catalog_handle: jlong) -> Option<Arc<GraphCatalog>> {
+    match CATALOG_REGISTRY.get(&catalog_handle) {
+        Some(entry) => Some(Arc::clone(entry.value())),
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid catalog handle: {}", catalog_handle),
+            );
+            None
+        }
+    }
+}
+
+/// Helper function to register a graph opened through a catalog and return its handle
+///
+/// A graph already registered shares its wrapper, so that the ID mappers are opened once.
+fn register_graph(
+    env: &mut JNIEnv,
+    lsm_community: anyhow::Result<Arc<LsmCommunity>>,
+    error_context: &str,
+) -> jlong {
+    let wrapper = lsm_community.and_then(|lsm_community| {
+        let registered = GRAPH_REGISTRY.iter().find_map(|entry| {
+            Arc::ptr_eq(&entry.value().lsm_community, &lsm_community)
+                .then(|| Arc::clone(entry.value()))
+        });
+        match registered {
+            Some(wrapper) => Ok(wrapper),
+            None => LsmCommunityWrapper::with_community(lsm_community).map(Arc::new),
+        }
+    });
+    match wrapper {
+        Ok(wrapper) => {
+            let handle = NEXT_GRAPH_HANDLE.fetch_add(1, Ordering::SeqCst);
+            GRAPH_REGISTRY.insert(handle, wrapper);
+            handle
+        }
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/io/IOException",
+                format!("Failed to {}: {}", error_context, e),
+            );
+            -1
+        }
+    }
+}
+
+/// Open the graph catalog of a workspace directory
+///
+/// # Arguments
+/// * `work_space_dir` - Workspace directory, created if missing
+///
+/// # Returns
+/// Handle of the catalog, to be released with closeCatalog
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_openCatalog(
+    mut env: JNIEnv,
+    _class: JObject,
+    work_space_dir: JString,
+) -> jlong {
+    let Ok(work_space_dir) = jstring_to_string(&mut env, &work_space_dir, "workspace directory")
+    else {
+        return -1;
+    };
+
+    match GraphCatalog::open(&work_space_dir) {
+        Ok(catalog) => {
+            let handle = NEXT_CATALOG_HANDLE.fetch_add(1, Ordering::SeqCst);
+            CATALOG_REGISTRY.insert(handle, Arc::new(catalog));
+            handle
+        }
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/io/IOException",
+                format!("Failed to open catalog: {}", e),
+            );
+            -1
+        }
+    }
+}
+
+/// Release a catalog, graphs opened through it stay open until closeDB
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_closeCatalog(
+    mut env: JNIEnv,
+    _class: JObject,
+    catalog_handle: jlong,
+) {
+    if CATALOG_REGISTRY.remove(&catalog_handle).is_none() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!(
+                "Invalid catalog handle: {} (already closed or never opened)",
+                catalog_handle
+            ),
+        );
+    }
+}
+
+/// List the names of the graphs in a catalog, in name order
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_listGraphs(
+    mut env: JNIEnv,
+    _class: JObject,
+    catalog_handle: jlong,
+) -> jobjectArray {
+    let Some(catalog) = get_catalog(&mut env, catalog_handle) else {
+        return std::ptr::null_mut();
+    };
+    let graphs = catalog.list();
+
+    // Convert the names to String[]
+    let array = match env.new_object_array(graphs.len() as i32, "java/lang/String", JObject::null())
+    {
+        Ok(array) => array,
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/OutOfMemoryError",
+                format!("Failed to allocate array: {}", e),
+            );
+            return std::ptr::null_mut();
+        }
+    };
+    for (i, graph) in graphs.iter().enumerate() {
+        let set = env
+            .new_string(&graph.name)
+            .and_then(|name| env.set_object_array_element(&array, i as i32, name));
+        if let Err(e) = set {
+            let _ = env.throw_new(
+                "java/lang/RuntimeException",
+                format!("Failed to set array element: {}", e),
+            );
+            return std::ptr::null_mut();
+        }
+    }
+    array.into_raw()
+}
+
+/// Describe a graph of a catalog
+///
+/// # Returns
+/// JSON object with the name, options, creation time, bucket count, size on disk and
+/// open mode (null if closed) of the graph
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_getGraphInfo(
+    mut env: JNIEnv,
+    _class: JObject,
+    catalog_handle: jlong,
+    graph_name: JString,
+) -> jstring {
+    let Some(catalog) = get_catalog(&mut env, catalog_handle) else {
+        return std::ptr::null_mut();
+    };
+    let Ok(graph_name) = jstring_to_string(&mut env, &graph_name, "graph name") else {
+        return std::ptr::null_mut();
+    };
+
+    let info = catalog
+        .info(&graph_name)
+        .and_then(|info| Ok(serde_json::to_string(&info)?));
+    match info.map(|info| env.new_string(info)) {
+        Ok(Ok(info)) => info.into_raw(),
+        Ok(Err(e)) => {
+            let _ = env.throw_new(
+                "java/lang/RuntimeException",
+                format!("Failed to create string: {}", e),
+            );
+            std::ptr::null_mut()
+        }
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Failed to get graph info: {}", e),
+            );
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Create an empty graph in a catalog and open it read-write
+///
+/// # Returns
+/// Handle of the graph, to be released with closeDB
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_createGraph(
+    mut env: JNIEnv,
+    _class: JObject,
+    catalog_handle: jlong,
+    graph_name: JString,
+) -> jlong {
+    let Some(catalog) = get_catalog(&mut env, catalog_handle) else {
+        return -1;
+    };
+    let Ok(graph_name) = jstring_to_string(&mut env, &graph_name, "graph name") else {
+        return -1;
+    };
+
+    let lsm_community = catalog.create(&graph_name, LsmCommunityStorageOptions::default());
+    register_graph(&mut env, lsm_community, "create graph")
+}
+
+/// Create a graph in a catalog from a graph file and open it read-write
+///
+/// # Returns
+/// Handle of the graph, to be released with closeDB
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_importGraph(
+    mut env: JNIEnv,
+    _class: JObject,
+    catalog_handle: jlong,
+    graph_name: JString,
+    graph_path: JString,
+) -> jlong {
+    let Some(catalog) = get_catalog(&mut env, catalog_handle) else {
+        return -1;
+    };
+    let Ok(graph_name) = jstring_to_string(&mut env, &graph_name, "graph name") else {
+        return -1;
+    };
+    let Ok(graph_path) = jstring_to_string(&mut env, &graph_path, "graph path") else {
+        return -1;
+    };
+
+    let lsm_community = catalog.import(
+        &graph_name,
+        &graph_path,
+        LsmCommunityStorageOptions::default(),
+    );
+    register_graph(&mut env, lsm_community, "import graph")
+}
+
+/// Open a graph of a catalog
+///
+/// # Arguments
+/// * `graph_name` - Name of the graph
+/// * `read_only` - Reject every mutation on the returned handle
+///
+/// # Returns
+/// Handle of the graph, to be released with closeDB
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_openGraph(
+    mut env: JNIEnv,
+    _class: JObject,
+    catalog_handle: jlong,
+    graph_name: JString,
+    read_only: jboolean,
+) -> jlong {
+    let Some(catalog) = get_catalog(&mut env, catalog_handle) else {
+        return -1;
+    };
+    let Ok(graph_name) = jstring_to_string(&mut env, &graph_name, "graph name") else {
+        return -1;
+    };
+
+    let mode = if read_only == JNI_TRUE {
+        OpenMode::ReadOnly
+    } else {
+        OpenMode::ReadWrite
+    };
+    let lsm_community = catalog.open_graph(&graph_name, mode);
+    register_graph(&mut env, lsm_community, "open graph")
+}
+
+/// Rename a closed graph of a catalog
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_renameGraph(
+    mut env: JNIEnv,
+    _class: JObject,
+    catalog_handle: jlong,
+    graph_name: JString,
+    new_graph_name: JString,
+) {
+    let Some(catalog) = get_catalog(&mut env, catalog_handle) else {
+        return;
+    };
+    let Ok(graph_name) = jstring_to_string(&mut env, &graph_name, "graph name") else {
+        return;
+    };
+    let Ok(new_graph_name) = jstring_to_string(&mut env, &new_graph_name, "new graph name") else {
+        return;
+    };
+
+    if let Err(e) = catalog.rename(&graph_name, &new_graph_name) {
+        let _ = env.throw_new(
+            "java/io/IOException",
+            format!("Failed to rename graph: {}", e),
+        );
+    }
+}
+
+/// Drop a closed graph of a catalog with all its files, including the ID mappers
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_dropGraph(
+    mut env: JNIEnv,
+    _class: JObject,
+    catalog_handle: jlong,
+    graph_name: JString,
+) {
+    let Some(catalog) = get_catalog(&mut env, catalog_handle) else {
+        return;
+    };
+    let Ok(graph_name) = jstring_to_string(&mut env, &graph_name, "graph name") else {
+        return;
+    };
+
+    if let Err(e) = catalog.drop_graph(&graph_name) {
+        let _ = env.throw_new(
+            "java/io/IOException",
+            format!("Failed to drop graph: {}", e),
+        );
+    }
+}
+
+/// Global registry to store change subscriptions
+/// Key: subscription_handle (jlong), Value: the subscription, polled by one thread at a time
+static SUBSCRIPTION_REGISTRY: Lazy<DashMap<i64, Arc<Mutex<ChangeSubscription>>>> =
+    Lazy::new(DashMap::new);
+
+/// Global counter for generating unique subscription handles
+static NEXT_SUBSCRIPTION_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+/// Get the sequence number of the next change of a graph
+///
+/// # Returns
+/// Sequence number to subscribe from for the changes made from now on
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_getNextChangeSeq(
+    mut env: JNIEnv,
+    _class: JObject,
+    graph_handle: jlong,
+) -> jlong {
+    // Get graph instance from global registry
+    let graph = match GRAPH_REGISTRY.get(&graph_handle) {
+        Some(entry) => Arc::clone(entry.value()),
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid graph handle: {}", graph_handle),
+            );
+            return -1;
+        }
+    };
+
+    match graph.lsm_community.next_change_seq() {
+        Ok(seq) => seq as jlong,
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/IllegalStateException",
+                format!("Failed to get next change: {}", e),
+            );
+            -1
+        }
+    }
+}
+
+/// Subscribe to the changes of a graph
+///
+/// # Arguments
+/// * `graph_handle` - Handle to the graph instance
+/// * `from_seq` - Sequence number of the first change returned
+///
+/// # Returns
+/// Handle of the subscription, to be polled with pollChanges and released with
+/// closeSubscription
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_subscribeChanges(
+    mut env: JNIEnv,
+    _class: JObject,
+    graph_handle: jlong,
+    from_seq: jlong,
+) -> jlong {
+    // Get graph instance from global registry
+    let graph = match GRAPH_REGISTRY.get(&graph_handle) {
+        Some(entry) => Arc::clone(entry.value()),
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid graph handle: {}", graph_handle),
+            );
+            return -1;
+        }
+    };
+    if from_seq < 0 {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!("Invalid sequence number: {}", from_seq),
+        );
+        return -1;
+    }
+
+    match graph.subscribe_changes(from_seq as u64) {
+        Ok(subscription) => {
+            let handle = NEXT_SUBSCRIPTION_HANDLE.fetch_add(1, Ordering::SeqCst);
+            SUBSCRIPTION_REGISTRY.insert(handle, Arc::new(Mutex::new(subscription)));
+            handle
+        }
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/IllegalStateException",
+                format!("Failed to subscribe to changes: {}", e),
+            );
+            -1
+        }
+    }
+}
+
+/// Poll the next changes of a subscription
+///
+/// # Arguments
+/// * `subscription_handle` - Handle of the subscription
+/// * `max_changes` - Maximum number of changes returned
+/// * `timeout_millis` - How long to wait if no change is available, 0 returns at once
+///
+/// # Returns
+/// JSON array of the changes in sequence order, each with its seq, timestamp, kind,
+/// vertex_id, neighbor and property_name. Empty if none came before the timeout.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_pollChanges(
+    mut env: JNIEnv,
+    _class: JObject,
+    subscription_handle: jlong,
+    max_changes: jint,
+    timeout_millis: jlong,
+) -> jstring {
+    let subscription = match SUBSCRIPTION_REGISTRY.get(&subscription_handle) {
+        Some(entry) => Arc::clone(entry.value()),
+        None => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Invalid subscription handle: {}", subscription_handle),
+            );
+            return std::ptr::null_mut();
+        }
+    };
+    if max_changes < 0 || timeout_millis < 0 {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!(
+                "Invalid poll of {} changes within {} ms",
+                max_changes, timeout_millis
+            ),
+        );
+        return std::ptr::null_mut();
+    }
+
+    let changes = subscription
+        .lock()
+        .poll(
+            max_changes as usize,
+            Duration::from_millis(timeout_millis as u64),
+        )
+        .and_then(|changes| Ok(serde_json::to_string(&changes)?));
+    match changes.map(|changes| env.new_string(changes)) {
+        Ok(Ok(changes)) => changes.into_raw(),
+        Ok(Err(e)) => {
+            let _ = env.throw_new(
+                "java/lang/RuntimeException",
+                format!("Failed to create string: {}", e),
+            );
+            std::ptr::null_mut()
+        }
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/IllegalStateException",
+                format!("Failed to poll changes: {}", e),
+            );
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Release a subscription
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_graph_rocks_RustJNI_closeSubscription(
+    mut env: JNIEnv,
+    _class: JObject,
+    subscription_handle: jlong,
+) {
+    if SUBSCRIPTION_REGISTRY.remove(&subscription_handle).is_none() {
+        let _ = env.throw_new(
+            "java/lang/IllegalArgumentException",
+            format!(
+                "Invalid subscription handle: {} (already closed or never opened)",
+                subscription_handle
+            ),
+        );
+    }
+}
@@ -1,7 +1,12 @@
-use std::{sync::Arc, vec};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    vec,
+};
 
 use lsm_storage::{
     LsmCommunity, LsmCommunityStorageOptions,
+    change_log::ChangeSubscription,
     types::{EdgeList, VId},
 };
 
@@ -21,23 +26,34 @@ pub struct LsmCommunityWrapper {
 }
 
 impl LsmCommunityWrapper {
-    /// Open an existing LSM-Community graph
+    /// Open an existing LSM-Community graph, tuned by the `LSM_COMMUNITY_*` environment
     pub fn open(graph_name: &str) -> anyhow::Result<Self> {
         let mut options = LsmCommunityStorageOptions::default();
+        options.apply_env_overrides()?;
         options.graph_name = graph_name.to_owned();
+        Self::with_community(LsmCommunity::open(options)?)
+    }
 
-        // Construct log paths for vertex and edge ID mappers
-        let vertex_log_path = format!(
-            "./{}/{}/vertex-id-mapping.log",
-            options.work_space_dir, options.graph_name
-        );
-        let edge_log_path = format!(
-            "./{}/{}/edge-id-mapping.log",
+    /// Wrap an opened LSM-Community graph, e.g. one opened through a `GraphCatalog`
+    ///
+    /// The ID-mapping logs live in the graph directory. They used to live under
+    /// `./{work_space_dir}/{graph_name}`, which is another directory for an absolute
+    /// workspace, logs found only there are moved into the graph directory
+    pub fn with_community(lsm_community: Arc<LsmCommunity>) -> anyhow::Result<Self> {
+        let options = lsm_community.options();
+
+        // Construct log paths for vertex and edge ID mappers, the workspace may be absolute
+        let graph_dir = Path::new(&options.work_space_dir).join(&options.graph_name);
+        let legacy_graph_dir = PathBuf::from(format!(
+            "./{}/{}",
             options.work_space_dir, options.graph_name
-        );
+        ));
+        let vertex_log_path =
+            id_mapping_log_path(&graph_dir, &legacy_graph_dir, "vertex-id-mapping.log");
+        let edge_log_path =
+            id_mapping_log_path(&graph_dir, &legacy_graph_dir, "edge-id-mapping.log");
 
-        // Open LSM-Community and ID mappers
-        let lsm_community = LsmCommunity::open(options)?;
+        // Open ID mappers
         let vertex_id_mapper = Arc::new(VertexIdMapper::new(vertex_log_path)?);
         let edge_id_mapper = Arc::new(EdgeIdMapper::new(edge_log_path)?);
 
@@ -48,6 +64,17 @@ impl LsmCommunityWrapper {
         })
     }
 
+    /// Fail before touching the ID mappers if the graph is read-only
+    fn check_writable(&self) -> anyhow::Result<()> {
+        if self.lsm_community.is_read_only() {
+            return Err(anyhow::anyhow!(
+                "Graph {} is opened read-only",
+                self.lsm_community.options().graph_name
+            ));
+        }
+        Ok(())
+    }
+
     pub fn vertex_count(&self) -> u32 { self.lsm_community.vertex_count() as u32}
 
     /// Get all vertex (Inner) IDs in the graph
@@ -102,6 +129,7 @@ impl LsmCommunityWrapper {
         dst: VId,
         edge_property: &[u8],
     ) -> anyhow::Result<()> {
+        self.check_writable()?;
         // Step 1 - Map the new outer edge ID to the new inner edge handle
         self.edge_id_mapper
             .insert(outer_id, EdgeIdMapper::pack_edge_handle(src, dst))?;
@@ -122,6 +150,7 @@ impl LsmCommunityWrapper {
 
     /// Remove an edge from the graph
     pub fn remove_edge(&self, src: VId, dst: VId) -> anyhow::Result<()> {
+        self.check_writable()?;
         // Lookup the outer edge ID and remove it from the edge id mapper
         let outer_edge_id_opt = self
             .edge_id_mapper
@@ -139,6 +168,11 @@ impl LsmCommunityWrapper {
         }
     }
 
+    /// Subscribe to the changes of the graph from a sequence number on
+    pub fn subscribe_changes(&self, from_seq: u64) -> anyhow::Result<ChangeSubscription> {
+        self.lsm_community.subscribe_changes(from_seq)
+    }
+
     /// Create example graph for testing with properties
     ///
     /// Topology: 13 vertices, 20 edges (from example.graph)
@@ -237,3 +271,25 @@ impl LsmCommunityWrapper {
         Ok(wrapper)
     }
 }
+
+/// Path of an ID-mapping log in the graph directory, moving a log found only in the legacy
+/// directory there first. A legacy log that cannot be moved is opened in place.
+fn id_mapping_log_path(graph_dir: &Path, legacy_graph_dir: &Path, file_name: &str) -> PathBuf {
+    let path = graph_dir.join(file_name);
+    let legacy_path = legacy_graph_dir.join(file_name);
+    if path.exists() || !legacy_path.exists() {
+        return path;
+    }
+    match std::fs::rename(&legacy_path, &path) {
+        Ok(()) => path,
+        Err(e) => {
+            eprintln!(
+                "Failed to move {} to {}, opening it in place: {}",
+                legacy_path.display(),
+                path.display(),
+                e
+            );
+            legacy_path
+        }
+    }
+}
@@ -1,4 +1,36 @@
 pub mod bfs;
+pub mod centrality;
 pub mod comm;
+pub mod kcore;
+pub mod khop;
+pub mod louvain;
+pub mod pagerank;
+pub mod path;
+pub mod reachability;
+pub mod sampling;
+pub mod subgraph;
+pub mod temporal;
+pub mod triangle;
 pub mod wcc;
 mod scc;
+mod undirected;
+
+/// Number of vertices whose neighbors are fetched with one batched read.
+pub(crate) const NEIGHBOR_BATCH_SIZE: usize = 4096;
+
+/// Build a pool of `num_threads` worker threads for one run, `None` runs on the global
+/// rayon pool.
+pub(crate) fn build_thread_pool(
+    num_threads: Option<usize>,
+) -> anyhow::Result<Option<rayon::ThreadPool>> {
+    num_threads
+        .map(|num_threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to build a pool of {} threads: {}", num_threads, e)
+                })
+        })
+        .transpose()
+}
@@ -1,11 +1,88 @@
-use crate::{LsmCommunity, types::VId};
-use std::collections::VecDeque;
+use crate::{LsmCommunity, algorithms::build_thread_pool, types::VId, vertex_index::VertexIndex};
+use rayon::prelude::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Number of frontier vertices whose neighbors are fetched with one batched read.
+const BFS_BATCH_SIZE: usize = 1024;
+
+/// Options of the parallel, direction-optimizing BFS.
+#[derive(Debug, Clone)]
+pub struct BfsOptions {
+    /// Stop expanding once this distance is reached, `None` for an unbounded traversal.
+    pub max_depth: Option<u32>,
+    /// Number of worker threads, `None` runs on the global rayon pool.
+    pub num_threads: Option<usize>,
+    /// Switch to bottom-up when the frontier edges exceed `unexplored edges / alpha`.
+    pub alpha: u64,
+    /// Switch back to top-down when the frontier shrinks below `vertex count / beta`.
+    pub beta: u64,
+}
+
+impl Default for BfsOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            num_threads: None,
+            alpha: 14,
+            beta: 24,
+        }
+    }
+}
+
+/// Visited bitmap that can be marked concurrently.
+struct AtomicBitmap {
+    words: Vec<AtomicU64>,
+    len: usize,
+}
+
+impl AtomicBitmap {
+    fn new(len: usize) -> Self {
+        Self {
+            words: (0..len.div_ceil(64)).map(|_| AtomicU64::new(0)).collect(),
+            len,
+        }
+    }
+
+    /// Set the bit of `vid`, returns `true` if this call set it.
+    #[inline]
+    fn test_and_set(&self, vid: VId) -> bool {
+        if vid as usize >= self.len {
+            return false;
+        }
+        let mask = 1u64 << (vid % 64);
+        let previous = self.words[(vid / 64) as usize].fetch_or(mask, Ordering::Relaxed);
+        previous & mask == 0
+    }
+
+    #[inline]
+    fn get(&self, vid: VId) -> bool {
+        (vid as usize) < self.len
+            && self.words[(vid / 64) as usize].load(Ordering::Relaxed) & (1u64 << (vid % 64)) != 0
+    }
+}
+
+/// In-neighbor lists of all vertices in CSR layout, used by the bottom-up phase.
+pub(crate) struct InAdjacency {
+    offsets: Vec<usize>,
+    sources: Vec<VId>,
+}
+
+impl InAdjacency {
+    /// The in neighbors of a vertex, empty for a vertex inserted after the build.
+    #[inline]
+    pub(crate) fn in_neighbors(&self, vid: VId) -> &[VId] {
+        if vid as usize + 1 >= self.offsets.len() {
+            return &[];
+        }
+        &self.sources[self.offsets[vid as usize]..self.offsets[vid as usize + 1]]
+    }
+}
 
 impl LsmCommunity {
     /// Performs a Breadth-First Search (BFS) starting from the specified vertex.
     ///
-    /// This implementation uses a bitmap-based visited tracking mechanism for optimal
-    /// space efficiency, particularly important when handling large-scale graphs.
+    /// Runs [`LsmCommunity::bfs_with_options`] with the default [`BfsOptions`].
     ///
     /// # Arguments
     ///
@@ -18,71 +95,213 @@ impl LsmCommunity {
     /// - `distance`: The number of hops (shortest path length) from the start vertex
     ///
     /// Returns an empty vector if the start vertex is invalid or does not exist.
+    pub fn bfs(&self, start_vertex: VId) -> Vec<(VId, u32)> {
+        self.bfs_parallel(start_vertex, &BfsOptions::default())
+    }
+
+    /// Performs a level-synchronous, parallel and direction-optimizing BFS.
     ///
-    /// # Performance Characteristics
+    /// Each level is expanded either top-down, reading the out neighbors of the frontier
+    /// with batched block reads, or bottom-up, letting every unvisited vertex look for a
+    /// parent among its in neighbors in the frontier bitmap. The direction is chosen per
+    /// level from the vertex degrees recorded in the vertex index.
     ///
-    /// - **Time Complexity**: O(V + E) where V is the number of reachable vertices and E is edges
-    /// - **Space Complexity**: O(V/64) for the visited bitmap + O(V) for the result vector
-    /// - Uses bit-level marking (1 bit per vertex) instead of hash-based sets for minimal memory overhead
-    /// - Pre-allocates queue and result capacities to reduce dynamic reallocations
+    /// # Arguments
     ///
-    /// # Notes
+    /// * `start_vertex` - The vertex ID from which to begin the BFS traversal
+    /// * `options` - Maximum depth, thread count and direction switching thresholds
     ///
-    /// - The algorithm gracefully handles invalid vertices encountered during traversal
-    /// - Each vertex appears at most once in the result with its shortest distance
-    /// - The order of vertices in the result follows BFS discovery order (level by level)
-    pub fn bfs(&self, start_vertex: VId) -> Vec<(VId, u32)> {
-        // Initialize visited bitmap - using bit-level marking for space efficiency
-        let max_vid = self.vertex_count() as VId;
-        let bitmap_size = ((max_vid + 63) / 64) as usize;
-        let mut visited = vec![0u64; bitmap_size];
-
-        // Helper closures for visited status
-        let mark_visited = |visited: &mut [u64], vid: VId| {
-            let idx = (vid / 64) as usize;
-            let bit = vid % 64;
-            if idx < visited.len() {
-                visited[idx] |= 1u64 << bit;
-            }
-        };
+    /// # Returns
+    ///
+    /// Returns a vector of tuples `(vertex_id, distance)`, ordered level by level and by
+    /// vertex ID inside a level. Returns an empty vector if the start vertex is invalid, or
+    /// an error if the thread pool cannot be built.
+    ///
+    /// # Performance Characteristics
+    ///
+    /// - **Time Complexity**: O(V + E) where V is the number of reachable vertices and E is edges
+    /// - **Space Complexity**: O(V/64) for the visited and frontier bitmaps + O(V) for the result.
+    ///   The first bottom-up level builds the in-neighbor lists of the graph in O(V + E),
+    ///   unless they are cached since the last edge update.
+    pub fn bfs_with_options(
+        &self,
+        start_vertex: VId,
+        options: &BfsOptions,
+    ) -> anyhow::Result<Vec<(VId, u32)>> {
+        Ok(match build_thread_pool(options.num_threads)? {
+            Some(pool) => pool.install(|| self.bfs_parallel(start_vertex, options)),
+            None => self.bfs_parallel(start_vertex, options),
+        })
+    }
+
+    fn bfs_parallel(&self, start_vertex: VId, options: &BfsOptions) -> Vec<(VId, u32)> {
+        self.expire_derived_indexes();
+        let vertex_index = self.vertex_index.read();
+        let vertex_count = vertex_index.vertex_array.len();
+        if start_vertex as usize >= vertex_count {
+            return Vec::new();
+        }
 
-        let is_visited = |visited: &[u64], vid: VId| -> bool {
-            let idx = (vid / 64) as usize;
-            let bit = vid % 64;
-            idx < visited.len() && (visited[idx] & (1u64 << bit)) != 0
+        let degree = |vid: VId| -> u64 {
+            vertex_index
+                .vertex_degree
+                .get(vid as usize)
+                .copied()
+                .unwrap_or(0) as u64
         };
 
-        // BFS queue: store (vertex_id, distance)
-        let mut queue = VecDeque::with_capacity(1024);
-        let mut result = Vec::with_capacity(1024);
+        let visited = AtomicBitmap::new(vertex_count);
+        let mut in_adjacency: Option<Arc<InAdjacency>> = None;
+        let mut unexplored_edges: u64 = vertex_index
+            .vertex_degree
+            .par_iter()
+            .map(|&d| d as u64)
+            .sum();
+        let mut bottom_up = false;
 
-        // Start BFS
-        queue.push_back((start_vertex, 0u32));
-        mark_visited(&mut visited, start_vertex);
+        let mut result = Vec::with_capacity(1024);
+        let mut frontier = vec![start_vertex];
+        visited.test_and_set(start_vertex);
         result.push((start_vertex, 0u32));
 
-        while let Some((current_vid, current_dist)) = queue.pop_front() {
-            // Read the neighbor list of the current vertex
-            let neighbors = match self.read_out_neighbor_clone(current_vid) {
-                // Success: Get the Vec<VId> neighbor list directly (no Option needed)
-                Ok(n) => n,
-                // Failure: Skip the current iteration without further processing
-                Err(_) => continue,
+        let mut current_dist = 0u32;
+        while !frontier.is_empty() {
+            if options
+                .max_depth
+                .is_some_and(|max_depth| current_dist >= max_depth)
+            {
+                break;
+            }
+
+            // Choose the direction of this level
+            let frontier_edges: u64 = frontier.par_iter().map(|&vid| degree(vid)).sum();
+            unexplored_edges = unexplored_edges.saturating_sub(frontier_edges);
+            if bottom_up {
+                bottom_up = frontier.len() as u64 >= vertex_count as u64 / options.beta.max(1);
+            } else {
+                bottom_up = frontier_edges > unexplored_edges / options.alpha.max(1);
+            }
+
+            let mut next_frontier = if bottom_up {
+                let in_adjacency =
+                    in_adjacency.get_or_insert_with(|| self.cached_in_adjacency(&vertex_index));
+                self.bfs_bottom_up_step(&frontier, &visited, in_adjacency)
+            } else {
+                self.bfs_top_down_step(&frontier, &visited, &vertex_index)
             };
+            next_frontier.par_sort_unstable();
 
-            // Calculate the distance of the neighbor vertices
             let next_dist = current_dist + 1;
+            result.extend(next_frontier.iter().map(|&vid| (vid, next_dist)));
 
-            // Iterate over and process each neighbor vertex
-            for neighbor_vid in neighbors {
-                if !is_visited(&visited, neighbor_vid) {
-                    mark_visited(&mut visited, neighbor_vid);
-                    queue.push_back((neighbor_vid, next_dist));
-                    result.push((neighbor_vid, next_dist));
+            frontier = next_frontier;
+            current_dist = next_dist;
+        }
+
+        result
+    }
+
+    /// Expand the frontier through the out neighbors of its vertices.
+    fn bfs_top_down_step(
+        &self,
+        frontier: &[VId],
+        visited: &AtomicBitmap,
+        vertex_index: &VertexIndex,
+    ) -> Vec<VId> {
+        frontier
+            .par_chunks(BFS_BATCH_SIZE)
+            .flat_map_iter(|batch| {
+                let neighbor_lists =
+                    self.read_out_neighbors_batch_or_each_hold_index(batch, vertex_index);
+                neighbor_lists
+                    .into_iter()
+                    .flatten()
+                    .filter(|&neighbor_vid| visited.test_and_set(neighbor_vid))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Let every unvisited vertex search its in neighbors for a parent in the frontier.
+    fn bfs_bottom_up_step(
+        &self,
+        frontier: &[VId],
+        visited: &AtomicBitmap,
+        in_adjacency: &InAdjacency,
+    ) -> Vec<VId> {
+        let vertex_count = visited.len;
+        let frontier_bitmap = AtomicBitmap::new(vertex_count);
+        frontier.par_iter().for_each(|&vid| {
+            frontier_bitmap.test_and_set(vid);
+        });
+
+        let next_frontier: Vec<VId> = (0..vertex_count as VId)
+            .into_par_iter()
+            .filter(|&vid| {
+                !visited.get(vid)
+                    && in_adjacency
+                        .in_neighbors(vid)
+                        .iter()
+                        .any(|&parent| frontier_bitmap.get(parent))
+            })
+            .collect();
+        for &vid in &next_frontier {
+            visited.test_and_set(vid);
+        }
+        next_frontier
+    }
+
+    /// Get the in-neighbor lists of all vertices, built on first use and kept until the next
+    /// edge update.
+    ///
+    /// Call [`LsmCommunity::expire_derived_indexes`] before taking the vertex index, so that
+    /// edges expired since the build are dropped too.
+    pub(crate) fn cached_in_adjacency(&self, vertex_index: &VertexIndex) -> Arc<InAdjacency> {
+        let edge_generation = self.edge_generation.load(Ordering::Acquire);
+        if let Some(in_adjacency) = self.in_adjacency.lock().as_ref() {
+            return Arc::clone(in_adjacency);
+        }
+        let in_adjacency = Arc::new(self.build_in_adjacency(vertex_index));
+        // Lists built across an edge update may miss it, they serve this query only
+        let mut cached = self.in_adjacency.lock();
+        if self.edge_generation.load(Ordering::Acquire) == edge_generation {
+            *cached = Some(Arc::clone(&in_adjacency));
+        }
+        in_adjacency
+    }
+
+    /// Build the in-neighbor lists of all vertices from the batched out-neighbor path.
+    pub(crate) fn build_in_adjacency(&self, vertex_index: &VertexIndex) -> InAdjacency {
+        let vertex_count = vertex_index.vertex_array.len();
+        let all_vertices: Vec<VId> = (0..vertex_count as VId).collect();
+        let out_lists: Vec<Vec<VId>> = all_vertices
+            .par_chunks(BFS_BATCH_SIZE)
+            .flat_map_iter(|batch| {
+                self.read_out_neighbors_batch_or_each_hold_index(batch, vertex_index)
+            })
+            .collect();
+
+        // Counting sort of the edges by destination
+        let mut offsets = vec![0usize; vertex_count + 1];
+        for &dst in out_lists.iter().flatten() {
+            if (dst as usize) < vertex_count {
+                offsets[dst as usize + 1] += 1;
+            }
+        }
+        for i in 0..vertex_count {
+            offsets[i + 1] += offsets[i];
+        }
+        let mut cursor = offsets.clone();
+        let mut sources = vec![0; offsets[vertex_count]];
+        for (src, neighbors) in out_lists.iter().enumerate() {
+            for &dst in neighbors {
+                if (dst as usize) < vertex_count {
+                    sources[cursor[dst as usize]] = src as VId;
+                    cursor[dst as usize] += 1;
                 }
             }
         }
 
-        result
+        InAdjacency { offsets, sources }
     }
 }
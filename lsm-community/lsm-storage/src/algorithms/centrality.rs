@@ -0,0 +1,279 @@
+use crate::{LsmCommunity, algorithms::build_thread_pool, types::VId};
+use rand::{SeedableRng, rngs::StdRng, seq::index};
+use rayon::prelude::*;
+
+/// Number of sources whose contributions are summed up by one task.
+const SOURCES_PER_TASK: usize = 64;
+
+/// Number of tasks whose partial sums are merged at once, bounds the memory to this many
+/// score vectors.
+const TASKS_PER_ROUND: usize = 16;
+
+/// Options of [`LsmCommunity::betweenness_centrality`] and
+/// [`LsmCommunity::harmonic_closeness`].
+#[derive(Debug, Clone, Default)]
+pub struct CentralityOptions {
+    /// Number of source vertices (pivots) sampled uniformly, `None` uses every vertex and
+    /// gives exact scores. More pivots give more accurate estimates.
+    pub samples: Option<usize>,
+    /// Seed of the pivot sampling, equal seeds give equal scores on the same graph.
+    pub seed: u64,
+    /// Number of worker threads, `None` runs on the global rayon pool.
+    pub num_threads: Option<usize>,
+}
+
+impl LsmCommunity {
+    /// Computes the betweenness centrality of every vertex with Brandes' algorithm.
+    ///
+    /// The betweenness of a vertex is the number of shortest paths between other vertices
+    /// that pass through it, shared among equally short paths. Vertices bridging two
+    /// communities carry the paths between them and score high. Paths follow the out edges,
+    /// with delta logs applied. With `samples`, only shortest paths from the sampled pivots
+    /// are counted and the sum is scaled by `V / samples`, an unbiased estimate.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Pivot count, seed and threads
+    ///
+    /// # Returns
+    ///
+    /// Returns a vector where `result[vertex_id]` is the (estimated) betweenness of the
+    /// vertex, not normalized, or an error if a neighbor list cannot be read or the thread
+    /// pool cannot be built.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(k * (V + E)) for k pivots, pivots run in parallel
+    /// - Space: O(V + E) + O(V) per worker
+    pub fn betweenness_centrality(&self, options: &CentralityOptions) -> anyhow::Result<Vec<f64>> {
+        let out_lists = {
+            let vertex_index = self.vertex_index.read();
+            self.read_all_out_neighbors(&vertex_index)?
+        };
+        let vertex_count = out_lists.len();
+        let pivots = sample_pivots(vertex_count, options);
+        let scale = if pivots.is_empty() {
+            0.0
+        } else {
+            vertex_count as f64 / pivots.len() as f64
+        };
+
+        let mut scores = sum_over_sources(&pivots, vertex_count, options, |sources, scores| {
+            let mut search = BrandesSearch::new(vertex_count);
+            for &source in sources {
+                search.accumulate(&out_lists, source, scores);
+            }
+        })?;
+        scores.iter_mut().for_each(|score| *score *= scale);
+        Ok(scores)
+    }
+
+    /// Computes the harmonic closeness centrality of every vertex.
+    ///
+    /// The harmonic closeness of a vertex is the sum of `1 / d(v, u)` over all other
+    /// vertices `u`, where `d` is the hop distance along the out edges with delta logs
+    /// applied and unreachable vertices count 0. With `samples`, only the distances to the
+    /// sampled pivots are summed and scaled up to the `V - 1` other vertices.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Pivot count, seed and threads
+    ///
+    /// # Returns
+    ///
+    /// Returns a vector where `result[vertex_id]` is the (estimated) harmonic closeness of
+    /// the vertex, not normalized, or an error if a neighbor list cannot be read or the
+    /// thread pool cannot be built.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(k * (V + E)) for k pivots, one backward BFS per pivot, in parallel
+    /// - Space: O(V + E) + O(V) per worker
+    pub fn harmonic_closeness(&self, options: &CentralityOptions) -> anyhow::Result<Vec<f64>> {
+        let out_lists = {
+            let vertex_index = self.vertex_index.read();
+            self.read_all_out_neighbors(&vertex_index)?
+        };
+        let vertex_count = out_lists.len();
+        let in_lists = transpose(&out_lists);
+        let pivots = sample_pivots(vertex_count, options);
+
+        let mut scores = sum_over_sources(&pivots, vertex_count, options, |sources, scores| {
+            let mut dist = vec![u32::MAX; vertex_count];
+            let mut queue = Vec::new();
+            for &source in sources {
+                // Backward BFS: dist[v] is the distance from v to the pivot
+                dist[source as usize] = 0;
+                queue.clear();
+                queue.push(source);
+                let mut head = 0;
+                while head < queue.len() {
+                    let vid = queue[head];
+                    head += 1;
+                    let next_dist = dist[vid as usize] + 1;
+                    for &in_neighbor in &in_lists[vid as usize] {
+                        if dist[in_neighbor as usize] == u32::MAX {
+                            dist[in_neighbor as usize] = next_dist;
+                            scores[in_neighbor as usize] += 1.0 / next_dist as f64;
+                            queue.push(in_neighbor);
+                        }
+                    }
+                }
+                for &vid in &queue {
+                    dist[vid as usize] = u32::MAX;
+                }
+            }
+        })?;
+
+        // Scale the sampled pivots up to all other vertices
+        if pivots.len() < vertex_count {
+            let mut is_pivot = vec![false; vertex_count];
+            for &pivot in &pivots {
+                is_pivot[pivot as usize] = true;
+            }
+            for (vid, score) in scores.iter_mut().enumerate() {
+                let other_pivots = pivots.len() - is_pivot[vid] as usize;
+                *score = if other_pivots == 0 {
+                    0.0
+                } else {
+                    *score * (vertex_count - 1) as f64 / other_pivots as f64
+                };
+            }
+        }
+        Ok(scores)
+    }
+}
+
+/// Reusable state of the single-source shortest paths of Brandes' algorithm.
+struct BrandesSearch {
+    dist: Vec<u32>,
+    /// Number of shortest paths from the source.
+    sigma: Vec<f64>,
+    /// Dependency of the source on every vertex.
+    delta: Vec<f64>,
+    /// Visited vertices in BFS order.
+    order: Vec<VId>,
+}
+
+impl BrandesSearch {
+    fn new(vertex_count: usize) -> Self {
+        Self {
+            dist: vec![u32::MAX; vertex_count],
+            sigma: vec![0.0; vertex_count],
+            delta: vec![0.0; vertex_count],
+            order: Vec::new(),
+        }
+    }
+
+    /// Add the dependencies of `source` on every other vertex to `scores`.
+    fn accumulate(&mut self, out_lists: &[Vec<VId>], source: VId, scores: &mut [f64]) {
+        self.order.clear();
+        self.dist[source as usize] = 0;
+        self.sigma[source as usize] = 1.0;
+        self.order.push(source);
+        let mut head = 0;
+        while head < self.order.len() {
+            let vid = self.order[head] as usize;
+            head += 1;
+            for &neighbor in &out_lists[vid] {
+                let neighbor = neighbor as usize;
+                if self.dist[neighbor] == u32::MAX {
+                    self.dist[neighbor] = self.dist[vid] + 1;
+                    self.order.push(neighbor as VId);
+                }
+                if self.dist[neighbor] == self.dist[vid] + 1 {
+                    self.sigma[neighbor] += self.sigma[vid];
+                }
+            }
+        }
+
+        // Back propagation in reverse BFS order, the successors on shortest paths are final
+        for &vid in self.order.iter().rev() {
+            let vid = vid as usize;
+            let mut dependency = 0.0;
+            for &neighbor in &out_lists[vid] {
+                let neighbor = neighbor as usize;
+                if self.dist[neighbor] == self.dist[vid] + 1 {
+                    dependency +=
+                        self.sigma[vid] / self.sigma[neighbor] * (1.0 + self.delta[neighbor]);
+                }
+            }
+            self.delta[vid] = dependency;
+            if vid != source as usize {
+                scores[vid] += dependency;
+            }
+        }
+
+        for &vid in &self.order {
+            let vid = vid as usize;
+            self.dist[vid] = u32::MAX;
+            self.sigma[vid] = 0.0;
+            self.delta[vid] = 0.0;
+        }
+    }
+}
+
+/// All vertices, or `samples` of them drawn without replacement with the seed.
+fn sample_pivots(vertex_count: usize, options: &CentralityOptions) -> Vec<VId> {
+    match options.samples {
+        Some(samples) if samples < vertex_count => {
+            let mut rng = StdRng::seed_from_u64(options.seed);
+            let mut pivots: Vec<VId> = index::sample(&mut rng, vertex_count, samples)
+                .into_iter()
+                .map(|vid| vid as VId)
+                .collect();
+            pivots.sort_unstable();
+            pivots
+        }
+        _ => (0..vertex_count as VId).collect(),
+    }
+}
+
+/// Sum the scores contributed by every source in parallel.
+///
+/// The sources are split in fixed-size tasks whose partial sums are merged in task order,
+/// so the floating-point result does not depend on the number of threads.
+fn sum_over_sources(
+    sources: &[VId],
+    vertex_count: usize,
+    options: &CentralityOptions,
+    contribute: impl Fn(&[VId], &mut [f64]) + Sync,
+) -> anyhow::Result<Vec<f64>> {
+    let run = || {
+        let mut scores = vec![0.0; vertex_count];
+        for round in sources.chunks(SOURCES_PER_TASK * TASKS_PER_ROUND) {
+            let partial_sums: Vec<Vec<f64>> = round
+                .par_chunks(SOURCES_PER_TASK)
+                .map(|task| {
+                    let mut partial = vec![0.0; vertex_count];
+                    contribute(task, &mut partial);
+                    partial
+                })
+                .collect();
+            for partial in partial_sums {
+                scores
+                    .iter_mut()
+                    .zip(partial)
+                    .for_each(|(score, value)| *score += value);
+            }
+        }
+        scores
+    };
+    Ok(match build_thread_pool(options.num_threads)? {
+        Some(pool) => pool.install(run),
+        None => run(),
+    })
+}
+
+/// In-neighbor lists from out-neighbor lists.
+fn transpose(out_lists: &[Vec<VId>]) -> Vec<Vec<VId>> {
+    let mut in_lists = vec![Vec::new(); out_lists.len()];
+    for (src, neighbors) in out_lists.iter().enumerate() {
+        for &dst in neighbors {
+            if let Some(in_list) = in_lists.get_mut(dst as usize) {
+                in_list.push(src as VId);
+            }
+        }
+    }
+    in_lists
+}
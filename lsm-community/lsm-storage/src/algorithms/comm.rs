@@ -5,7 +5,8 @@ impl LsmCommunity {
     ///
     /// Communities are groups of vertices that were detected during graph loading,
     /// typically based on the community IDs present in the input graph file.
-    /// This method provides access to the static community partitioning.
+    /// This method provides access to the static community partitioning, or to the
+    /// partitioning installed with [`LsmCommunity::apply_communities`].
     ///
     /// # Returns
     ///
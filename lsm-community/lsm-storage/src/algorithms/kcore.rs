@@ -0,0 +1,251 @@
+use crate::{LsmCommunity, algorithms::undirected::UndirectedGraph, types::VId};
+use std::collections::VecDeque;
+
+impl LsmCommunity {
+    /// Computes the core number of every vertex.
+    ///
+    /// The core number of a vertex is the largest `k` such that the vertex belongs to the
+    /// k-core, the maximal subgraph in which every vertex has at least `k` neighbors. The
+    /// graph is treated as undirected with delta logs applied, ignoring duplicate edges
+    /// and self loops.
+    ///
+    /// # Returns
+    ///
+    /// Returns a vector where `result[vertex_id]` is the core number of the vertex.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(V + E) with the bucket-based peeling of Batagelj and Zaversnik
+    /// - Space: O(V + E) for the undirected adjacency
+    pub fn core_decomposition(&self) -> Vec<u32> {
+        let graph = {
+            let vertex_index = self.vertex_index.read();
+            self.build_undirected_graph(&vertex_index)
+        };
+        core_numbers(&graph)
+    }
+
+    /// Finds the maximal connected k-core containing a query vertex.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertex_id` - The query vertex
+    /// * `k` - Minimum number of neighbors of every member inside the community
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Vec<VId>)`: The members of the community in ascending order, including the
+    ///   query vertex
+    /// - `None`: If the vertex is invalid or its core number is below `k`
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(V + E) for the core decomposition + O(C + E_C) to collect the community
+    /// - Space: O(V + E)
+    pub fn k_core_search(&self, vertex_id: VId, k: u32) -> Option<Vec<VId>> {
+        if vertex_id as usize >= self.vertex_count() {
+            return None;
+        }
+        let graph = {
+            let vertex_index = self.vertex_index.read();
+            self.build_undirected_graph(&vertex_index)
+        };
+        let core_number = core_numbers(&graph);
+        if core_number[vertex_id as usize] < k {
+            return None;
+        }
+
+        let members = connected_members(&graph, vertex_id, |u, v| {
+            core_number[u as usize] >= k && core_number[v as usize] >= k
+        });
+        Some(members)
+    }
+
+    /// Finds the maximal connected k-truss containing a query vertex.
+    ///
+    /// The k-truss is the maximal subgraph in which every edge belongs to at least `k - 2`
+    /// triangles, a denser notion of community than the k-core.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertex_id` - The query vertex
+    /// * `k` - Truss order, every edge of the community closes at least `k - 2` triangles
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Vec<VId>)`: The members of the community in ascending order, including the
+    ///   query vertex
+    /// - `None`: If the vertex is invalid or none of its edges survives in the k-truss
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(E^1.5) for the edge supports and the peeling
+    /// - Space: O(V + E)
+    pub fn k_truss_search(&self, vertex_id: VId, k: u32) -> Option<Vec<VId>> {
+        if vertex_id as usize >= self.vertex_count() {
+            return None;
+        }
+        let graph = {
+            let vertex_index = self.vertex_index.read();
+            self.build_undirected_graph(&vertex_index)
+        };
+        let alive = truss_edges(&graph, k.saturating_sub(2));
+        let edge_alive = |u: VId, v: VId| {
+            graph
+                .slot(u.min(v), u.max(v))
+                .is_some_and(|slot| alive[slot])
+        };
+        if !graph
+            .neighbors(vertex_id)
+            .iter()
+            .any(|&neighbor| edge_alive(vertex_id, neighbor))
+        {
+            return None;
+        }
+
+        Some(connected_members(&graph, vertex_id, edge_alive))
+    }
+}
+
+/// Core numbers by bucket-based peeling in increasing degree order.
+fn core_numbers(graph: &UndirectedGraph) -> Vec<u32> {
+    let vertex_count = graph.vertex_count();
+    let mut degree: Vec<usize> = (0..vertex_count as VId)
+        .map(|vid| graph.degree(vid))
+        .collect();
+    let max_degree = degree.iter().copied().max().unwrap_or(0);
+
+    // Counting sort of the vertices by degree
+    let mut bucket_start = vec![0usize; max_degree + 2];
+    for &d in &degree {
+        bucket_start[d + 1] += 1;
+    }
+    for d in 0..=max_degree {
+        bucket_start[d + 1] += bucket_start[d];
+    }
+    let mut order = vec![0 as VId; vertex_count];
+    let mut position = vec![0usize; vertex_count];
+    let mut cursor = bucket_start.clone();
+    for vid in 0..vertex_count {
+        position[vid] = cursor[degree[vid]];
+        order[position[vid]] = vid as VId;
+        cursor[degree[vid]] += 1;
+    }
+
+    for i in 0..vertex_count {
+        let vid = order[i];
+        for &neighbor in graph.neighbors(vid) {
+            let neighbor = neighbor as usize;
+            if degree[neighbor] > degree[vid as usize] {
+                // Move the neighbor to the front of its bucket, then shrink the bucket
+                let d = degree[neighbor];
+                let front = bucket_start[d];
+                let swapped = order[front] as usize;
+                if swapped != neighbor {
+                    order.swap(front, position[neighbor]);
+                    position[swapped] = position[neighbor];
+                    position[neighbor] = front;
+                }
+                bucket_start[d] += 1;
+                degree[neighbor] -= 1;
+            }
+        }
+    }
+
+    degree.into_iter().map(|d| d as u32).collect()
+}
+
+/// Peel the edges closing fewer than `min_support` triangles until none is left.
+///
+/// Every edge `(u, v)` is tracked at the adjacency slot of `v` in the list of `u`, `u < v`.
+/// Returns the alive flag of every adjacency slot.
+fn truss_edges(graph: &UndirectedGraph, min_support: u32) -> Vec<bool> {
+    let vertex_count = graph.vertex_count();
+    let slot_of = |u: VId, v: VId| graph.slot(u.min(v), u.max(v));
+
+    // Support of every edge: the number of common neighbors of its endpoints
+    let mut alive = vec![false; graph.slot_count()];
+    let mut support = vec![0u32; graph.slot_count()];
+    let mut queue: VecDeque<(VId, VId)> = VecDeque::new();
+    for u in 0..vertex_count as VId {
+        for &v in graph.neighbors(u) {
+            if u < v {
+                let edge = slot_of(u, v).expect("Neighbor must have a slot");
+                alive[edge] = true;
+                support[edge] =
+                    common_neighbors(graph.neighbors(u), graph.neighbors(v)).count() as u32;
+                if support[edge] < min_support {
+                    queue.push_back((u, v));
+                }
+            }
+        }
+    }
+
+    while let Some((u, v)) = queue.pop_front() {
+        let edge = slot_of(u, v).expect("Queued edge must exist");
+        if !alive[edge] {
+            continue;
+        }
+        alive[edge] = false;
+
+        // Every triangle still closed by (u, v) loses this edge
+        for w in common_neighbors(graph.neighbors(u), graph.neighbors(v)) {
+            let (Some(uw), Some(vw)) = (slot_of(u, w), slot_of(v, w)) else {
+                continue;
+            };
+            if !alive[uw] || !alive[vw] {
+                continue;
+            }
+            for (edge, a, b) in [(uw, u, w), (vw, v, w)] {
+                support[edge] -= 1;
+                if support[edge] + 1 == min_support {
+                    queue.push_back((a, b));
+                }
+            }
+        }
+    }
+
+    alive
+}
+
+/// Intersection of two sorted neighbor lists.
+fn common_neighbors<'a>(a: &'a [VId], b: &'a [VId]) -> impl Iterator<Item = VId> + 'a {
+    let (mut i, mut j) = (0, 0);
+    std::iter::from_fn(move || {
+        while i < a.len() && j < b.len() {
+            match a[i].cmp(&b[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    i += 1;
+                    j += 1;
+                    return Some(a[i - 1]);
+                }
+            }
+        }
+        None
+    })
+}
+
+/// Vertices reachable from `start` through the edges accepted by `keep_edge`, sorted.
+fn connected_members(
+    graph: &UndirectedGraph,
+    start: VId,
+    keep_edge: impl Fn(VId, VId) -> bool,
+) -> Vec<VId> {
+    let mut visited = vec![false; graph.vertex_count()];
+    let mut queue = VecDeque::from([start]);
+    visited[start as usize] = true;
+    let mut members = vec![start];
+    while let Some(vid) = queue.pop_front() {
+        for &neighbor in graph.neighbors(vid) {
+            if !visited[neighbor as usize] && keep_edge(vid, neighbor) {
+                visited[neighbor as usize] = true;
+                members.push(neighbor);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    members.sort_unstable();
+    members
+}
@@ -0,0 +1,148 @@
+use crate::{
+    LsmCommunity,
+    types::{Direction, EdgeList, VId},
+};
+
+impl LsmCommunity {
+    /// Collects the vertices within `k` hops of a start vertex.
+    ///
+    /// Every level reads the out neighbors of the whole frontier with batched block reads
+    /// (grouped by community and page), in neighbors come from the in-neighbor lists cached
+    /// until the next edge update.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_vertex` - The vertex ID from which to expand
+    /// * `k` - Maximum number of hops
+    /// * `direction` - Which edges to follow
+    /// * `limit` - Maximum number of returned vertices, `None` for no limit
+    ///
+    /// # Returns
+    ///
+    /// Returns a vector of tuples `(vertex_id, hops)` for every vertex at 1..=k hops,
+    /// excluding the start vertex, ordered by hop count and by vertex ID inside a hop.
+    /// Returns an empty vector if the start vertex is invalid.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(V + E) in the visited part of the graph, plus O(V + E) of the whole
+    ///   graph to build the in-neighbor lists when following in edges after an edge update
+    /// - Space: O(V/64) for the visited bitmap + O(V) for the result
+    pub fn k_hop(
+        &self,
+        start_vertex: VId,
+        k: u32,
+        direction: Direction,
+        limit: Option<usize>,
+    ) -> Vec<(VId, u32)> {
+        self.expire_derived_indexes();
+        let vertex_index = self.vertex_index.read();
+        let vertex_count = vertex_index.vertex_array.len();
+        if start_vertex as usize >= vertex_count || limit == Some(0) {
+            return Vec::new();
+        }
+
+        let in_adjacency = matches!(direction, Direction::In | Direction::Both)
+            .then(|| self.cached_in_adjacency(&vertex_index));
+
+        let mut visited = vec![0u64; vertex_count.div_ceil(64)];
+        let mut test_and_mark = |vid: VId| -> bool {
+            let (idx, mask) = ((vid / 64) as usize, 1u64 << (vid % 64));
+            if idx >= visited.len() || visited[idx] & mask != 0 {
+                return false;
+            }
+            visited[idx] |= mask;
+            true
+        };
+        test_and_mark(start_vertex);
+
+        let mut result = Vec::new();
+        let mut frontier = vec![start_vertex];
+        for hops in 1..=k {
+            if frontier.is_empty() {
+                break;
+            }
+
+            // Gather the candidates of the next level
+            let mut candidates = Vec::new();
+            if matches!(direction, Direction::Out | Direction::Both) {
+                let neighbor_lists =
+                    self.read_out_neighbors_batch_or_each_hold_index(&frontier, &vertex_index);
+                candidates.extend(neighbor_lists.into_iter().flatten());
+            }
+            if let Some(in_adjacency) = &in_adjacency {
+                for &vid in &frontier {
+                    candidates.extend_from_slice(in_adjacency.in_neighbors(vid));
+                }
+            }
+            candidates.sort_unstable();
+            candidates.dedup();
+
+            let mut next_frontier: Vec<VId> = candidates
+                .into_iter()
+                .filter(|&vid| test_and_mark(vid))
+                .collect();
+            if let Some(limit) = limit {
+                next_frontier.truncate(limit - result.len());
+            }
+            result.extend(next_frontier.iter().map(|&vid| (vid, hops)));
+
+            if limit.is_some_and(|limit| result.len() >= limit) {
+                break;
+            }
+            frontier = next_frontier;
+        }
+
+        result
+    }
+
+    /// Extracts the ego network of a vertex.
+    ///
+    /// The ego network contains the start vertex and every vertex within `k` hops of it,
+    /// following edges in both directions, together with all edges among these vertices.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_vertex` - The center of the ego network
+    /// * `k` - Radius of the ego network in hops
+    ///
+    /// # Returns
+    ///
+    /// Returns the induced edge list `(src, dst)` in ascending order. Returns an empty
+    /// list if the start vertex is invalid.
+    pub fn ego_network(&self, start_vertex: VId, k: u32) -> EdgeList {
+        if start_vertex as usize >= self.vertex_count() {
+            return Vec::new();
+        }
+        let mut members: Vec<VId> = self
+            .k_hop(start_vertex, k, Direction::Both, None)
+            .into_iter()
+            .map(|(vid, _)| vid)
+            .collect();
+        members.push(start_vertex);
+        members.sort_unstable();
+
+        let vertex_index = self.vertex_index.read();
+        let mut is_member = vec![0u64; vertex_index.vertex_array.len().div_ceil(64)];
+        for &vid in &members {
+            is_member[(vid / 64) as usize] |= 1u64 << (vid % 64);
+        }
+        let contains = |vid: VId| -> bool {
+            let idx = (vid / 64) as usize;
+            idx < is_member.len() && is_member[idx] & (1u64 << (vid % 64)) != 0
+        };
+
+        let neighbor_lists =
+            self.read_out_neighbors_batch_or_each_hold_index(&members, &vertex_index);
+        members
+            .iter()
+            .zip(neighbor_lists)
+            .flat_map(|(&src, neighbors)| {
+                neighbors
+                    .into_iter()
+                    .filter(|&dst| contains(dst))
+                    .map(move |dst| (src, dst))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,453 @@
+use crate::{
+    LsmCommunity,
+    algorithms::{NEIGHBOR_BATCH_SIZE, undirected::UndirectedGraph},
+    graph::CsrGraph,
+    types::{CommId, VId},
+};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use rayon::prelude::*;
+use rustc_hash::FxHashMap;
+
+/// Smallest modularity gain accepted by a Louvain move, guards against float noise.
+const MIN_GAIN: f64 = 1e-12;
+
+/// Options of [`LsmCommunity::label_propagation`] and [`LsmCommunity::louvain`].
+#[derive(Debug, Clone)]
+pub struct CommunityDetectionOptions {
+    /// Upper bound of the passes over all vertices (per level for Louvain).
+    pub max_iterations: u32,
+    /// Seed of the vertex visiting order and of the tie breaking, equal seeds give equal
+    /// results on the same graph.
+    pub seed: u64,
+}
+
+impl Default for CommunityDetectionOptions {
+    fn default() -> Self {
+        Self {
+            max_iterations: 20,
+            seed: 0,
+        }
+    }
+}
+
+/// Result of a community detection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommunityAssignment {
+    /// `community_map[vertex_id]` is the community of the vertex. Community IDs are dense,
+    /// numbered in the order of their smallest vertex ID.
+    pub community_map: Vec<CommId>,
+    /// Number of communities.
+    pub community_count: usize,
+    /// Modularity of the partition on the undirected graph.
+    pub modularity: f64,
+}
+
+impl CommunityAssignment {
+    fn new(graph: &UndirectedGraph, labels: &[u32]) -> Self {
+        let (community_map, community_count) = dense_labels(labels);
+        let modularity = modularity_of(graph, &community_map);
+        Self {
+            community_map,
+            community_count,
+            modularity,
+        }
+    }
+
+    /// Group the vertices by community, `result[comm_id]` lists the members in ascending order.
+    pub fn community_list(&self) -> Vec<Vec<VId>> {
+        let mut community_list = vec![Vec::new(); self.community_count];
+        for (vid, &comm_id) in self.community_map.iter().enumerate() {
+            community_list[comm_id as usize].push(vid as VId);
+        }
+        community_list
+    }
+}
+
+/// Undirected weighted graph of one Louvain level, every vertex is a community of the
+/// level below.
+struct LevelGraph {
+    offsets: Vec<usize>,
+    targets: Vec<u32>,
+    weights: Vec<f64>,
+    /// Weight of the edges inside every vertex.
+    self_loops: Vec<f64>,
+}
+
+impl LevelGraph {
+    fn from_undirected(graph: &UndirectedGraph) -> Self {
+        let vertex_count = graph.vertex_count();
+        let mut offsets = Vec::with_capacity(vertex_count + 1);
+        let mut targets = Vec::with_capacity(graph.slot_count());
+        offsets.push(0);
+        for vid in 0..vertex_count as VId {
+            targets.extend_from_slice(graph.neighbors(vid));
+            offsets.push(targets.len());
+        }
+        Self {
+            offsets,
+            weights: vec![1.0; targets.len()],
+            targets,
+            self_loops: vec![0.0; vertex_count],
+        }
+    }
+
+    #[inline]
+    fn vertex_count(&self) -> usize {
+        self.self_loops.len()
+    }
+
+    #[inline]
+    fn edges(&self, vid: usize) -> impl Iterator<Item = (u32, f64)> + '_ {
+        let range = self.offsets[vid]..self.offsets[vid + 1];
+        self.targets[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.weights[range].iter().copied())
+    }
+
+    /// Weighted degree, the edges inside the vertex count twice.
+    fn degree(&self, vid: usize) -> f64 {
+        self.edges(vid).map(|(_, weight)| weight).sum::<f64>() + 2.0 * self.self_loops[vid]
+    }
+
+    /// Collapse every community into one vertex.
+    fn aggregate(&self, community: &[u32], community_count: usize) -> Self {
+        let mut self_loops = vec![0.0; community_count];
+        let mut adjacency: Vec<FxHashMap<u32, f64>> = vec![FxHashMap::default(); community_count];
+        for vid in 0..self.vertex_count() {
+            let c = community[vid];
+            self_loops[c as usize] += self.self_loops[vid];
+            for (neighbor, weight) in self.edges(vid) {
+                let d = community[neighbor as usize];
+                if c == d {
+                    // Both directions of the edge are visited
+                    self_loops[c as usize] += weight / 2.0;
+                } else {
+                    *adjacency[c as usize].entry(d).or_default() += weight;
+                }
+            }
+        }
+
+        let mut offsets = Vec::with_capacity(community_count + 1);
+        let mut targets = Vec::new();
+        let mut weights = Vec::new();
+        offsets.push(0);
+        for edges in adjacency {
+            let mut edges: Vec<(u32, f64)> = edges.into_iter().collect();
+            edges.sort_unstable_by_key(|&(target, _)| target);
+            for (target, weight) in edges {
+                targets.push(target);
+                weights.push(weight);
+            }
+            offsets.push(targets.len());
+        }
+        Self {
+            offsets,
+            targets,
+            weights,
+            self_loops,
+        }
+    }
+}
+
+impl LsmCommunity {
+    /// Detects communities by label propagation.
+    ///
+    /// Every vertex starts in its own community and repeatedly adopts the label shared
+    /// by most of its neighbors, visiting the vertices in a seeded random order, until no
+    /// label changes. The graph is treated as undirected with delta logs applied, so edges
+    /// inserted after loading are taken into account.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Pass bound and random seed
+    ///
+    /// # Returns
+    ///
+    /// Returns the community of every vertex and the modularity of the partition.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(V + E) to load the graph + O(V + E) per pass
+    /// - Space: O(V + E) for the undirected adjacency
+    pub fn label_propagation(&self, options: &CommunityDetectionOptions) -> CommunityAssignment {
+        let graph = {
+            let vertex_index = self.vertex_index.read();
+            self.build_undirected_graph(&vertex_index)
+        };
+        let vertex_count = graph.vertex_count();
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        let mut labels: Vec<u32> = (0..vertex_count as u32).collect();
+        let mut order: Vec<VId> = (0..vertex_count as VId).collect();
+        let mut counts = FxHashMap::<u32, u32>::default();
+        let mut candidates = Vec::new();
+
+        for _ in 0..options.max_iterations {
+            order.shuffle(&mut rng);
+            let mut changed = false;
+            for &vid in &order {
+                if graph.degree(vid) == 0 {
+                    continue;
+                }
+                counts.clear();
+                for &neighbor in graph.neighbors(vid) {
+                    *counts.entry(labels[neighbor as usize]).or_default() += 1;
+                }
+                let max_count = counts.values().copied().max().unwrap_or(0);
+                let current = labels[vid as usize];
+                if counts.get(&current) == Some(&max_count) {
+                    continue;
+                }
+                candidates.clear();
+                candidates.extend(
+                    counts
+                        .iter()
+                        .filter(|&(_, &count)| count == max_count)
+                        .map(|(&label, _)| label),
+                );
+                candidates.sort_unstable();
+                labels[vid as usize] = candidates[rng.random_range(0..candidates.len())];
+                changed = true;
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        CommunityAssignment::new(&graph, &labels)
+    }
+
+    /// Detects communities with the Louvain modularity optimization.
+    ///
+    /// Vertices are moved greedily to the neighboring community with the largest
+    /// modularity gain, then every community is collapsed into one vertex and the moves
+    /// are repeated on the coarser graph, until a level merges nothing. The graph is
+    /// treated as undirected with delta logs applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Pass bound per level and random seed
+    ///
+    /// # Returns
+    ///
+    /// Returns the community of every vertex and the modularity of the partition.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(V + E) to load the graph + O(V + E) per pass, the levels shrink quickly
+    /// - Space: O(V + E) for the undirected adjacency and the level graphs
+    pub fn louvain(&self, options: &CommunityDetectionOptions) -> CommunityAssignment {
+        let graph = {
+            let vertex_index = self.vertex_index.read();
+            self.build_undirected_graph(&vertex_index)
+        };
+        let mut rng = StdRng::seed_from_u64(options.seed);
+        let mut membership: Vec<u32> = (0..graph.vertex_count() as u32).collect();
+        let mut level = LevelGraph::from_undirected(&graph);
+
+        loop {
+            let community = move_vertices(&level, options.max_iterations, &mut rng);
+            let (community, community_count) = dense_labels(&community);
+            if community_count == level.vertex_count() {
+                break;
+            }
+            for label in membership.iter_mut() {
+                *label = community[*label as usize];
+            }
+            level = level.aggregate(&community, community_count);
+        }
+
+        CommunityAssignment::new(&graph, &membership)
+    }
+
+    /// Computes the modularity of a partition of the current graph.
+    ///
+    /// The graph is treated as undirected with delta logs applied, ignoring duplicate edges
+    /// and self loops.
+    ///
+    /// # Arguments
+    ///
+    /// * `community_map` - Community ID of every vertex
+    ///
+    /// # Returns
+    ///
+    /// Returns the modularity in `[-0.5, 1]`, 0 for a graph without edges. Returns an error
+    /// if the map does not have one entry per vertex.
+    pub fn modularity(&self, community_map: &[CommId]) -> anyhow::Result<f64> {
+        let graph = {
+            let vertex_index = self.vertex_index.read();
+            self.build_undirected_graph(&vertex_index)
+        };
+        if community_map.len() != graph.vertex_count() {
+            return Err(anyhow::anyhow!(
+                "Community map has {} entries for {} vertices",
+                community_map.len(),
+                graph.vertex_count()
+            ));
+        }
+        Ok(modularity_of(&graph, community_map))
+    }
+
+    /// Replaces the community structure returned by [`LsmCommunity::community_detection`]
+    /// and [`LsmCommunity::community_search`] with a detected one.
+    ///
+    /// The vertex index keeps the new structure and persists it on the next flush. Bucket
+    /// placement is not changed; use [`LsmCommunity::export_graph`] to rebuild the buckets
+    /// around the new communities.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the assignment does not have one entry per vertex, e.g. because
+    /// vertices were added after the detection.
+    pub fn apply_communities(&self, assignment: &CommunityAssignment) -> anyhow::Result<()> {
+        self.check_writable()?;
+        let mut vertex_index = self.vertex_index.write();
+        if assignment.community_map.len() != vertex_index.vertex_array.len() {
+            return Err(anyhow::anyhow!(
+                "Community map has {} entries for {} vertices",
+                assignment.community_map.len(),
+                vertex_index.vertex_array.len()
+            ));
+        }
+        vertex_index.community_map = assignment.community_map.clone();
+        vertex_index.community_list = assignment.community_list();
+        Ok(())
+    }
+
+    /// Exports the current graph, delta logs applied, with a given community map.
+    ///
+    /// Buckets are laid out from the communities of the graph file on the first open, so
+    /// writing the export with [`CsrGraph::write_to_file`] as the graph file of a fresh
+    /// workspace re-lays out the buckets around the detected communities.
+    ///
+    /// # Arguments
+    ///
+    /// * `community_map` - Community ID of every vertex, e.g. from [`LsmCommunity::louvain`]
+    ///
+    /// # Returns
+    ///
+    /// Returns the directed graph with the community map, or an error if the map does not
+    /// have one entry per vertex or a neighbor list cannot be read.
+    pub fn export_graph(&self, community_map: &[CommId]) -> anyhow::Result<CsrGraph> {
+        let vertex_index = self.vertex_index.read();
+        let vertex_count = vertex_index.vertex_array.len();
+        let all_vertices: Vec<VId> = (0..vertex_count as VId).collect();
+        let batches: Vec<Vec<Vec<VId>>> = all_vertices
+            .par_chunks(NEIGHBOR_BATCH_SIZE)
+            .map(|batch| self.read_out_neighbors_batch_hold_index(batch, &vertex_index))
+            .collect::<anyhow::Result<_>>()?;
+        let edge_lists = batches.into_iter().flatten().collect();
+        Ok(CsrGraph::from_edge_lists(
+            edge_lists,
+            community_map.to_vec(),
+        )?)
+    }
+}
+
+/// One Louvain level: move every vertex to the neighboring community of the largest
+/// modularity gain until no vertex moves. Returns the community of every vertex.
+fn move_vertices(level: &LevelGraph, max_iterations: u32, rng: &mut StdRng) -> Vec<u32> {
+    let vertex_count = level.vertex_count();
+    let degree: Vec<f64> = (0..vertex_count).map(|vid| level.degree(vid)).collect();
+    let total_weight: f64 = degree.iter().sum();
+    let mut community: Vec<u32> = (0..vertex_count as u32).collect();
+    if total_weight == 0.0 {
+        return community;
+    }
+
+    // Sum of the degrees of the members of every community
+    let mut community_degree = degree.clone();
+    // Weight from the current vertex to every neighboring community
+    let mut link_weight = vec![0.0; vertex_count];
+    let mut neighbor_communities = Vec::new();
+    let mut order: Vec<usize> = (0..vertex_count).collect();
+
+    for _ in 0..max_iterations {
+        order.shuffle(rng);
+        let mut moved = false;
+        for &vid in &order {
+            let current = community[vid];
+            neighbor_communities.clear();
+            for (neighbor, weight) in level.edges(vid) {
+                let c = community[neighbor as usize];
+                if link_weight[c as usize] == 0.0 {
+                    neighbor_communities.push(c);
+                }
+                link_weight[c as usize] += weight;
+            }
+
+            // Take the vertex out, then put it where the gain is largest
+            community_degree[current as usize] -= degree[vid];
+            let gain = |c: u32, link_weight: &[f64]| {
+                link_weight[c as usize] - community_degree[c as usize] * degree[vid] / total_weight
+            };
+            let mut best = current;
+            let mut best_gain = gain(current, &link_weight);
+            for &c in &neighbor_communities {
+                let candidate_gain = gain(c, &link_weight);
+                if candidate_gain > best_gain + MIN_GAIN {
+                    best = c;
+                    best_gain = candidate_gain;
+                }
+            }
+            community_degree[best as usize] += degree[vid];
+            if best != current {
+                community[vid] = best;
+                moved = true;
+            }
+
+            for &c in &neighbor_communities {
+                link_weight[c as usize] = 0.0;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+    community
+}
+
+/// Renumber the labels densely in the order of their smallest vertex ID.
+fn dense_labels(labels: &[u32]) -> (Vec<CommId>, usize) {
+    let mut dense = FxHashMap::<u32, CommId>::default();
+    let map = labels
+        .iter()
+        .map(|&label| {
+            let next = dense.len() as CommId;
+            *dense.entry(label).or_insert(next)
+        })
+        .collect();
+    (map, dense.len())
+}
+
+/// Modularity of a partition of the undirected graph.
+fn modularity_of(graph: &UndirectedGraph, community_map: &[CommId]) -> f64 {
+    let total_degree = graph.slot_count() as f64;
+    if total_degree == 0.0 {
+        return 0.0;
+    }
+    let community_count = community_map
+        .iter()
+        .map(|&comm_id| comm_id as usize + 1)
+        .max()
+        .unwrap_or(0);
+    // Adjacency slots inside every community and sum of the degrees of its members
+    let mut internal = vec![0.0; community_count];
+    let mut degree_sum = vec![0.0; community_count];
+    for vid in 0..graph.vertex_count() as VId {
+        let comm_id = community_map[vid as usize];
+        degree_sum[comm_id as usize] += graph.degree(vid) as f64;
+        internal[comm_id as usize] += graph
+            .neighbors(vid)
+            .iter()
+            .filter(|&&neighbor| community_map[neighbor as usize] == comm_id)
+            .count() as f64;
+    }
+    internal
+        .iter()
+        .zip(&degree_sum)
+        .map(|(&internal, &degree_sum)| {
+            internal / total_degree - (degree_sum / total_degree).powi(2)
+        })
+        .sum()
+}
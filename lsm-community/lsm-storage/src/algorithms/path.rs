@@ -0,0 +1,373 @@
+use crate::{LsmCommunity, algorithms::bfs::InAdjacency, types::VId, vertex_index::VertexIndex};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{cmp::Ordering, collections::BinaryHeap, sync::Arc};
+
+/// Source of the edge weights used by [`LsmCommunity::shortest_path`].
+#[derive(Clone)]
+pub enum EdgeWeight {
+    /// Every edge costs 1, the shortest path is the path with the fewest hops.
+    Unit,
+    /// Read the weight from the named edge property in the external storage, encoded as a
+    /// little-endian `f64` (see [`LsmCommunity::put_edge_weight`]) or `f32`.
+    Property(String),
+    /// Compute the weight with a caller supplied function, e.g. from a native weight field.
+    Native(Arc<dyn Fn(VId, VId) -> Option<f64> + Send + Sync>),
+}
+
+/// Search strategy of [`LsmCommunity::shortest_path_with_algorithm`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShortestPathAlgorithm {
+    /// Single-source Dijkstra from the source vertex, stopping once the target is settled.
+    #[default]
+    Dijkstra,
+    /// Dijkstra from both ends, the backward search follows the in edges. Needs the
+    /// in-neighbor lists of the whole graph, built again after every edge update.
+    Bidirectional,
+}
+
+/// A weighted shortest path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShortestPath {
+    /// Vertices of the path, from the source to the target (both included).
+    pub path: Vec<VId>,
+    /// Sum of the weights of the edges on the path.
+    pub cost: f64,
+}
+
+/// Entry of the Dijkstra priority queue, ordered as a min-heap on the cost.
+struct HeapEntry {
+    cost: f64,
+    vid: VId,
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.vid.cmp(&self.vid))
+    }
+}
+
+/// State of one Dijkstra search.
+struct DijkstraSearch {
+    dist: FxHashMap<VId, f64>,
+    parent: FxHashMap<VId, VId>,
+    settled: FxHashSet<VId>,
+    heap: BinaryHeap<HeapEntry>,
+}
+
+impl DijkstraSearch {
+    fn new(source: VId) -> Self {
+        let mut dist = FxHashMap::default();
+        dist.insert(source, 0.0);
+        let mut heap = BinaryHeap::new();
+        heap.push(HeapEntry {
+            cost: 0.0,
+            vid: source,
+        });
+        Self {
+            dist,
+            parent: FxHashMap::default(),
+            settled: FxHashSet::default(),
+            heap,
+        }
+    }
+
+    /// Smallest tentative distance still in the queue.
+    fn min_cost(&mut self) -> Option<f64> {
+        while let Some(entry) = self.heap.peek() {
+            if self.settled.contains(&entry.vid) {
+                self.heap.pop();
+            } else {
+                return Some(entry.cost);
+            }
+        }
+        None
+    }
+
+    /// Settle the closest unsettled vertex.
+    fn settle_next(&mut self) -> Option<(VId, f64)> {
+        while let Some(HeapEntry { cost, vid }) = self.heap.pop() {
+            if self.settled.insert(vid) {
+                return Some((vid, cost));
+            }
+        }
+        None
+    }
+
+    /// Lower the tentative distance of `vid` to `cost` if that improves it.
+    fn relax(&mut self, from: VId, vid: VId, cost: f64) {
+        if self.settled.contains(&vid) || self.dist.get(&vid).is_some_and(|&d| d <= cost) {
+            return;
+        }
+        self.dist.insert(vid, cost);
+        self.parent.insert(vid, from);
+        self.heap.push(HeapEntry { cost, vid });
+    }
+
+    /// Walk the parent pointers from `vid` back to the source of the search.
+    fn path_to(&self, mut vid: VId) -> Vec<VId> {
+        let mut path = vec![vid];
+        while let Some(&parent) = self.parent.get(&vid) {
+            path.push(parent);
+            vid = parent;
+        }
+        path
+    }
+}
+
+impl LsmCommunity {
+    /// Finds the minimum-cost path between two vertices with Dijkstra.
+    ///
+    /// Runs [`LsmCommunity::shortest_path_with_algorithm`] with
+    /// [`ShortestPathAlgorithm::Dijkstra`].
+    pub fn shortest_path(
+        &self,
+        src_vertex: VId,
+        dst_vertex: VId,
+        weight: &EdgeWeight,
+    ) -> anyhow::Result<Option<ShortestPath>> {
+        self.shortest_path_with_algorithm(
+            src_vertex,
+            dst_vertex,
+            weight,
+            ShortestPathAlgorithm::Dijkstra,
+        )
+    }
+
+    /// Finds the minimum-cost path between two vertices.
+    ///
+    /// Edges without a weight (missing property, or `None` from a native weight function)
+    /// are not traversed.
+    ///
+    /// # Arguments
+    ///
+    /// * `src_vertex` - The vertex ID where the path starts
+    /// * `dst_vertex` - The vertex ID where the path ends
+    /// * `weight` - Where the edge weights come from
+    /// * `algorithm` - Unidirectional or bidirectional Dijkstra
+    ///
+    /// # Returns
+    ///
+    /// Returns the path and its total cost, or `None` if the target is unreachable.
+    /// Returns an error if a vertex does not exist, or a weight is negative, NaN or malformed.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O((V + E) log V) in the explored part of the graph, plus one property read
+    ///   per relaxed edge for [`EdgeWeight::Property`]. The bidirectional search builds the
+    ///   in-neighbor lists of the graph in O(V + E) first if an edge changed since the last
+    ///   build.
+    /// - Space: O(V) for the distances and parents of the explored vertices
+    pub fn shortest_path_with_algorithm(
+        &self,
+        src_vertex: VId,
+        dst_vertex: VId,
+        weight: &EdgeWeight,
+        algorithm: ShortestPathAlgorithm,
+    ) -> anyhow::Result<Option<ShortestPath>> {
+        if algorithm == ShortestPathAlgorithm::Bidirectional {
+            self.expire_derived_indexes();
+        }
+        let vertex_index = self.vertex_index.read();
+        let vertex_count = vertex_index.vertex_array.len();
+        if src_vertex as usize >= vertex_count || dst_vertex as usize >= vertex_count {
+            return Err(anyhow::anyhow!("Vertex not exists"));
+        }
+        if src_vertex == dst_vertex {
+            return Ok(Some(ShortestPath {
+                path: vec![src_vertex],
+                cost: 0.0,
+            }));
+        }
+
+        match algorithm {
+            ShortestPathAlgorithm::Dijkstra => {
+                self.dijkstra(src_vertex, dst_vertex, weight, &vertex_index)
+            }
+            ShortestPathAlgorithm::Bidirectional => {
+                let in_adjacency = self.cached_in_adjacency(&vertex_index);
+                self.bidirectional_dijkstra(
+                    src_vertex,
+                    dst_vertex,
+                    weight,
+                    &vertex_index,
+                    &in_adjacency,
+                )
+            }
+        }
+    }
+
+    /// Stores the weight of an edge as a named edge property.
+    ///
+    /// The weight is encoded as a little-endian `f64`, the format read by
+    /// [`EdgeWeight::Property`].
+    pub fn put_edge_weight(
+        &self,
+        src_vertex: VId,
+        dst_vertex: VId,
+        property_name: &str,
+        weight: f64,
+    ) -> anyhow::Result<()> {
+        self.check_writable()?;
+        let vertex_count = self.vertex_count();
+        if src_vertex as usize >= vertex_count || dst_vertex as usize >= vertex_count {
+            return Err(anyhow::anyhow!("Vertex not exists"));
+        }
+        self.external_db.put_edge_property(
+            src_vertex,
+            dst_vertex,
+            property_name.to_owned(),
+            &weight.to_le_bytes(),
+        )
+    }
+
+    fn dijkstra(
+        &self,
+        src_vertex: VId,
+        dst_vertex: VId,
+        weight: &EdgeWeight,
+        vertex_index: &VertexIndex,
+    ) -> anyhow::Result<Option<ShortestPath>> {
+        let mut search = DijkstraSearch::new(src_vertex);
+        while let Some((vid, cost)) = search.settle_next() {
+            if vid == dst_vertex {
+                let mut path = search.path_to(vid);
+                path.reverse();
+                return Ok(Some(ShortestPath { path, cost }));
+            }
+            for neighbor_vid in self.read_out_neighbor_iter_hold_index(vid, vertex_index)? {
+                if let Some(edge_cost) = self.edge_weight(weight, vid, neighbor_vid)? {
+                    search.relax(vid, neighbor_vid, cost + edge_cost);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn bidirectional_dijkstra(
+        &self,
+        src_vertex: VId,
+        dst_vertex: VId,
+        weight: &EdgeWeight,
+        vertex_index: &VertexIndex,
+        in_adjacency: &InAdjacency,
+    ) -> anyhow::Result<Option<ShortestPath>> {
+        let mut forward = DijkstraSearch::new(src_vertex);
+        let mut backward = DijkstraSearch::new(dst_vertex);
+        // Cost of the best path found so far and the vertex where both searches met
+        let mut best: Option<(f64, VId)> = None;
+
+        while let (Some(forward_min), Some(backward_min)) =
+            (forward.min_cost(), backward.min_cost())
+        {
+            if best.is_some_and(|(cost, _)| forward_min + backward_min >= cost) {
+                break;
+            }
+
+            if forward_min <= backward_min {
+                let Some((vid, cost)) = forward.settle_next() else {
+                    break;
+                };
+                for neighbor_vid in self.read_out_neighbor_iter_hold_index(vid, vertex_index)? {
+                    let Some(edge_cost) = self.edge_weight(weight, vid, neighbor_vid)? else {
+                        continue;
+                    };
+                    let total = cost + edge_cost;
+                    forward.relax(vid, neighbor_vid, total);
+                    if let Some(&remaining) = backward.dist.get(&neighbor_vid)
+                        && best.is_none_or(|(cost, _)| total + remaining < cost)
+                    {
+                        best = Some((total + remaining, neighbor_vid));
+                    }
+                }
+            } else {
+                let Some((vid, cost)) = backward.settle_next() else {
+                    break;
+                };
+                for &neighbor_vid in in_adjacency.in_neighbors(vid) {
+                    let Some(edge_cost) = self.edge_weight(weight, neighbor_vid, vid)? else {
+                        continue;
+                    };
+                    let total = cost + edge_cost;
+                    backward.relax(vid, neighbor_vid, total);
+                    if let Some(&remaining) = forward.dist.get(&neighbor_vid)
+                        && best.is_none_or(|(cost, _)| total + remaining < cost)
+                    {
+                        best = Some((total + remaining, neighbor_vid));
+                    }
+                }
+            }
+        }
+
+        Ok(best.map(|(cost, meeting_vid)| {
+            let mut path = forward.path_to(meeting_vid);
+            path.reverse();
+            path.extend(backward.path_to(meeting_vid).into_iter().skip(1));
+            ShortestPath { path, cost }
+        }))
+    }
+
+    /// Weight of the edge `(src_vertex, dst_vertex)`, `None` if the edge has no weight.
+    pub(crate) fn edge_weight(
+        &self,
+        weight: &EdgeWeight,
+        src_vertex: VId,
+        dst_vertex: VId,
+    ) -> anyhow::Result<Option<f64>> {
+        let value = match weight {
+            EdgeWeight::Unit => Some(1.0),
+            EdgeWeight::Property(property_name) => {
+                match self.external_db.get_edge_property(
+                    src_vertex,
+                    dst_vertex,
+                    property_name.clone(),
+                )? {
+                    Some(bytes) => Some(decode_weight(&bytes).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Malformed weight on edge ({}, {}): {} bytes",
+                            src_vertex,
+                            dst_vertex,
+                            bytes.len()
+                        )
+                    })?),
+                    None => None,
+                }
+            }
+            EdgeWeight::Native(weight_fn) => weight_fn(src_vertex, dst_vertex),
+        };
+        match value {
+            Some(w) if w.is_nan() || w < 0.0 => Err(anyhow::anyhow!(
+                "Invalid weight {} on edge ({}, {})",
+                w,
+                src_vertex,
+                dst_vertex
+            )),
+            _ => Ok(value),
+        }
+    }
+}
+
+/// Decode a little-endian `f64` or `f32` weight.
+fn decode_weight(bytes: &[u8]) -> Option<f64> {
+    match bytes.len() {
+        8 => Some(f64::from_le_bytes(bytes.try_into().ok()?)),
+        4 => Some(f32::from_le_bytes(bytes.try_into().ok()?) as f64),
+        _ => None,
+    }
+}
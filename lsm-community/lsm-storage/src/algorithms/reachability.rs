@@ -0,0 +1,506 @@
+use crate::{
+    LsmCommunity,
+    algorithms::{NEIGHBOR_BATCH_SIZE, scc::tarjan_scc},
+    types::VId,
+    utils::generate_timestamp_micros,
+    vertex_index::{VertexIndex, write_file_atomically},
+};
+use rayon::prelude::*;
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+/// Number of interval labelings, each one from a different traversal order of the DAG.
+const LABELING_COUNT: usize = 2;
+
+/// File name of the saved index inside the graph workspace.
+const REACHABILITY_INDEX_FILE: &str = "reachability_index.bin";
+
+/// Reachability index over the condensation DAG of the strongly connected components.
+///
+/// Components are numbered in the order of [`tarjan_scc`], so every DAG edge goes from the
+/// higher to the lower component ID. Queries are answered from the labels when possible:
+/// topological levels and interval containment rule pairs out, the DFS tree intervals of
+/// the first labeling prove pairs in. Only the remaining pairs search the DAG, pruned by
+/// the same labels.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ReachabilityIndex {
+    /// Sequence number of the external storage the index was built at, a saved index is
+    /// loaded only while it has not moved on.
+    sequence: u64,
+    /// Time the graph was read at, in microseconds since the Unix epoch. Edges expiring
+    /// later drop a loaded index.
+    built_at: u64,
+    /// Earliest expiry of an edge read into the index, `u64::MAX` if none. A saved index is
+    /// loaded only before, compaction may drop the expired edge from the delta log.
+    next_expiry: u64,
+    /// Component of every vertex.
+    component: Vec<u32>,
+    /// Longest path from a source component to every component.
+    level: Vec<u32>,
+    /// CSR offsets of the DAG successors of every component.
+    offsets: Vec<usize>,
+    /// DAG successors, sorted inside each list.
+    targets: Vec<u32>,
+    /// Number of graph edges behind every DAG edge.
+    support: Vec<u32>,
+    /// DFS preorder of every component in the first labeling.
+    pre: Vec<u32>,
+    /// DFS postorder of every component, per labeling.
+    post: Vec<Vec<u32>>,
+    /// Smallest postorder among the components reachable from every component, per labeling.
+    low: Vec<Vec<u32>>,
+}
+
+/// Effect of an edge update on the reachability index.
+pub(crate) enum ReachabilityUpdate {
+    /// The update leaves reachability and the index untouched.
+    Unchanged,
+    /// The update adds or removes one of several graph edges behind a DAG edge.
+    Support { slot: usize, inserted: bool },
+    /// The update may change reachability, the index is rebuilt on the next query.
+    Invalidate,
+}
+
+impl ReachabilityIndex {
+    /// Build the index from the out-neighbor lists of all vertices, read at `sequence` from
+    /// the time `built_at`, with an edge expiring at `next_expiry` first.
+    fn build(all_neighbors: &[Vec<VId>], sequence: u64, built_at: u64, next_expiry: u64) -> Self {
+        let component = tarjan_scc(all_neighbors);
+        let component_count = component.iter().map(|&c| c as usize + 1).max().unwrap_or(0);
+
+        // DAG edges with the number of graph edges behind them
+        let mut dag_edges: Vec<(u32, u32)> = all_neighbors
+            .par_iter()
+            .enumerate()
+            .flat_map_iter(|(vid, neighbors)| {
+                let component = &component;
+                neighbors
+                    .iter()
+                    .filter(move |&&neighbor| (neighbor as usize) < component.len())
+                    .map(move |&neighbor| (component[vid], component[neighbor as usize]))
+                    .filter(|&(from, to)| from != to)
+            })
+            .collect();
+        dag_edges.par_sort_unstable();
+        let mut offsets = vec![0usize; component_count + 1];
+        let mut targets = Vec::new();
+        let mut support: Vec<u32> = Vec::new();
+        let mut last = None;
+        for (from, to) in dag_edges {
+            if last == Some((from, to)) {
+                *support.last_mut().unwrap() += 1;
+            } else {
+                offsets[from as usize + 1] += 1;
+                targets.push(to);
+                support.push(1);
+                last = Some((from, to));
+            }
+        }
+        for c in 0..component_count {
+            offsets[c + 1] += offsets[c];
+        }
+
+        let mut index = Self {
+            sequence,
+            built_at,
+            next_expiry,
+            component,
+            level: vec![0; component_count],
+            offsets,
+            targets,
+            support,
+            pre: vec![0; component_count],
+            post: Vec::with_capacity(LABELING_COUNT),
+            low: Vec::with_capacity(LABELING_COUNT),
+        };
+
+        // Successors have lower IDs, so the levels flow from the highest ID downwards
+        for c in (0..component_count).rev() {
+            let next_level = index.level[c] + 1;
+            for i in index.offsets[c]..index.offsets[c + 1] {
+                let target = index.targets[i] as usize;
+                index.level[target] = index.level[target].max(next_level);
+            }
+        }
+
+        for labeling in 0..LABELING_COUNT {
+            let (pre, post) = index.traverse(labeling % 2 == 1);
+            // Every successor has a lower ID and thus its final `low` already
+            let mut low = post.clone();
+            for c in 0..component_count {
+                for &target in index.successors(c as u32) {
+                    low[c] = low[c].min(low[target as usize]);
+                }
+            }
+            if labeling == 0 {
+                index.pre = pre;
+            }
+            index.post.push(post);
+            index.low.push(low);
+        }
+        index
+    }
+
+    /// Iterative DFS over the DAG returning the pre- and postorder of every component.
+    /// `reversed` visits the roots and the successors in the opposite order.
+    fn traverse(&self, reversed: bool) -> (Vec<u32>, Vec<u32>) {
+        let component_count = self.level.len();
+        let mut pre = vec![0u32; component_count];
+        let mut post = vec![0u32; component_count];
+        let mut visited = vec![false; component_count];
+        let (mut pre_counter, mut post_counter) = (0u32, 0u32);
+        let mut stack: Vec<(u32, usize)> = Vec::new();
+
+        let roots: Box<dyn Iterator<Item = usize>> = if reversed {
+            Box::new(0..component_count)
+        } else {
+            Box::new((0..component_count).rev())
+        };
+        for root in roots {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            pre[root] = pre_counter;
+            pre_counter += 1;
+            stack.push((root as u32, 0));
+            while let Some((c, next)) = stack.last_mut() {
+                let successors = self.successors(*c);
+                if *next < successors.len() {
+                    let child = if reversed {
+                        successors[successors.len() - 1 - *next]
+                    } else {
+                        successors[*next]
+                    };
+                    *next += 1;
+                    if !visited[child as usize] {
+                        visited[child as usize] = true;
+                        pre[child as usize] = pre_counter;
+                        pre_counter += 1;
+                        stack.push((child, 0));
+                    }
+                } else {
+                    post[*c as usize] = post_counter;
+                    post_counter += 1;
+                    stack.pop();
+                }
+            }
+        }
+        (pre, post)
+    }
+
+    #[inline]
+    fn successors(&self, c: u32) -> &[u32] {
+        &self.targets[self.offsets[c as usize]..self.offsets[c as usize + 1]]
+    }
+
+    /// Slot of the DAG edge `(from, to)`, `None` if the DAG has no such edge.
+    fn dag_slot(&self, from: u32, to: u32) -> Option<usize> {
+        self.successors(from)
+            .binary_search(&to)
+            .ok()
+            .map(|i| self.offsets[from as usize] + i)
+    }
+
+    /// `false` proves that `to` is unreachable from `from`.
+    #[inline]
+    fn may_reach(&self, from: u32, to: u32) -> bool {
+        let (from, to) = (from as usize, to as usize);
+        self.level[from] < self.level[to]
+            && (0..LABELING_COUNT).all(|labeling| {
+                self.low[labeling][from] <= self.low[labeling][to]
+                    && self.post[labeling][to] <= self.post[labeling][from]
+            })
+    }
+
+    /// `true` proves that `to` is reachable from `from`, through the first DFS tree.
+    #[inline]
+    fn tree_reaches(&self, from: u32, to: u32) -> bool {
+        let (from, to) = (from as usize, to as usize);
+        self.pre[from] <= self.pre[to] && self.post[0][to] <= self.post[0][from]
+    }
+
+    /// Whether the component `to` is reachable from the component `from`.
+    fn reaches(&self, from: u32, to: u32) -> bool {
+        if from == to || self.tree_reaches(from, to) {
+            return true;
+        }
+        if !self.may_reach(from, to) {
+            return false;
+        }
+        let mut visited = FxHashSet::default();
+        let mut stack = vec![from];
+        while let Some(c) = stack.pop() {
+            for &next in self.successors(c) {
+                if next == to || self.tree_reaches(next, to) {
+                    return true;
+                }
+                if self.may_reach(next, to) && visited.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        false
+    }
+
+    /// Whether `dst_vertex` is reachable from `src_vertex`. Vertices inserted after the
+    /// build have no edges yet and reach only themselves.
+    fn is_reachable(&self, src_vertex: VId, dst_vertex: VId) -> bool {
+        if src_vertex == dst_vertex {
+            return true;
+        }
+        match (
+            self.component.get(src_vertex as usize),
+            self.component.get(dst_vertex as usize),
+        ) {
+            (Some(&from), Some(&to)) => self.reaches(from, to),
+            _ => false,
+        }
+    }
+
+    /// Save the index next to `path` and rename it into place, so that a crash never
+    /// leaves a truncated index behind.
+    fn save(&self, path: &Path) -> std::io::Result<()> {
+        write_file_atomically(path, |writer| {
+            bincode::serialize_into(writer, self).map_err(std::io::Error::other)
+        })
+    }
+
+    /// Load a saved index, `None` if there is none, it cannot be read, it was built at
+    /// another sequence number than `sequence` or an edge it holds expired since.
+    pub(crate) fn load(path: &Path, sequence: u64) -> Option<Self> {
+        let file = File::open(path).ok()?;
+        let buf_reader = BufReader::with_capacity(8 * 1024 * 1024, file);
+        let index: Self = bincode::deserialize_from(buf_reader).ok()?;
+        (index.sequence == sequence && index.next_expiry > generate_timestamp_micros())
+            .then_some(index)
+    }
+
+    /// Time the graph was read at to build the index, in microseconds since the Unix epoch.
+    pub(crate) fn built_at(&self) -> u64 {
+        self.built_at
+    }
+
+    /// Path of the saved index of a graph.
+    pub(crate) fn path(work_space_dir: &str, graph_name: &str) -> PathBuf {
+        PathBuf::from(work_space_dir)
+            .join(graph_name)
+            .join(REACHABILITY_INDEX_FILE)
+    }
+}
+
+impl LsmCommunity {
+    /// Checks whether a vertex can reach another one along the out edges.
+    ///
+    /// With the `reachability_index` option the query is answered from the reachability
+    /// index over the condensation DAG of the strongly connected components, built on the
+    /// first query (or loaded with the graph) and saved in the graph workspace. Edge updates
+    /// patch the index when they cannot change reachability, e.g. an inserted edge between
+    /// vertices already connected, and drop it otherwise; the next query then rebuilds it.
+    /// Without the option every query searches the graph from `src_vertex`.
+    ///
+    /// # Arguments
+    ///
+    /// * `src_vertex` - The vertex where the path starts
+    /// * `dst_vertex` - The vertex where the path ends
+    ///
+    /// # Returns
+    ///
+    /// Returns whether a directed path exists, a vertex reaches itself. Returns an error if
+    /// a vertex does not exist.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(V + E) to build the index, most queries are O(1) label comparisons and
+    ///   the others search only the pruned part of the DAG. O(V + E) in the part of the
+    ///   graph reachable from `src_vertex` without the index.
+    /// - Space: O(V + E_dag) for the index, O(V) without it
+    pub fn is_reachable(&self, src_vertex: VId, dst_vertex: VId) -> anyhow::Result<bool> {
+        let vertex_count = self.vertex_count();
+        if src_vertex as usize >= vertex_count || dst_vertex as usize >= vertex_count {
+            return Err(anyhow::anyhow!("Vertex not exists"));
+        }
+        if !self.inner.options.reachability_index {
+            let vertex_index = self.vertex_index.read();
+            return self.search_reachable(src_vertex, dst_vertex, &vertex_index);
+        }
+        self.expire_derived_indexes();
+        let mut state = self.reachability_index.lock();
+        if state.is_none() {
+            *state = Some(self.build_reachability_index()?);
+        }
+        Ok(state
+            .as_ref()
+            .is_some_and(|index| index.is_reachable(src_vertex, dst_vertex)))
+    }
+
+    /// Rebuilds the reachability index used by [`LsmCommunity::is_reachable`] now, instead
+    /// of on the next query.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the `reachability_index` option is not set.
+    pub fn rebuild_reachability_index(&self) -> anyhow::Result<()> {
+        if !self.inner.options.reachability_index {
+            return Err(anyhow::anyhow!(
+                "Graph {} is opened without the reachability_index option",
+                self.inner.options.graph_name
+            ));
+        }
+        let mut state = self.reachability_index.lock();
+        *state = Some(self.build_reachability_index()?);
+        Ok(())
+    }
+
+    /// Depth-first search for `dst_vertex` along the out edges of `src_vertex`.
+    fn search_reachable(
+        &self,
+        src_vertex: VId,
+        dst_vertex: VId,
+        vertex_index: &VertexIndex,
+    ) -> anyhow::Result<bool> {
+        let vertex_count = vertex_index.vertex_array.len();
+        let mut visited = vec![false; vertex_count];
+        visited[src_vertex as usize] = true;
+        let mut stack = vec![src_vertex];
+        while let Some(vid) = stack.pop() {
+            if vid == dst_vertex {
+                return Ok(true);
+            }
+            for neighbor in self.read_out_neighbor_iter_hold_index(vid, vertex_index)? {
+                if (neighbor as usize) < vertex_count && !visited[neighbor as usize] {
+                    visited[neighbor as usize] = true;
+                    stack.push(neighbor);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Build the index from the current graph and save it, unless opened read-only.
+    ///
+    /// Called with the index locked, so no edge update runs until it is built.
+    fn build_reachability_index(&self) -> anyhow::Result<ReachabilityIndex> {
+        let sequence = self.external_db.latest_sequence_number();
+        let built_at = generate_timestamp_micros();
+        let next_expiry = self
+            .edge_expiries
+            .lock()
+            .peek()
+            .map_or(u64::MAX, |Reverse((expires_at, _, _))| *expires_at);
+        let index = {
+            let vertex_index = self.vertex_index.read();
+            let all_neighbors = self.read_all_out_neighbors(&vertex_index)?;
+            ReachabilityIndex::build(&all_neighbors, sequence, built_at, next_expiry)
+        };
+        if !self.is_read_only() {
+            index.save(&self.reachability_index_path())?;
+        }
+        Ok(index)
+    }
+
+    /// Out-neighbor lists of all vertices in vertex ID order, sorted, without duplicates and
+    /// without neighbors outside the graph, read in storage order.
+    pub(crate) fn read_all_out_neighbors(
+        &self,
+        vertex_index: &VertexIndex,
+    ) -> anyhow::Result<Vec<Vec<VId>>> {
+        let order = vertex_index.storage_order();
+        let batches: Vec<Vec<Vec<VId>>> = order
+            .par_chunks(NEIGHBOR_BATCH_SIZE)
+            .map(|batch| self.read_out_neighbors_batch_hold_index(batch, vertex_index))
+            .collect::<anyhow::Result<_>>()?;
+        let mut all_neighbors = vec![Vec::new(); order.len()];
+        for (&vid, mut neighbors) in order.iter().zip(batches.into_iter().flatten()) {
+            // Edges form a set, the support counts must count every edge once
+            neighbors.retain(|&neighbor| (neighbor as usize) < order.len());
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            all_neighbors[vid as usize] = neighbors;
+        }
+        Ok(all_neighbors)
+    }
+
+    fn reachability_index_path(&self) -> PathBuf {
+        ReachabilityIndex::path(
+            &self.inner.options.work_space_dir,
+            &self.inner.options.graph_name,
+        )
+    }
+
+    /// Work out how an edge update, not yet logged, affects the reachability index.
+    pub(crate) fn prepare_reachability_update(
+        &self,
+        state: &Option<ReachabilityIndex>,
+        src_vertex: VId,
+        dst_vertex: VId,
+        inserted: bool,
+    ) -> anyhow::Result<ReachabilityUpdate> {
+        let Some(index) = state else {
+            return Ok(ReachabilityUpdate::Unchanged);
+        };
+        if src_vertex == dst_vertex {
+            return Ok(ReachabilityUpdate::Unchanged);
+        }
+        let (Some(&from), Some(&to)) = (
+            index.component.get(src_vertex as usize),
+            index.component.get(dst_vertex as usize),
+        ) else {
+            return Ok(ReachabilityUpdate::Invalidate);
+        };
+        if from == to && inserted {
+            return Ok(ReachabilityUpdate::Unchanged);
+        }
+
+        // Edges form a set, updates that do not change it do not change the index
+        let exists = {
+            let vertex_index = self.vertex_index.read();
+            self.read_out_neighbor_iter_hold_index(src_vertex, &vertex_index)?
+                .any(|neighbor| neighbor == dst_vertex)
+        };
+        if exists == inserted {
+            return Ok(ReachabilityUpdate::Unchanged);
+        }
+
+        let update = match (inserted, index.dag_slot(from, to)) {
+            // A removal inside a component may split it
+            (false, _) if from == to => ReachabilityUpdate::Invalidate,
+            (true, _) if !index.reaches(from, to) => ReachabilityUpdate::Invalidate,
+            (false, Some(slot)) if index.support[slot] == 1 => ReachabilityUpdate::Invalidate,
+            (inserted, Some(slot)) => ReachabilityUpdate::Support { slot, inserted },
+            // A shortcut between components already connected through the DAG
+            (_, None) => ReachabilityUpdate::Unchanged,
+        };
+        Ok(update)
+    }
+
+    /// Apply the effect of a logged edge update to the reachability index.
+    ///
+    /// The saved copy is left as it is, it no longer matches the sequence number of the
+    /// storage and is not loaded again.
+    pub(crate) fn apply_reachability_update(
+        &self,
+        state: &mut Option<ReachabilityIndex>,
+        update: ReachabilityUpdate,
+    ) {
+        match update {
+            ReachabilityUpdate::Unchanged => {}
+            ReachabilityUpdate::Support { slot, inserted } => {
+                if let Some(index) = state.as_mut() {
+                    if inserted {
+                        index.support[slot] += 1;
+                    } else {
+                        index.support[slot] -= 1;
+                    }
+                }
+            }
+            ReachabilityUpdate::Invalidate => *state = None,
+        }
+    }
+}
@@ -0,0 +1,455 @@
+use crate::{
+    LsmCommunity,
+    algorithms::{NEIGHBOR_BATCH_SIZE, build_thread_pool, path::EdgeWeight},
+    types::VId,
+    vertex_index::VertexIndex,
+};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::index};
+use rayon::prelude::*;
+use rustc_hash::FxHashMap;
+use std::collections::hash_map::Entry;
+
+/// Number of walks sharing one neighbor memo.
+const WALK_BATCH_SIZE: usize = 1024;
+
+/// Spreads consecutive walk and vertex indices over the seed space.
+const SEED_MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Options of [`LsmCommunity::random_walks`].
+#[derive(Clone)]
+pub struct RandomWalkOptions {
+    /// Number of vertices of every walk, the start vertex included. Walks stop early at
+    /// vertices without out edges.
+    pub walk_length: usize,
+    /// Number of walks started from every seed vertex.
+    pub walks_per_vertex: usize,
+    /// Transition weights of the out edges, edges without a weight are not followed.
+    pub weight: EdgeWeight,
+    /// node2vec return parameter, the weight of going back to the previous vertex is
+    /// divided by `p`.
+    pub p: f64,
+    /// node2vec in-out parameter, the weight of moving away from the previous vertex is
+    /// divided by `q`. `p = q = 1` gives a first-order walk.
+    pub q: f64,
+    /// Random seed, equal seeds give equal walks on the same graph.
+    pub seed: u64,
+    /// Number of worker threads, `None` runs on the global rayon pool.
+    pub num_threads: Option<usize>,
+}
+
+impl Default for RandomWalkOptions {
+    fn default() -> Self {
+        Self {
+            walk_length: 80,
+            walks_per_vertex: 1,
+            weight: EdgeWeight::Unit,
+            p: 1.0,
+            q: 1.0,
+            seed: 0,
+            num_threads: None,
+        }
+    }
+}
+
+/// Walks stored back to back in one array.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RandomWalks {
+    /// `vertices[offsets[i]..offsets[i + 1]]` is the walk `i`.
+    pub offsets: Vec<usize>,
+    /// Vertices of all walks.
+    pub vertices: Vec<VId>,
+}
+
+impl RandomWalks {
+    /// Number of walks.
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Vertices of the walk `i`.
+    pub fn walk(&self, i: usize) -> &[VId] {
+        &self.vertices[self.offsets[i]..self.offsets[i + 1]]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &[VId]> + '_ {
+        (0..self.len()).map(|i| self.walk(i))
+    }
+}
+
+/// Options of [`LsmCommunity::sample_neighbors`].
+#[derive(Debug, Clone)]
+pub struct NeighborSamplingOptions {
+    /// Number of out neighbors sampled per vertex at every hop, `usize::MAX` takes all of
+    /// them. The length is the number of hops.
+    pub fanouts: Vec<usize>,
+    /// Sample with replacement, a neighbor may then be drawn several times.
+    pub replace: bool,
+    /// Random seed, equal seeds give equal samples on the same graph.
+    pub seed: u64,
+    /// Number of worker threads, `None` runs on the global rayon pool.
+    pub num_threads: Option<usize>,
+}
+
+impl Default for NeighborSamplingOptions {
+    fn default() -> Self {
+        Self {
+            fanouts: vec![10, 10],
+            replace: false,
+            seed: 0,
+            num_threads: None,
+        }
+    }
+}
+
+/// Edges sampled at one hop, as indices into [`NeighborSample::nodes`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SampledLayer {
+    /// `(targets[i], neighbors[i])` is a sampled out edge of a vertex of the previous hop.
+    pub targets: Vec<u32>,
+    pub neighbors: Vec<u32>,
+}
+
+/// Layered neighborhood sampled around a set of seed vertices.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NeighborSample {
+    /// Sampled vertices without duplicates: the seeds first, then the vertices in the order
+    /// they were first reached.
+    pub nodes: Vec<VId>,
+    /// `nodes[hop_offsets[h]..hop_offsets[h + 1]]` are the vertices first reached at hop `h`,
+    /// hop 0 being the seeds.
+    pub hop_offsets: Vec<usize>,
+    /// Sampled edges of every hop.
+    pub layers: Vec<SampledLayer>,
+}
+
+/// Transition candidates of a vertex, memoized for the walks of one batch.
+struct WalkNeighbors {
+    neighbors: Vec<VId>,
+    /// Weight of every neighbor, empty for unit weights.
+    weights: Vec<f64>,
+    /// Neighbors in ascending order, for the node2vec distance test.
+    sorted: Vec<VId>,
+}
+
+impl LsmCommunity {
+    /// Generates random walks over the out edges, for node embeddings.
+    ///
+    /// Every walk moves to an out neighbor chosen with a probability proportional to the
+    /// edge weight, biased by the node2vec `p` and `q` parameters relative to the previous
+    /// vertex. Walks run in parallel in batches of seeds sorted by storage position, so the
+    /// walks of one batch mostly start, and stay, in one community: its blocks stay hot in
+    /// the block cache and every neighbor list is read once per batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `seeds` - Start vertices
+    /// * `options` - Walk length and count, weights, node2vec biases, seed and threads
+    ///
+    /// # Returns
+    ///
+    /// Returns `walks_per_vertex` rounds of walks, each round one walk per seed in input
+    /// order. Returns an error if a seed does not exist, `p` or `q` is not positive, or a
+    /// weight cannot be read.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(S * L * d) for S walks of length L over vertices of out-degree d
+    /// - Space: O(S * L) for the walks + the neighbor lists visited by one batch
+    pub fn random_walks(
+        &self,
+        seeds: &[VId],
+        options: &RandomWalkOptions,
+    ) -> anyhow::Result<RandomWalks> {
+        if !(options.p > 0.0 && options.q > 0.0) {
+            return Err(anyhow::anyhow!(
+                "node2vec parameters must be positive: p = {}, q = {}",
+                options.p,
+                options.q
+            ));
+        }
+        let vertex_index = self.vertex_index.read();
+        check_seeds(seeds, &vertex_index)?;
+
+        // Walk indices grouped by the storage position of their start vertex
+        let walk_count = seeds.len() * options.walks_per_vertex;
+        let mut walk_order: Vec<usize> = (0..walk_count).collect();
+        walk_order.sort_by_key(|&walk| vertex_index.storage_key(seeds[walk % seeds.len()]));
+
+        let run = || {
+            walk_order
+                .par_chunks(WALK_BATCH_SIZE)
+                .map(|batch| {
+                    let mut memo = FxHashMap::<VId, WalkNeighbors>::default();
+                    batch
+                        .iter()
+                        .map(|&walk| {
+                            let start = seeds[walk % seeds.len()];
+                            let mut rng = StdRng::seed_from_u64(
+                                options.seed ^ (walk as u64).wrapping_mul(SEED_MIX),
+                            );
+                            let path =
+                                self.walk_from(start, options, &mut rng, &mut memo, &vertex_index)?;
+                            Ok((walk, path))
+                        })
+                        .collect::<anyhow::Result<Vec<_>>>()
+                })
+                .collect::<anyhow::Result<Vec<_>>>()
+        };
+        let batches = match build_thread_pool(options.num_threads)? {
+            Some(pool) => pool.install(run),
+            None => run(),
+        }?;
+
+        let mut paths = vec![Vec::new(); walk_count];
+        for (walk, path) in batches.into_iter().flatten() {
+            paths[walk] = path;
+        }
+        let mut walks = RandomWalks {
+            offsets: Vec::with_capacity(walk_count + 1),
+            vertices: Vec::with_capacity(walk_count * options.walk_length),
+        };
+        walks.offsets.push(0);
+        for path in paths {
+            walks.vertices.extend(path);
+            walks.offsets.push(walks.vertices.len());
+        }
+        Ok(walks)
+    }
+
+    /// Samples a layered neighborhood around seed vertices, for GNN mini-batches.
+    ///
+    /// At hop `h`, up to `fanouts[h]` out neighbors are drawn uniformly for every vertex
+    /// first reached at the previous hop. The vertices of a hop are read in parallel with
+    /// batched block reads in storage order, so the vertices of one community share their
+    /// block loads.
+    ///
+    /// # Arguments
+    ///
+    /// * `seeds` - Target vertices of the mini-batch, duplicates are ignored
+    /// * `options` - Fan-out per hop, replacement, seed and threads
+    ///
+    /// # Returns
+    ///
+    /// Returns the sampled vertices and, for every hop, the sampled edges as local
+    /// indices. Returns an error if a seed does not exist.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(N * d) for the N sampled vertices of out-degree d
+    /// - Space: O(N) for the sample
+    pub fn sample_neighbors(
+        &self,
+        seeds: &[VId],
+        options: &NeighborSamplingOptions,
+    ) -> anyhow::Result<NeighborSample> {
+        let vertex_index = self.vertex_index.read();
+        check_seeds(seeds, &vertex_index)?;
+
+        let mut sample = NeighborSample::default();
+        let mut local = FxHashMap::<VId, u32>::default();
+        for &seed in seeds {
+            if let Entry::Vacant(entry) = local.entry(seed) {
+                entry.insert(sample.nodes.len() as u32);
+                sample.nodes.push(seed);
+            }
+        }
+        sample.hop_offsets = vec![0, sample.nodes.len()];
+
+        let run = |frontier: &[VId], hop: usize, fanout: usize| {
+            let mut order: Vec<VId> = frontier.to_vec();
+            order.sort_by_key(|&vid| vertex_index.storage_key(vid));
+            order
+                .par_chunks(NEIGHBOR_BATCH_SIZE)
+                .map(|batch| {
+                    let neighbor_lists =
+                        self.read_out_neighbors_batch_hold_index(batch, &vertex_index)?;
+                    Ok(batch
+                        .iter()
+                        .zip(neighbor_lists)
+                        .map(|(&vid, neighbors)| {
+                            let mut rng = StdRng::seed_from_u64(
+                                options.seed
+                                    ^ (hop as u64).wrapping_mul(SEED_MIX).rotate_left(32)
+                                    ^ (vid as u64).wrapping_mul(SEED_MIX),
+                            );
+                            (vid, draw(&neighbors, fanout, options.replace, &mut rng))
+                        })
+                        .collect::<Vec<_>>())
+                })
+                .collect::<anyhow::Result<Vec<_>>>()
+        };
+        let pool = build_thread_pool(options.num_threads)?;
+
+        for (hop, &fanout) in options.fanouts.iter().enumerate() {
+            let frontier = &sample.nodes[sample.hop_offsets[hop]..sample.hop_offsets[hop + 1]];
+            let drawn = match &pool {
+                Some(pool) => pool.install(|| run(frontier, hop, fanout)),
+                None => run(frontier, hop, fanout),
+            }?;
+            let mut drawn: FxHashMap<VId, Vec<VId>> = drawn.into_iter().flatten().collect();
+
+            // Assign local indices in frontier order, so the result does not depend on threads
+            let mut layer = SampledLayer::default();
+            for i in sample.hop_offsets[hop]..sample.hop_offsets[hop + 1] {
+                let target = sample.nodes[i];
+                for neighbor in drawn.remove(&target).unwrap_or_default() {
+                    let next = sample.nodes.len() as u32;
+                    let neighbor_local = *local.entry(neighbor).or_insert(next);
+                    if neighbor_local == next {
+                        sample.nodes.push(neighbor);
+                    }
+                    layer.targets.push(i as u32);
+                    layer.neighbors.push(neighbor_local);
+                }
+            }
+            sample.layers.push(layer);
+            sample.hop_offsets.push(sample.nodes.len());
+        }
+        Ok(sample)
+    }
+
+    /// One walk from `start`.
+    fn walk_from(
+        &self,
+        start: VId,
+        options: &RandomWalkOptions,
+        rng: &mut StdRng,
+        memo: &mut FxHashMap<VId, WalkNeighbors>,
+        vertex_index: &VertexIndex,
+    ) -> anyhow::Result<Vec<VId>> {
+        let biased = options.p != 1.0 || options.q != 1.0;
+        let mut path = Vec::with_capacity(options.walk_length);
+        if options.walk_length == 0 {
+            return Ok(path);
+        }
+        path.push(start);
+        let mut previous: Option<VId> = None;
+        let mut current = start;
+
+        while path.len() < options.walk_length {
+            if let Entry::Vacant(entry) = memo.entry(current) {
+                entry.insert(self.walk_neighbors(current, options, biased, vertex_index)?);
+            }
+            let candidates = &memo[&current];
+            if candidates.neighbors.is_empty() {
+                break;
+            }
+
+            let next = match previous {
+                // Second-order step, the previous vertex is in the memo already
+                Some(previous) if biased => {
+                    let previous_neighbors = &memo[&previous].sorted;
+                    let bias = |i: usize| {
+                        let neighbor = candidates.neighbors[i];
+                        let weight = candidates.weights.get(i).copied().unwrap_or(1.0);
+                        if neighbor == previous {
+                            weight / options.p
+                        } else if previous_neighbors.binary_search(&neighbor).is_ok() {
+                            weight
+                        } else {
+                            weight / options.q
+                        }
+                    };
+                    choose_weighted(candidates.neighbors.len(), bias, rng)
+                }
+                _ if candidates.weights.is_empty() => {
+                    Some(rng.random_range(0..candidates.neighbors.len()))
+                }
+                _ => choose_weighted(candidates.neighbors.len(), |i| candidates.weights[i], rng),
+            };
+            let Some(next) = next else {
+                break;
+            };
+            previous = Some(current);
+            current = candidates.neighbors[next];
+            path.push(current);
+        }
+        Ok(path)
+    }
+
+    /// Read the out neighbors of a vertex with their transition weights.
+    fn walk_neighbors(
+        &self,
+        vertex_id: VId,
+        options: &RandomWalkOptions,
+        biased: bool,
+        vertex_index: &VertexIndex,
+    ) -> anyhow::Result<WalkNeighbors> {
+        let all_neighbors: Vec<VId> = self
+            .read_out_neighbor_iter_hold_index(vertex_id, vertex_index)?
+            .collect();
+        let mut neighbors = Vec::with_capacity(all_neighbors.len());
+        let mut weights = Vec::new();
+        for neighbor in all_neighbors {
+            if let EdgeWeight::Unit = options.weight {
+                neighbors.push(neighbor);
+            } else if let Some(weight) = self.edge_weight(&options.weight, vertex_id, neighbor)? {
+                neighbors.push(neighbor);
+                weights.push(weight);
+            }
+        }
+        let sorted = if biased {
+            let mut sorted = neighbors.clone();
+            sorted.sort_unstable();
+            sorted
+        } else {
+            Vec::new()
+        };
+        Ok(WalkNeighbors {
+            neighbors,
+            weights,
+            sorted,
+        })
+    }
+}
+
+/// Fail on the first seed outside the graph.
+fn check_seeds(seeds: &[VId], vertex_index: &VertexIndex) -> anyhow::Result<()> {
+    match seeds
+        .iter()
+        .find(|&&seed| seed as usize >= vertex_index.vertex_array.len())
+    {
+        Some(seed) => Err(anyhow::anyhow!("Seed vertex {} not exists", seed)),
+        None => Ok(()),
+    }
+}
+
+/// Index in `0..len` drawn with probability proportional to `weight`, `None` if all
+/// weights are zero.
+fn choose_weighted(len: usize, weight: impl Fn(usize) -> f64, rng: &mut StdRng) -> Option<usize> {
+    let total: f64 = (0..len).map(&weight).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut target = rng.random::<f64>() * total;
+    for i in 0..len {
+        target -= weight(i);
+        if target < 0.0 {
+            return Some(i);
+        }
+    }
+    // Rounding left a sliver at the end, take the last candidate with a weight
+    (0..len).rev().find(|&i| weight(i) > 0.0)
+}
+
+/// Draw up to `fanout` neighbors uniformly.
+fn draw(neighbors: &[VId], fanout: usize, replace: bool, rng: &mut StdRng) -> Vec<VId> {
+    if neighbors.is_empty() || fanout == 0 {
+        Vec::new()
+    } else if fanout == usize::MAX || (!replace && fanout >= neighbors.len()) {
+        neighbors.to_vec()
+    } else if replace {
+        (0..fanout)
+            .map(|_| neighbors[rng.random_range(0..neighbors.len())])
+            .collect()
+    } else {
+        index::sample(rng, neighbors.len(), fanout)
+            .into_iter()
+            .map(|i| neighbors[i])
+            .collect()
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    LsmCommunity,
+    algorithms::NEIGHBOR_BATCH_SIZE,
+    graph::CsrGraph,
+    types::{CommId, VId},
+};
+use rayon::prelude::*;
+
+/// In-memory copy of part of the graph, with vertices renumbered from 0.
+#[derive(Debug, Clone)]
+pub struct InducedSubgraph {
+    /// The edges between the selected vertices on local IDs. Its community map holds the
+    /// community ID of every vertex in the full graph.
+    pub graph: CsrGraph,
+    /// `vertex_ids[local_id]` is the vertex ID in the full graph, in ascending order.
+    pub vertex_ids: Vec<VId>,
+}
+
+impl InducedSubgraph {
+    /// Local ID of a vertex of the full graph, `None` if it is not in the subgraph.
+    pub fn local_id(&self, vertex_id: VId) -> Option<VId> {
+        self.vertex_ids
+            .binary_search(&vertex_id)
+            .ok()
+            .map(|local_id| local_id as VId)
+    }
+
+    /// Vertex ID in the full graph of a local ID.
+    pub fn global_id(&self, local_id: VId) -> Option<VId> {
+        self.vertex_ids.get(local_id as usize).copied()
+    }
+}
+
+impl LsmCommunity {
+    /// Extracts the subgraph induced by a set of vertices into an in-memory [`CsrGraph`].
+    ///
+    /// The subgraph keeps every out edge, delta logs applied, whose endpoints are both
+    /// selected. Vertices are renumbered in ascending order of their ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertex_ids` - The selected vertices, duplicates are ignored
+    ///
+    /// # Returns
+    ///
+    /// Returns the subgraph with its mapping to the full graph, or an error if a vertex
+    /// does not exist or a neighbor list cannot be read.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(S log S + E_S) for S vertices of E_S out edges, read in batches in storage
+    ///   order
+    /// - Space: O(S + E_S)
+    pub fn induced_subgraph(&self, vertex_ids: &[VId]) -> anyhow::Result<InducedSubgraph> {
+        let vertex_index = self.vertex_index.read();
+        let vertex_count = vertex_index.vertex_array.len();
+        if let Some(vid) = vertex_ids.iter().find(|&&vid| vid as usize >= vertex_count) {
+            return Err(anyhow::anyhow!("Vertex {} not exists", vid));
+        }
+        let mut selected = vertex_ids.to_vec();
+        selected.sort_unstable();
+        selected.dedup();
+
+        // Read in storage order so that each batch touches few buckets and pages
+        let mut order: Vec<VId> = (0..selected.len() as VId).collect();
+        order.sort_by_key(|&local_id| vertex_index.storage_key(selected[local_id as usize]));
+        let batches: Vec<Vec<Vec<VId>>> = order
+            .par_chunks(NEIGHBOR_BATCH_SIZE)
+            .map(|batch| {
+                let global_ids: Vec<VId> = batch
+                    .iter()
+                    .map(|&local_id| selected[local_id as usize])
+                    .collect();
+                self.read_out_neighbors_batch_hold_index(&global_ids, &vertex_index)
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let mut edge_lists = vec![Vec::new(); selected.len()];
+        for (&local_id, neighbors) in order.iter().zip(batches.into_iter().flatten()) {
+            let mut local_neighbors: Vec<VId> = neighbors
+                .into_iter()
+                .filter_map(|neighbor| selected.binary_search(&neighbor).ok())
+                .map(|neighbor| neighbor as VId)
+                .collect();
+            local_neighbors.sort_unstable();
+            edge_lists[local_id as usize] = local_neighbors;
+        }
+        let community_map = selected
+            .iter()
+            .map(|&vid| vertex_index.community_map[vid as usize])
+            .collect();
+
+        Ok(InducedSubgraph {
+            graph: CsrGraph::from_edge_lists(edge_lists, community_map)?,
+            vertex_ids: selected,
+        })
+    }
+
+    /// Extracts the subgraph induced by the members of a community.
+    ///
+    /// See [`LsmCommunity::induced_subgraph`], the members are the ones returned by
+    /// [`LsmCommunity::community_detection`].
+    ///
+    /// # Returns
+    ///
+    /// Returns the subgraph of the community, or an error if the community does not exist.
+    pub fn community_subgraph(&self, comm_id: CommId) -> anyhow::Result<InducedSubgraph> {
+        let members = {
+            let vertex_index = self.vertex_index.read();
+            vertex_index
+                .community_list
+                .get(comm_id as usize)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Community {} not exists", comm_id))?
+        };
+        self.induced_subgraph(&members)
+    }
+}
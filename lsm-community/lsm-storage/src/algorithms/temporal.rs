@@ -0,0 +1,133 @@
+use crate::{LsmCommunity, types::VId, vertex_index::VertexIndex};
+use rustc_hash::FxHashMap;
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+impl LsmCommunity {
+    /// Collects the vertices reachable from a start vertex over time-respecting paths.
+    ///
+    /// A time-respecting path takes every hop over an edge valid at the time of the hop,
+    /// and no hop earlier than the previous one. The edges are valid over the intervals
+    /// of [`LsmCommunity::read_out_edge_intervals`], so that edges of the base graph can be
+    /// taken at any time. The search settles the vertices in order of earliest arrival,
+    /// as Dijkstra does on the arrival times.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_vertex` - The vertex ID from which to expand, left at `from`
+    /// * `from` - Start of the time window, included, in microseconds since the Unix epoch
+    /// * `to` - End of the time window, excluded, no hop happens at or after it
+    ///
+    /// # Returns
+    ///
+    /// Returns a vector of tuples `(vertex_id, arrival)` for every vertex reached, excluding
+    /// the start vertex, with the earliest time it can be reached, ordered by arrival and
+    /// by vertex ID for equal arrivals. Returns an error if the start vertex does not exist
+    /// or the graph is opened without the `edge_history` option.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O((V + E) log V) in the reached part of the graph, one delta log or edge
+    ///   history read per reached vertex
+    /// - Space: O(V) for the arrival times
+    pub fn temporal_bfs(
+        &self,
+        start_vertex: VId,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<Vec<(VId, u64)>> {
+        self.check_edge_history()?;
+        let vertex_index = self.vertex_index.read();
+        let mut result = Vec::new();
+        self.earliest_arrivals(start_vertex, from, to, &vertex_index, |vid, arrival| {
+            result.push((vid, arrival));
+            false
+        })?;
+        Ok(result)
+    }
+
+    /// Checks whether a time-respecting path leads from one vertex to another inside a
+    /// time window, see [`LsmCommunity::temporal_bfs`].
+    ///
+    /// # Arguments
+    ///
+    /// * `src_vertex` - The vertex where the path starts, left at `from`
+    /// * `dst_vertex` - The vertex where the path ends
+    /// * `from` - Start of the time window, included, in microseconds since the Unix epoch
+    /// * `to` - End of the time window, excluded
+    ///
+    /// # Returns
+    ///
+    /// Returns the earliest time the path reaches `dst_vertex`, `from` if both vertices are
+    /// the same, or None if no path exists. Returns an error if a vertex does not exist or
+    /// the graph is opened without the `edge_history` option.
+    pub fn temporal_reachability(
+        &self,
+        src_vertex: VId,
+        dst_vertex: VId,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<Option<u64>> {
+        self.check_edge_history()?;
+        let vertex_index = self.vertex_index.read();
+        if dst_vertex as usize >= vertex_index.vertex_array.len() {
+            return Err(anyhow::anyhow!("Vertex not exists"));
+        }
+        if src_vertex == dst_vertex && (src_vertex as usize) < vertex_index.vertex_array.len() {
+            return Ok((from < to).then_some(from));
+        }
+        let mut found = None;
+        self.earliest_arrivals(src_vertex, from, to, &vertex_index, |vid, arrival| {
+            if vid == dst_vertex {
+                found = Some(arrival);
+            }
+            found.is_some()
+        })?;
+        Ok(found)
+    }
+
+    /// Settle the vertices reachable from `start_vertex` in order of earliest arrival,
+    /// calling `visit` on each until it returns true.
+    fn earliest_arrivals(
+        &self,
+        start_vertex: VId,
+        from: u64,
+        to: u64,
+        vertex_index: &VertexIndex,
+        mut visit: impl FnMut(VId, u64) -> bool,
+    ) -> anyhow::Result<()> {
+        if start_vertex as usize >= vertex_index.vertex_array.len() {
+            return Err(anyhow::anyhow!("Vertex not exists"));
+        }
+        if from >= to {
+            return Ok(());
+        }
+
+        let mut arrivals: FxHashMap<VId, u64> = FxHashMap::default();
+        let mut heap = BinaryHeap::new();
+        arrivals.insert(start_vertex, from);
+        heap.push(Reverse((from, start_vertex)));
+
+        while let Some(Reverse((arrival, vid))) = heap.pop() {
+            // Skip entries superseded by an earlier arrival
+            if arrivals.get(&vid) != Some(&arrival) {
+                continue;
+            }
+            if vid != start_vertex && visit(vid, arrival) {
+                break;
+            }
+            for interval in self.read_out_edge_intervals_hold_index(vid, vertex_index)? {
+                // The earliest hop over the edge, not before the arrival
+                let hop = arrival.max(interval.valid_from);
+                if hop >= to || !interval.contains(hop) {
+                    continue;
+                }
+                let neighbor = interval.neighbor;
+                if arrivals.get(&neighbor).is_none_or(|&known| hop < known) {
+                    arrivals.insert(neighbor, hop);
+                    heap.push(Reverse((hop, neighbor)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{LsmCommunity, algorithms::NEIGHBOR_BATCH_SIZE, types::VId, vertex_index::VertexIndex};
+use rayon::prelude::*;
+
+/// Simple undirected view of the graph in CSR layout: edge directions, duplicate edges
+/// and self loops are dropped, every neighbor list is sorted.
+pub(crate) struct UndirectedGraph {
+    offsets: Vec<usize>,
+    neighbors: Vec<VId>,
+}
+
+impl UndirectedGraph {
+    #[inline]
+    pub(crate) fn vertex_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    #[inline]
+    pub(crate) fn neighbors(&self, vid: VId) -> &[VId] {
+        &self.neighbors[self.offsets[vid as usize]..self.offsets[vid as usize + 1]]
+    }
+
+    #[inline]
+    pub(crate) fn degree(&self, vid: VId) -> usize {
+        self.offsets[vid as usize + 1] - self.offsets[vid as usize]
+    }
+
+    /// Number of adjacency slots, twice the number of edges.
+    #[inline]
+    pub(crate) fn slot_count(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    /// Adjacency slot of `v` in the neighbor list of `u`, `None` if they are not adjacent.
+    #[inline]
+    pub(crate) fn slot(&self, u: VId, v: VId) -> Option<usize> {
+        self.neighbors(u)
+            .binary_search(&v)
+            .ok()
+            .map(|i| self.offsets[u as usize] + i)
+    }
+}
+
+impl LsmCommunity {
+    /// Collect the undirected edges `(u, v)` with `u < v`, sorted and without duplicates.
+    ///
+    /// The vertices are read in storage order with batched block reads and delta logs
+    /// applied, so each batch touches few buckets and pages.
+    pub(crate) fn undirected_edges(&self, vertex_index: &VertexIndex) -> Vec<(VId, VId)> {
+        let vertex_count = vertex_index.vertex_array.len();
+        let mut edges: Vec<(VId, VId)> = vertex_index
+            .storage_order()
+            .par_chunks(NEIGHBOR_BATCH_SIZE)
+            .flat_map_iter(|batch| {
+                let neighbor_lists =
+                    self.read_out_neighbors_batch_or_each_hold_index(batch, vertex_index);
+                batch
+                    .iter()
+                    .zip(neighbor_lists)
+                    .flat_map(|(&vid, neighbors)| {
+                        neighbors
+                            .into_iter()
+                            .filter(move |&neighbor| {
+                                neighbor != vid && (neighbor as usize) < vertex_count
+                            })
+                            .map(move |neighbor| (vid.min(neighbor), vid.max(neighbor)))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        edges.par_sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    /// Build the simple undirected view of the graph.
+    pub(crate) fn build_undirected_graph(&self, vertex_index: &VertexIndex) -> UndirectedGraph {
+        let vertex_count = vertex_index.vertex_array.len();
+        let edges = self.undirected_edges(vertex_index);
+
+        let mut offsets = vec![0usize; vertex_count + 1];
+        for &(u, v) in &edges {
+            offsets[u as usize + 1] += 1;
+            offsets[v as usize + 1] += 1;
+        }
+        for i in 0..vertex_count {
+            offsets[i + 1] += offsets[i];
+        }
+        // Edges are sorted by (u, v): the first pass appends the smaller neighbors of every
+        // vertex in ascending order, the second pass the larger ones.
+        let mut cursor = offsets.clone();
+        let mut neighbors = vec![0; offsets[vertex_count]];
+        for &(u, v) in &edges {
+            neighbors[cursor[v as usize]] = u;
+            cursor[v as usize] += 1;
+        }
+        for &(u, v) in &edges {
+            neighbors[cursor[u as usize]] = v;
+            cursor[u as usize] += 1;
+        }
+
+        UndirectedGraph { offsets, neighbors }
+    }
+}
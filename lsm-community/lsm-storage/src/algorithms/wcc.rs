@@ -1,14 +1,90 @@
 use rayon::prelude::*;
 use rustc_hash::FxHashSet;
 
-use crate::{LsmCommunity, types::VId, delta::DeltaOpType};
+use crate::{LsmCommunity, algorithms::NEIGHBOR_BATCH_SIZE, types::VId, vertex_index::VertexIndex};
+
+/// Union-Find over all vertices, kept up to date across edge updates when the
+/// `incremental_wcc` option is set.
+pub(crate) struct IncrementalWcc {
+    parent: Vec<VId>,
+    rank: Vec<u32>,
+    /// Number of disjoint sets.
+    component_count: usize,
+    /// Vertices whose component lost an edge since the last repair.
+    dirty: FxHashSet<VId>,
+}
+
+impl IncrementalWcc {
+    fn new(vertex_count: usize) -> Self {
+        Self {
+            parent: (0..vertex_count as VId).collect(),
+            rank: vec![0; vertex_count],
+            component_count: vertex_count,
+            dirty: FxHashSet::default(),
+        }
+    }
+
+    /// Find with path compression
+    fn find(&mut self, mut x: VId) -> VId {
+        while self.parent[x as usize] != x {
+            let next = self.parent[x as usize];
+            self.parent[x as usize] = self.parent[next as usize];
+            x = next;
+        }
+        x
+    }
+
+    /// Union by rank
+    fn union(&mut self, x: VId, y: VId) {
+        let root_x = self.find(x);
+        let root_y = self.find(y);
+
+        if root_x != root_y {
+            if self.rank[root_x as usize] < self.rank[root_y as usize] {
+                self.parent[root_x as usize] = root_y;
+            } else if self.rank[root_x as usize] > self.rank[root_y as usize] {
+                self.parent[root_y as usize] = root_x;
+            } else {
+                self.parent[root_y as usize] = root_x;
+                self.rank[root_x as usize] += 1;
+            }
+            self.component_count -= 1;
+        }
+    }
+
+    /// Add singleton sets for the vertices inserted since the last call.
+    fn grow(&mut self, vertex_count: usize) {
+        for vid in self.parent.len()..vertex_count {
+            self.parent.push(vid as VId);
+            self.rank.push(0);
+            self.component_count += 1;
+        }
+    }
+
+    /// Record the insertion of the edge `(src_vertex, dst_vertex)`.
+    pub(crate) fn on_edge_inserted(&mut self, src_vertex: VId, dst_vertex: VId) {
+        let vertex_count = (src_vertex.max(dst_vertex) as usize + 1).max(self.parent.len());
+        self.grow(vertex_count);
+        self.union(src_vertex, dst_vertex);
+    }
+
+    /// Record the removal of the edge `(src_vertex, dst_vertex)`, the component of its
+    /// endpoints is recomputed on the next query.
+    pub(crate) fn on_edge_removed(&mut self, src_vertex: VId, _dst_vertex: VId) {
+        if (src_vertex as usize) < self.parent.len() {
+            self.dirty.insert(src_vertex);
+        }
+    }
+}
 
 impl LsmCommunity {
     /// Compute Weakly Connected Components using Union-Find.
     ///
     /// In a directed graph, WCC treats all edges as undirected.
-    /// This implementation uses parallel edge collection followed by
-    /// sequential Union-Find algorithm.
+    /// This implementation uses parallel edge collection, reading the vertices
+    /// in batches with `read_out_neighbors_batch_hold_index`, followed by
+    /// sequential Union-Find algorithm. With the `incremental_wcc` option the
+    /// components are read from the maintained Union-Find instead.
     ///
     /// # Returns
     ///
@@ -18,98 +94,134 @@ impl LsmCommunity {
     ///
     /// # Performance
     ///
-    /// - Time: O(V + E * α(V)) where α is the inverse Ackermann function
+    /// - Time: O(V + E * α(V)) where α is the inverse Ackermann function,
+    ///   O(V * α(V)) when maintained incrementally
     /// - Space: O(V + E)
     pub fn wcc(&self) -> Vec<VId> {
-        let vertex_count = self.vertex_count();
-        let vertex_index_state = self.vertex_index.read();
+        if self.inner.options.incremental_wcc {
+            return self.with_incremental_wcc(|wcc| {
+                (0..wcc.parent.len() as VId)
+                    .map(|vid| wcc.find(vid))
+                    .collect()
+            });
+        }
 
-        let all_edges: Vec<(VId, VId)> = (0..vertex_count as VId)
-            .into_par_iter()
-            .flat_map(|vid| {
-                let mut edges = Vec::new();
-
-                if let Ok((neighbors_iter, delta_log)) =
-                    self.read_neighbor_hold_index_vertex(vid, true, &vertex_index_state)
-                {
-                    // 1. Process base neighbor list (replaced with FxHashSet for O(1) lookup/removal)
-                    let mut base_neighbors = if let Some(neighbors) = neighbors_iter {
-                        // Convert iterator to HashSet, O(n) construction, subsequent operations are O(1)
-                        neighbors.collect::<FxHashSet<VId>>()
-                    } else {
-                        FxHashSet::default()
-                    };
-
-                    // 2. Process incremental operations in the delta log (Add/Remove)
-                    if let Some(delta) = delta_log {
-                        for op in delta.ops {
-                            match op.get_op_type() {
-                                Some(DeltaOpType::AddNeighbor) => {
-                                    // Add neighbor: HashSet.insert is O(1) (average case)
-                                    base_neighbors.insert(op.neighbor);
-                                }
-                                Some(DeltaOpType::RemoveNeighbor) => {
-                                    // Remove neighbor: HashSet.remove is O(1) (average case)
-                                    base_neighbors.remove(&op.neighbor);
-                                }
-                                None => {
-                                    // Ignore invalid operation types
-                                    continue;
-                                }
-                            }
-                        }
-                    }
-
-                    // 3. Generate undirected edges (stored in both directions)
-                    for neighbor in base_neighbors {
-                        edges.push((vid, neighbor));
-                    }
-                }
-
-                edges
-            })
-            .collect();
+        let vertex_index_state = self.vertex_index.read();
+        let mut wcc = self.build_wcc(&vertex_index_state);
 
-        let mut parent: Vec<VId> = (0..vertex_count as VId).collect();
-        let mut rank = vec![0u32; vertex_count as usize];
+        // Build final result with path compression
+        (0..wcc.parent.len() as VId)
+            .map(|vid| wcc.find(vid))
+            .collect()
+    }
 
-        // Find with path compression
-        fn find(parent: &mut [VId], mut x: VId) -> VId {
-            while parent[x as usize] != x {
-                let next = parent[x as usize];
-                parent[x as usize] = parent[next as usize];
-                x = next;
-            }
-            x
+    /// Get the weakly connected component of a vertex.
+    ///
+    /// # Returns
+    ///
+    /// Returns the component ID of the vertex, the same ID as in [`LsmCommunity::wcc`],
+    /// or `None` if the vertex does not exist.
+    ///
+    /// # Performance
+    ///
+    /// - Time: amortized O(α(V)) with the `incremental_wcc` option, a full [`LsmCommunity::wcc`]
+    ///   otherwise. The first query, and the first one after an edge removal, rebuild the
+    ///   affected components.
+    pub fn component_of(&self, vertex_id: VId) -> Option<VId> {
+        if vertex_id as usize >= self.vertex_count() {
+            return None;
+        }
+        if self.inner.options.incremental_wcc {
+            Some(self.with_incremental_wcc(|wcc| wcc.find(vertex_id)))
+        } else {
+            self.wcc().get(vertex_id as usize).copied()
         }
+    }
 
-        // Union by rank
-        fn union(parent: &mut [VId], rank: &mut [u32], x: VId, y: VId) {
-            let root_x = find(parent, x);
-            let root_y = find(parent, y);
-
-            if root_x != root_y {
-                if rank[root_x as usize] < rank[root_y as usize] {
-                    parent[root_x as usize] = root_y;
-                } else if rank[root_x as usize] > rank[root_y as usize] {
-                    parent[root_y as usize] = root_x;
-                } else {
-                    parent[root_y as usize] = root_x;
-                    rank[root_x as usize] += 1;
-                }
-            }
+    /// Get the number of weakly connected components.
+    ///
+    /// # Performance
+    ///
+    /// - Time: O(1) with the `incremental_wcc` option once the components are up to date,
+    ///   a full [`LsmCommunity::wcc`] otherwise
+    pub fn component_count(&self) -> usize {
+        if self.inner.options.incremental_wcc {
+            self.with_incremental_wcc(|wcc| wcc.component_count)
+        } else {
+            let wcc = self.wcc();
+            (0..wcc.len())
+                .filter(|&vid| wcc[vid] as usize == vid)
+                .count()
         }
+    }
+
+    /// Run `f` on the maintained Union-Find, building it on first use or once an edge
+    /// expired, and repairing the components affected by edge removals.
+    fn with_incremental_wcc<R>(&self, f: impl FnOnce(&mut IncrementalWcc) -> R) -> R {
+        self.expire_derived_indexes();
+        let mut state = self.incremental_wcc.lock();
+        let vertex_index_state = self.vertex_index.read();
+        let wcc = state.get_or_insert_with(|| self.build_wcc(&vertex_index_state));
+        wcc.grow(vertex_index_state.vertex_array.len());
+        self.repair_wcc(wcc, &vertex_index_state);
+        f(wcc)
+    }
+
+    /// Build the Union-Find from all edges.
+    fn build_wcc(&self, vertex_index: &VertexIndex) -> IncrementalWcc {
+        let vertex_count = vertex_index.vertex_array.len();
+        let all_vertices: Vec<VId> = (0..vertex_count as VId).collect();
+        let mut wcc = IncrementalWcc::new(vertex_count);
 
         // Execute union for all edges
-        for (u, v) in all_edges {
-            union(&mut parent, &mut rank, u, v);
+        for (u, v) in self.collect_out_edges(&all_vertices, vertex_index) {
+            wcc.union(u, v);
         }
+        wcc
+    }
 
-        // Step 3: Build final result with path compression
-        let mut result = vec![0; vertex_count as usize];
-        for vid in 0..vertex_count as VId {
-            result[vid as usize] = find(&mut parent, vid);
+    /// Recompute the components that lost an edge.
+    ///
+    /// An edge never leaves its component, so the members of a dirty component are reset
+    /// to singletons and united again through their own out edges only.
+    fn repair_wcc(&self, wcc: &mut IncrementalWcc, vertex_index: &VertexIndex) {
+        if wcc.dirty.is_empty() {
+            return;
         }
-        result
+        let dirty: Vec<VId> = std::mem::take(&mut wcc.dirty).into_iter().collect();
+        let dirty_roots: FxHashSet<VId> = dirty.into_iter().map(|vid| wcc.find(vid)).collect();
+        let members: Vec<VId> = (0..wcc.parent.len() as VId)
+            .filter(|&vid| dirty_roots.contains(&wcc.find(vid)))
+            .collect();
+
+        for &vid in &members {
+            wcc.parent[vid as usize] = vid;
+            wcc.rank[vid as usize] = 0;
+        }
+        wcc.component_count += members.len() - dirty_roots.len();
+        for (u, v) in self.collect_out_edges(&members, vertex_index) {
+            wcc.union(u, v);
+        }
+    }
+
+    /// Read the out edges of the given vertices in parallel batches.
+    fn collect_out_edges(&self, vertices: &[VId], vertex_index: &VertexIndex) -> Vec<(VId, VId)> {
+        vertices
+            .par_chunks(NEIGHBOR_BATCH_SIZE)
+            .flat_map_iter(|batch| {
+                // Read the neighbor lists of the batch with batched block reads
+                let neighbor_lists =
+                    self.read_out_neighbors_batch_or_each_hold_index(batch, vertex_index);
+
+                // Generate undirected edges (stored in both directions)
+                batch
+                    .iter()
+                    .zip(neighbor_lists)
+                    .flat_map(|(&vid, neighbors)| {
+                        neighbors.into_iter().map(move |neighbor| (vid, neighbor))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
     }
-}
\ No newline at end of file
+}
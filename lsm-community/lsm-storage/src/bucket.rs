@@ -12,8 +12,10 @@ use bytes::{Buf, BufMut};
 use rustc_hash::FxHashMap;
 
 mod bloom;
+pub mod buffer_pool;
 pub mod builder;
 pub mod disk_manager;
+pub mod io;
 
 /// The metadata of each vertex in bucket
 #[derive(Debug)]
@@ -29,6 +31,9 @@ pub struct VertexMeta {
 }
 
 impl VertexMeta {
+    /// Bytes of an encoded vertex meta.
+    const ENCODED_SIZE: usize = 10;
+
     pub fn encode(vertex_meta: &[VertexMeta], buf: &mut Vec<u8>) {
         let mut estimated_size = std::mem::size_of::<u32>(); // number of vertices
         for _ in vertex_meta {
@@ -50,7 +55,17 @@ impl VertexMeta {
 
     pub fn decode(mut buf: &[u8]) -> Result<Vec<VertexMeta>> {
         let mut vertex_meta = Vec::new();
+        if buf.remaining() < 4 {
+            bail!("Vertex metas of {} bytes have no count", buf.remaining());
+        }
         let num = buf.get_u32() as usize;
+        if buf.remaining() != num * Self::ENCODED_SIZE {
+            bail!(
+                "Vertex metas of {} bytes cannot hold {} vertices",
+                buf.remaining(),
+                num
+            );
+        }
         for _ in 0..num {
             let vertex_id = buf.get_u32();
             let page_id = buf.get_u32();
@@ -100,14 +115,29 @@ impl Bucket {
         self.virtual_comm_id
     }
 
+    /// Get the total size of the block segment in bytes;
+    pub fn block_segment_size(&self) -> usize {
+        self.vertex_meta_offset
+    }
+
     /// Get block size
     pub fn get_block_size(&self) -> usize {
         self.block_size
     }
 
+    /// Get the size of the bucket file in bytes
+    pub fn file_size(&self) -> u64 {
+        self.file.size()
+    }
+
     /// Open bucket from a file.
+    ///
+    /// A torn or corrupted file, whose footer does not describe it, is an error.
     pub fn open(id: VirtualCommId, file: BktDiskManager, build_map: bool) -> Result<Self> {
         let len = file.size();
+        if len < 12 {
+            bail!("Bucket {} file of {} bytes has no footer", id, len);
+        }
 
         // Read bloom size (last 4 bytes, file size - 4)
         let raw_bloom_size = file.read(len - 4, 4)?;
@@ -121,6 +151,19 @@ impl Bucket {
         // Read block size (file size - 12)
         let raw_block_size = file.read(len - 12, 4)?;
         let block_size = (&raw_block_size[..]).get_u32() as usize;
+        if block_size == 0 {
+            bail!("Bucket {} footer has a block size of 0", id);
+        }
+
+        if bloom_size > len - 12 || vertex_meta_offset > len - 12 - bloom_size {
+            bail!(
+                "Bucket {} footer does not fit its file of {} bytes (bloom size {}, vertex meta offset {})",
+                id,
+                len,
+                bloom_size,
+                vertex_meta_offset
+            );
+        }
 
         // Read bloom filter
         let bloom_offset = len - 12 - bloom_size;
@@ -185,6 +228,38 @@ impl Bucket {
         Ok(Arc::new(Block::decode(block_data)))
     }
 
+    /// Read several blocks from the disk with one batched request.
+    ///
+    /// # Arguments
+    /// * `page_ids` - Pages to read, the result follows the same order
+    ///
+    /// # Returns
+    /// * `Result<Vec<Arc<Block>>>` - The decoded blocks
+    pub fn read_blocks(&self, page_ids: &[PageId]) -> Result<Vec<Arc<Block>>> {
+        let mut requests = Vec::with_capacity(page_ids.len());
+        for &page_id in page_ids {
+            let offset = page_id as usize * self.block_size;
+            if offset >= self.vertex_meta_offset {
+                bail!(
+                    "Page {} is out of bounds (offset {} >= vertex_meta_offset {})",
+                    page_id,
+                    offset,
+                    self.vertex_meta_offset
+                );
+            }
+            let offset_end = std::cmp::min(offset + self.block_size, self.vertex_meta_offset);
+            requests.push((offset as u64, (offset_end - offset) as u64));
+        }
+
+        let blocks = self
+            .file
+            .read_batch(&requests)?
+            .into_iter()
+            .map(|block_data| Arc::new(Block::decode(block_data)))
+            .collect();
+        Ok(blocks)
+    }
+
     /// Get neighbors of a vertex
     pub fn get_neighbors_for_test(&mut self, vertex_id: VId) -> Result<Vec<VId>> {
         // Build block map if not exists
@@ -68,6 +68,9 @@ impl<T: AsMut<[u8]>> BitSliceMut for T {
 impl Bloom {
     /// Decode a bloom filter
     pub fn decode(buf: &[u8]) -> Result<Self> {
+        if buf.len() < 5 {
+            bail!("bloom filter of {} bytes is truncated", buf.len());
+        }
         let checksum = (&buf[buf.len() - 4..buf.len()]).get_u32();
         if checksum != crc32fast::hash(&buf[..buf.len() - 4]) {
             bail!("checksum mismatched for bloom filters");
@@ -0,0 +1,182 @@
+use std::{
+    alloc::{Layout, alloc_zeroed, dealloc},
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+    sync::{Arc, OnceLock},
+};
+
+use parking_lot::Mutex;
+
+/// Alignment of direct I/O buffers, offsets and lengths, the logical block size of most
+/// devices.
+pub const DIRECT_IO_ALIGNMENT: usize = 4096;
+
+/// Maximum bytes kept by the shared pool between reads.
+const SHARED_POOL_BYTES: usize = 16 * 1024 * 1024;
+
+/// Round `value` up to a multiple of `alignment`, a power of two.
+pub fn align_up(value: u64, alignment: usize) -> u64 {
+    let mask = alignment as u64 - 1;
+    (value + mask) & !mask
+}
+
+/// Round `value` down to a multiple of `alignment`, a power of two.
+pub fn align_down(value: u64, alignment: usize) -> u64 {
+    value & !(alignment as u64 - 1)
+}
+
+/// A zero-initialized heap buffer whose address and capacity are multiples of its alignment.
+#[derive(Debug)]
+pub struct AlignedBuffer {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+// The buffer owns its allocation, like a `Vec<u8>`.
+unsafe impl Send for AlignedBuffer {}
+unsafe impl Sync for AlignedBuffer {}
+
+impl AlignedBuffer {
+    /// Allocate a buffer of at least `len` bytes, rounded up to the alignment.
+    pub fn new(len: usize, alignment: usize) -> Self {
+        let capacity = align_up(len.max(1) as u64, alignment) as usize;
+        let layout = Layout::from_size_align(capacity, alignment).expect("invalid alignment");
+        // Zeroed, so that no uninitialized byte is ever exposed
+        let ptr = unsafe { alloc_zeroed(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| std::alloc::handle_alloc_error(layout));
+        Self { ptr, layout }
+    }
+
+    /// Size of the buffer in bytes.
+    pub fn capacity(&self) -> usize {
+        self.layout.size()
+    }
+
+    /// Alignment of the buffer address in bytes.
+    pub fn alignment(&self) -> usize {
+        self.layout.align()
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.capacity()) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.capacity()) }
+    }
+}
+
+impl Drop for AlignedBuffer {
+    fn drop(&mut self) {
+        unsafe { dealloc(self.ptr.as_ptr(), self.layout) }
+    }
+}
+
+/// Pool of aligned buffers reused across direct I/O reads.
+///
+/// A buffer is borrowed with [`AlignedBufferPool::acquire`] and goes back to the pool when
+/// the returned [`PooledBuffer`] is dropped. At most `max_pooled_bytes` are kept, larger
+/// surpluses are freed.
+#[derive(Debug)]
+pub struct AlignedBufferPool {
+    alignment: usize,
+    max_pooled_bytes: usize,
+    /// Free buffers sorted by capacity, and their total bytes.
+    free: Mutex<(Vec<AlignedBuffer>, usize)>,
+}
+
+impl AlignedBufferPool {
+    /// Create a pool of buffers aligned to `alignment`, a power of two.
+    pub fn new(alignment: usize, max_pooled_bytes: usize) -> Self {
+        assert!(
+            alignment.is_power_of_two(),
+            "alignment must be a power of two"
+        );
+        Self {
+            alignment,
+            max_pooled_bytes,
+            free: Mutex::new((Vec::new(), 0)),
+        }
+    }
+
+    /// The pool shared by all bucket files of the process.
+    pub fn shared() -> Arc<Self> {
+        static SHARED: OnceLock<Arc<AlignedBufferPool>> = OnceLock::new();
+        SHARED
+            .get_or_init(|| Arc::new(Self::new(DIRECT_IO_ALIGNMENT, SHARED_POOL_BYTES)))
+            .clone()
+    }
+
+    /// Alignment of the buffers in bytes.
+    pub fn alignment(&self) -> usize {
+        self.alignment
+    }
+
+    /// Total bytes of the buffers waiting in the pool.
+    pub fn pooled_bytes(&self) -> usize {
+        self.free.lock().1
+    }
+
+    /// Borrow a buffer of at least `len` bytes, the smallest pooled one that fits or a new one.
+    ///
+    /// A reused buffer keeps the bytes of its previous use.
+    pub fn acquire(&self, len: usize) -> PooledBuffer<'_> {
+        let capacity = align_up(len.max(1) as u64, self.alignment) as usize;
+        let buffer = {
+            let mut free = self.free.lock();
+            let (buffers, pooled_bytes) = &mut *free;
+            let index = buffers.partition_point(|buffer| buffer.capacity() < capacity);
+            (index < buffers.len()).then(|| {
+                let buffer = buffers.remove(index);
+                *pooled_bytes -= buffer.capacity();
+                buffer
+            })
+        };
+        PooledBuffer {
+            pool: self,
+            buffer: Some(buffer.unwrap_or_else(|| AlignedBuffer::new(capacity, self.alignment))),
+            len,
+        }
+    }
+
+    fn release(&self, buffer: AlignedBuffer) {
+        let mut free = self.free.lock();
+        let (buffers, pooled_bytes) = &mut *free;
+        if *pooled_bytes + buffer.capacity() > self.max_pooled_bytes {
+            return;
+        }
+        *pooled_bytes += buffer.capacity();
+        let index = buffers.partition_point(|pooled| pooled.capacity() < buffer.capacity());
+        buffers.insert(index, buffer);
+    }
+}
+
+/// An aligned buffer borrowed from an [`AlignedBufferPool`], dereferences to its first `len`
+/// bytes.
+pub struct PooledBuffer<'a> {
+    pool: &'a AlignedBufferPool,
+    buffer: Option<AlignedBuffer>,
+    len: usize,
+}
+
+impl Deref for PooledBuffer<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buffer.as_ref().unwrap().as_slice()[..self.len]
+    }
+}
+
+impl DerefMut for PooledBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer.as_mut().unwrap().as_mut_slice()[..self.len]
+    }
+}
+
+impl Drop for PooledBuffer<'_> {
+    fn drop(&mut self) {
+        if let Some(buffer) = self.buffer.take() {
+            self.pool.release(buffer);
+        }
+    }
+}
@@ -2,7 +2,11 @@ use std::path::Path;
 
 use crate::{
     block::builder::BlockBuilder,
-    bucket::{Bucket, VertexMeta, bloom::Bloom, disk_manager::BktDiskManager},
+    bucket::{
+        Bucket, VertexMeta,
+        bloom::Bloom,
+        disk_manager::{BktDiskManager, DiskManagerOptions},
+    },
     types::{PageId, VId, VirtualCommId},
 };
 use anyhow::Result;
@@ -46,8 +50,10 @@ impl BucketBuilder {
     /// Add a vertex with its neighbors to the bucket.
     /// The neighbors are provided as an iterator.
     pub fn add(&mut self, vertex_id: VId, neighbors: impl Iterator<Item = VId>) {
-        // Collect neighbors into a Vec (needed for BlockBuilder::add_vertex)
-        let neighbors_vec: Vec<VId> = neighbors.collect();
+        // Collect neighbors into a sorted Vec (needed for BlockBuilder::add_vertex),
+        // readers merge the block neighbors with deltas in ascending order
+        let mut neighbors_vec: Vec<VId> = neighbors.collect();
+        neighbors_vec.sort_unstable();
 
         // Hash all edges for bloom filter
         for &neighbor in &neighbors_vec {
@@ -95,7 +101,17 @@ impl BucketBuilder {
         self.current_page_id += 1;
     }
 
-    pub fn build(mut self, id: VirtualCommId, path: impl AsRef<Path>) -> Result<Bucket> {
+    pub fn build(self, id: VirtualCommId, path: impl AsRef<Path>) -> Result<Bucket> {
+        self.build_with_options(id, path, DiskManagerOptions::default())
+    }
+
+    /// Build the bucket and write it to `path` with the I/O options of its file.
+    pub fn build_with_options(
+        mut self,
+        id: VirtualCommId,
+        path: impl AsRef<Path>,
+        options: DiskManagerOptions,
+    ) -> Result<Bucket> {
         // Finish the last block if not empty
         if !self.builder.is_empty() {
             self.finish_block();
@@ -123,13 +139,17 @@ impl BucketBuilder {
         // Calculate bloom size
         let bloom_size = buf.len() - bloom_offset;
 
-        // Write footer (3 x u32 = 12 bytes)
-        buf.put_u32(self.block_size as u32); // Block size
-        buf.put_u32(vertex_meta_offset as u32); // Vertex meta offset
-        buf.put_u32(bloom_size as u32); // Bloom size
+        // Write footer (3 x u32 = 12 bytes), offsets beyond u32 cannot be encoded
+        let footer_u32 = |value: usize, name: &str| {
+            u32::try_from(value)
+                .map_err(|_| anyhow::anyhow!("Bucket {} {} exceeds the u32 footer", id, name))
+        };
+        buf.put_u32(footer_u32(self.block_size, "block size")?); // Block size
+        buf.put_u32(footer_u32(vertex_meta_offset, "vertex meta offset")?); // Vertex meta offset
+        buf.put_u32(footer_u32(bloom_size, "bloom size")?); // Bloom size
 
         // Create disk file
-        let file = BktDiskManager::create(path.as_ref(), &buf)?;
+        let file = BktDiskManager::create_with_options(path.as_ref(), &buf, options)?;
 
         // Build and return bucket
         Ok(Bucket {
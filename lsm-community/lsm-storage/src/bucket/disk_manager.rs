@@ -1,16 +1,16 @@
-use std::fs::File;
-use std::io::Result;
+use std::io::{ErrorKind, Result};
 use std::path::Path;
+use std::sync::Arc;
 
-#[cfg(unix)]
-use std::os::unix::fs::FileExt;
-#[cfg(windows)]
-use std::os::windows::fs::FileExt;
+use crate::bucket::buffer_pool::{AlignedBufferPool, align_down, align_up};
+use crate::bucket::io::{self, BucketIo, IoBackend};
 
+#[cfg(target_os = "linux")]
+use crate::bucket::io::{UringIo, uring::submit_all_on};
 #[cfg(target_os = "linux")]
 use io_uring::{IoUring, opcode, types};
-#[cfg(unix)]
-use std::os::unix::io::AsRawFd;
+#[cfg(target_os = "linux")]
+use std::os::unix::io::{AsRawFd, RawFd};
 
 /// Configuration options for disk I/O
 #[derive(Debug, Clone, Copy)]
@@ -19,6 +19,10 @@ pub struct DiskManagerOptions {
     pub use_direct_io: bool,
     /// Queue depth for async I/O (Linux io_uring)
     pub queue_depth: u32,
+    /// Open existing files without write access, writes fail
+    pub read_only: bool,
+    /// Implementation of the file I/O
+    pub backend: IoBackend,
 }
 
 impl Default for DiskManagerOptions {
@@ -28,6 +32,8 @@ impl Default for DiskManagerOptions {
             return Self {
                 use_direct_io: true,
                 queue_depth: 256,
+                read_only: false,
+                backend: IoBackend::default(),
             };
         }
 
@@ -35,21 +41,31 @@ impl Default for DiskManagerOptions {
         Self {
             use_direct_io: false,
             queue_depth: 256,
+            read_only: false,
+            backend: IoBackend::default(),
         }
     }
 }
 
-/// Platform-agnostic disk manager
-/// Automatically uses the best I/O backend for each platform:
-/// - Linux: io_uring for async batched I/O
-/// - macOS: fcntl optimizations + pread
-/// - Windows: standard I/O with optional FILE_FLAG_NO_BUFFERING
+/// Disk manager of a bucket file
+///
+/// The file I/O goes through a [`BucketIo`] chosen by [`DiskManagerOptions::backend`]:
+/// - Uring: Linux io_uring for async batched I/O
+/// - Pread: positional I/O on every platform, fcntl optimizations on macOS and
+///   FILE_FLAG_NO_BUFFERING on Windows
+/// - Mmap: reads from a shared memory map through the page cache
+/// - Memory: files kept in process memory
+///
+/// With direct I/O, every read and write is widened to `DIRECT_IO_ALIGNMENT` and goes
+/// through an aligned buffer of the shared [`AlignedBufferPool`], so tail blocks and
+/// footer reads at arbitrary offsets work on every filesystem. If the filesystem rejects
+/// direct I/O, the file falls back to buffered I/O.
 #[derive(Debug)]
 pub struct BktDiskManager {
-    file: File,
+    io: Box<dyn BucketIo>,
     size: u64,
-    #[cfg(target_os = "linux")]
-    io_uring: Option<std::sync::Arc<std::sync::Mutex<IoUring>>>,
+    /// Aligned buffers of direct I/O
+    buffer_pool: Arc<AlignedBufferPool>,
 }
 
 impl BktDiskManager {
@@ -60,25 +76,7 @@ impl BktDiskManager {
 
     /// Create a new disk manager with custom options
     pub fn with_options(path: &Path, options: DiskManagerOptions) -> Result<Self> {
-        #[cfg(target_os = "linux")]
-        {
-            Self::new_linux(path, options)
-        }
-
-        #[cfg(target_os = "macos")]
-        {
-            Self::new_macos(path, options)
-        }
-
-        #[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
-        {
-            Self::new_unix(path, options)
-        }
-
-        #[cfg(windows)]
-        {
-            Self::new_windows(path, options)
-        }
+        Ok(Self::from_io(io::open(path, options)?))
     }
 
     /// Create a new file and return a disk manager
@@ -92,79 +90,87 @@ impl BktDiskManager {
         data: &[u8],
         options: DiskManagerOptions,
     ) -> Result<Self> {
-        #[cfg(target_os = "linux")]
-        {
-            Self::create_linux(path, data, options)
-        }
-
-        #[cfg(target_os = "macos")]
-        {
-            Self::create_macos(path, data, options)
-        }
-
-        #[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
-        {
-            Self::create_unix(path, data, options)
-        }
+        Ok(Self::from_io(io::create(path, data, options)?))
+    }
 
-        #[cfg(windows)]
-        {
-            Self::create_windows(path, data, options)
+    /// Create a disk manager over an opened file of any backend
+    pub fn from_io(io: Box<dyn BucketIo>) -> Self {
+        Self {
+            size: io.size(),
+            io,
+            buffer_pool: AlignedBufferPool::shared(),
         }
     }
 
     /// Read data from file at given offset
     pub fn read(&self, offset: u64, len: u64) -> Result<Vec<u8>> {
-        #[cfg(target_os = "linux")]
-        {
-            if self.io_uring.is_some() {
-                return self.read_uring_single(offset, len);
+        if self.uses_direct_io() {
+            match self.read_direct(offset, len) {
+                Err(error) if error.kind() == ErrorKind::InvalidInput => {
+                    self.io.disable_direct_io(error)?
+                }
+                result => return result,
             }
         }
 
-        // Default synchronous read for all platforms
-        self.read_sync(offset, len)
+        let mut data = vec![0; len as usize];
+        let read = self.io.read_at(offset, &mut data)?;
+        if read != data.len() {
+            return Err(std::io::Error::new(ErrorKind::UnexpectedEof, "Short read"));
+        }
+        Ok(data)
     }
 
     /// Write data to file at given offset
     pub fn write(&self, offset: u64, data: &[u8]) -> Result<()> {
-        #[cfg(target_os = "linux")]
-        {
-            if self.io_uring.is_some() {
-                return self.write_uring_single(offset, data);
+        if self.uses_direct_io() {
+            match self.write_direct(offset, data) {
+                Err(error) if error.kind() == ErrorKind::InvalidInput => {
+                    self.io.disable_direct_io(error)?
+                }
+                result => return result,
             }
         }
 
-        // Default synchronous write
-        self.write_sync(offset, data)
+        self.io.write_at(offset, data)
     }
 
-    /// Batch read multiple regions (optimized on Linux with io_uring)
+    /// Batch read multiple regions (one submission with io_uring)
     pub fn read_batch(&self, requests: &[(u64, u64)]) -> Result<Vec<Vec<u8>>> {
-        #[cfg(target_os = "linux")]
-        {
-            if self.io_uring.is_some() {
-                return self.read_batch_uring(requests);
+        if self.uses_direct_io() {
+            match self.read_batch_direct(requests) {
+                Err(error) if error.kind() == ErrorKind::InvalidInput => {
+                    self.io.disable_direct_io(error)?
+                }
+                result => return result,
             }
         }
 
-        // Fallback: sequential reads for non-Linux or non-io_uring systems
-        requests
+        let mut buffers: Vec<Vec<u8>> = requests
             .iter()
-            .map(|(offset, len)| self.read(*offset, *len))
-            .collect()
+            .map(|(_, len)| vec![0u8; *len as usize])
+            .collect();
+        let offsets: Vec<u64> = requests.iter().map(|(offset, _)| *offset).collect();
+        let mut slices: Vec<&mut [u8]> = buffers.iter_mut().map(|buf| buf.as_mut_slice()).collect();
+        let reads = self.io.read_batch_at(&offsets, &mut slices)?;
+        for (i, read) in reads.iter().enumerate() {
+            if *read != requests[i].1 as usize {
+                return Err(std::io::Error::new(
+                    ErrorKind::Other,
+                    format!("Read failed for request {}", i),
+                ));
+            }
+        }
+        Ok(buffers)
     }
 
-    /// Batch write multiple regions (optimized on Linux with io_uring)
+    /// Batch write multiple regions (one submission with io_uring)
     pub fn write_batch(&self, requests: &[(u64, Vec<u8>)]) -> Result<()> {
-        #[cfg(target_os = "linux")]
-        {
-            if self.io_uring.is_some() {
-                return self.write_batch_uring(requests);
-            }
+        if !self.uses_direct_io() {
+            return self.io.write_batch_at(requests);
         }
 
-        // Fallback: sequential writes
+        // Direct I/O aligns each write on its own
         for (offset, data) in requests {
             self.write(*offset, data)?;
         }
@@ -176,365 +182,77 @@ impl BktDiskManager {
         self.size
     }
 
-    #[cfg(target_os = "linux")]
-    fn new_linux(path: &Path, options: DiskManagerOptions) -> Result<Self> {
-        use std::os::unix::fs::OpenOptionsExt;
-
-        let mut opts = OpenOptions::new();
-        opts.read(true).write(true);
-
-        if options.use_direct_io {
-            opts.custom_flags(libc::O_DIRECT);
-        }
-
-        let file = opts.open(path)?;
-        let size = file.metadata()?.len();
-
-        // Initialize io_uring for async I/O
-        let io_uring = Some(std::sync::Arc::new(std::sync::Mutex::new(IoUring::new(
-            options.queue_depth,
-        )?)));
-
-        // Advise kernel for random access (typical for LSM-Tree)
-        unsafe {
-            libc::posix_fadvise(file.as_raw_fd(), 0, size as i64, libc::POSIX_FADV_RANDOM);
-        }
-
-        Ok(Self {
-            file,
-            size,
-            io_uring,
-        })
+    /// Whether the file is read and written with direct I/O
+    pub fn uses_direct_io(&self) -> bool {
+        self.io.direct_io()
     }
 
-    #[cfg(target_os = "linux")]
-    fn create_linux(path: &Path, data: &[u8], options: DiskManagerOptions) -> Result<Self> {
-        use std::io::Write;
-        use std::os::unix::fs::OpenOptionsExt;
-
-        let mut write_opts = OpenOptions::new();
-        write_opts.write(true).create(true).truncate(true);
+    /// Read `[offset, offset + len)` through an aligned buffer covering it.
+    fn read_direct(&self, offset: u64, len: u64) -> Result<Vec<u8>> {
+        let alignment = self.buffer_pool.alignment();
+        let start = align_down(offset, alignment);
+        let end = align_up(offset + len, alignment);
+        let mut buffer = self.buffer_pool.acquire((end - start) as usize);
 
-        if options.use_direct_io {
-            write_opts.custom_flags(libc::O_DIRECT | libc::O_SYNC);
+        // The aligned range may pass the end of the file, the read is then short
+        let read = self.io.read_at(start, &mut buffer)?;
+        let skip = (offset - start) as usize;
+        if read < skip + len as usize {
+            return Err(std::io::Error::new(ErrorKind::UnexpectedEof, "Short read"));
         }
-
-        let mut write_file = write_opts.open(path)?;
-        write_file.write_all(data)?;
-        write_file.sync_all()?;
-        drop(write_file);
-
-        Self::new_linux(path, options)
+        Ok(buffer[skip..skip + len as usize].to_vec())
     }
 
-    #[cfg(target_os = "linux")]
-    fn read_uring_single(&self, offset: u64, len: u64) -> Result<Vec<u8>> {
-        let mut data = vec![0; len as usize];
-        let fd = types::Fd(self.file.as_raw_fd());
-
-        let ring = self.io_uring.as_ref().unwrap();
-        let mut ring = ring.lock().unwrap();
-
-        let read_op = opcode::Read::new(fd, data.as_mut_ptr(), len as u32).offset(offset);
-
-        unsafe {
-            ring.submission()
-                .push(&read_op.build().user_data(0))
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-        }
-
-        ring.submit_and_wait(1)?;
-
-        let cqe = ring
-            .completion()
-            .next()
-            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "No completion"))?;
-
-        let result = cqe.result();
-        if result < 0 {
-            return Err(std::io::Error::from_raw_os_error(-result));
-        }
-
-        if result as usize != len as usize {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::UnexpectedEof,
-                "Short read",
-            ));
+    /// Write `data` at `offset` by rewriting the aligned range covering it.
+    fn write_direct(&self, offset: u64, data: &[u8]) -> Result<()> {
+        let alignment = self.buffer_pool.alignment();
+        let start = align_down(offset, alignment);
+        let end = align_up(offset + data.len() as u64, alignment);
+        let mut buffer = self.buffer_pool.acquire((end - start) as usize);
+
+        // Keep the bytes around the written range, past the end of the file is padding
+        let read = self.io.read_at(start, &mut buffer)?;
+        buffer[read..].fill(0);
+        let skip = (offset - start) as usize;
+        buffer[skip..skip + data.len()].copy_from_slice(data);
+        self.io.write_at(start, &buffer)?;
+
+        // Cut the padding written past the end of the file
+        let file_end = (start + read as u64).max(offset + data.len() as u64);
+        if end > file_end {
+            self.io.set_len(file_end)?;
         }
-
-        Ok(data)
-    }
-
-    #[cfg(target_os = "linux")]
-    fn write_uring_single(&self, offset: u64, data: &[u8]) -> Result<()> {
-        let fd = types::Fd(self.file.as_raw_fd());
-        let ring = self.io_uring.as_ref().unwrap();
-        let mut ring = ring.lock().unwrap();
-
-        let write_op = opcode::Write::new(fd, data.as_ptr(), data.len() as u32).offset(offset);
-
-        unsafe {
-            ring.submission()
-                .push(&write_op.build().user_data(0))
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-        }
-
-        ring.submit_and_wait(1)?;
-
-        let cqe = ring
-            .completion()
-            .next()
-            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "No completion"))?;
-
-        let result = cqe.result();
-        if result < 0 {
-            return Err(std::io::Error::from_raw_os_error(-result));
-        }
-
-        if result as usize != data.len() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::WriteZero,
-                "Short write",
-            ));
-        }
-
         Ok(())
     }
 
-    #[cfg(target_os = "linux")]
-    fn read_batch_uring(&self, requests: &[(u64, u64)]) -> Result<Vec<Vec<u8>>> {
-        let fd = types::Fd(self.file.as_raw_fd());
-        let ring = self.io_uring.as_ref().unwrap();
-        let mut ring = ring.lock().unwrap();
-
-        let mut buffers: Vec<Vec<u8>> = requests
-            .iter()
-            .map(|(_, len)| vec![0u8; *len as usize])
-            .collect();
-
-        // Submit all read operations
-        unsafe {
-            let mut sq = ring.submission();
-            for (i, ((offset, len), buf)) in requests.iter().zip(buffers.iter_mut()).enumerate() {
-                let read_op = opcode::Read::new(fd, buf.as_mut_ptr(), *len as u32).offset(*offset);
-
-                sq.push(&read_op.build().user_data(i as u64))
-                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-            }
-        }
-
-        ring.submit_and_wait(requests.len())?;
-
-        // Collect results
-        let mut results = vec![None; requests.len()];
-        for _ in 0..requests.len() {
-            if let Some(cqe) = ring.completion().next() {
-                let idx = cqe.user_data() as usize;
-                let result = cqe.result();
-
-                if result < 0 {
-                    return Err(std::io::Error::from_raw_os_error(-result));
-                }
-
-                results[idx] = Some(result);
-            }
+    /// Batch read through aligned buffers covering the requests.
+    fn read_batch_direct(&self, requests: &[(u64, u64)]) -> Result<Vec<Vec<u8>>> {
+        let alignment = self.buffer_pool.alignment();
+        let mut offsets = Vec::with_capacity(requests.len());
+        let mut buffers = Vec::with_capacity(requests.len());
+        for (offset, len) in requests {
+            let start = align_down(*offset, alignment);
+            let end = align_up(offset + len, alignment);
+            offsets.push(start);
+            buffers.push(self.buffer_pool.acquire((end - start) as usize));
         }
 
-        // Verify all completed
-        for (i, result) in results.iter().enumerate() {
-            match result {
-                Some(n) if *n as usize == requests[i].1 as usize => {}
-                _ => {
+        let mut slices: Vec<&mut [u8]> = buffers.iter_mut().map(|buf| &mut **buf).collect();
+        let reads = self.io.read_batch_at(&offsets, &mut slices)?;
+        requests
+            .iter()
+            .enumerate()
+            .map(|(i, (offset, len))| {
+                let skip = (offset - offsets[i]) as usize;
+                if reads[i] < skip + *len as usize {
                     return Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
+                        ErrorKind::Other,
                         format!("Read failed for request {}", i),
                     ));
                 }
-            }
-        }
-
-        Ok(buffers)
-    }
-
-    #[cfg(target_os = "linux")]
-    fn write_batch_uring(&self, requests: &[(u64, Vec<u8>)]) -> Result<()> {
-        let fd = types::Fd(self.file.as_raw_fd());
-        let ring = self.io_uring.as_ref().unwrap();
-        let mut ring = ring.lock().unwrap();
-
-        // Submit all write operations
-        unsafe {
-            let mut sq = ring.submission();
-            for (i, (offset, data)) in requests.iter().enumerate() {
-                let write_op =
-                    opcode::Write::new(fd, data.as_ptr(), data.len() as u32).offset(*offset);
-
-                sq.push(&write_op.build().user_data(i as u64))
-                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-            }
-        }
-
-        ring.submit_and_wait(requests.len())?;
-
-        // Collect results
-        for _ in 0..requests.len() {
-            if let Some(cqe) = ring.completion().next() {
-                let result = cqe.result();
-                if result < 0 {
-                    return Err(std::io::Error::from_raw_os_error(-result));
-                }
-            }
-        }
-
-        Ok(())
-    }
-
-    #[cfg(target_os = "macos")]
-    fn new_macos(path: &Path, options: DiskManagerOptions) -> Result<Self> {
-        let file = File::options().read(true).write(true).open(path)?;
-
-        if options.use_direct_io {
-            unsafe {
-                let fd = file.as_raw_fd();
-                // F_NOCACHE: disable caching
-                libc::fcntl(fd, 48, 1);
-                // F_RDAHEAD: disable read-ahead for random access
-                libc::fcntl(fd, 45, 0);
-            }
-        }
-
-        let size = file.metadata()?.len();
-
-        Ok(Self { file, size })
-    }
-
-    #[cfg(target_os = "macos")]
-    fn create_macos(path: &Path, data: &[u8], options: DiskManagerOptions) -> Result<Self> {
-        use std::io::Write;
-
-        let mut write_file = File::create(path)?;
-
-        if options.use_direct_io {
-            unsafe {
-                let fd = write_file.as_raw_fd();
-                libc::fcntl(fd, 48, 1); // F_NOCACHE
-            }
-        }
-
-        write_file.write_all(data)?;
-        write_file.sync_all()?;
-        drop(write_file);
-
-        Self::new_macos(path, options)
-    }
-
-    #[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
-    fn new_unix(path: &Path, options: DiskManagerOptions) -> Result<Self> {
-        use std::os::unix::fs::OpenOptionsExt;
-
-        let mut opts = OpenOptions::new();
-        opts.read(true).write(true);
-
-        if options.use_direct_io {
-            opts.custom_flags(libc::O_DIRECT);
-        }
-
-        let file = opts.open(path)?;
-        let size = file.metadata()?.len();
-
-        Ok(Self { file, size })
-    }
-
-    #[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
-    fn create_unix(path: &Path, data: &[u8], options: DiskManagerOptions) -> Result<Self> {
-        use std::io::Write;
-        use std::os::unix::fs::OpenOptionsExt;
-
-        let mut write_opts = OpenOptions::new();
-        write_opts.write(true).create(true).truncate(true);
-
-        if options.use_direct_io {
-            write_opts.custom_flags(libc::O_DIRECT | libc::O_SYNC);
-        }
-
-        let mut write_file = write_opts.open(path)?;
-        write_file.write_all(data)?;
-        write_file.sync_all()?;
-        drop(write_file);
-
-        Self::new_unix(path, options)
-    }
-
-    #[cfg(windows)]
-    fn new_windows(path: &Path, options: DiskManagerOptions) -> Result<Self> {
-        use std::{fs::OpenOptions, os::windows::fs::OpenOptionsExt};
-
-        let mut opts = OpenOptions::new();
-        opts.read(true).write(true);
-
-        if options.use_direct_io {
-            use winapi::um::winbase::FILE_FLAG_NO_BUFFERING;
-            opts.custom_flags(FILE_FLAG_NO_BUFFERING);
-        }
-
-        let file = opts.open(path)?;
-        let size = file.metadata()?.len();
-
-        Ok(Self { file, size })
-    }
-
-    #[cfg(windows)]
-    fn create_windows(path: &Path, data: &[u8], options: DiskManagerOptions) -> Result<Self> {
-        use std::os::windows::fs::OpenOptionsExt;
-        use std::{fs::OpenOptions, io::Write};
-        use winapi::um::winbase::{FILE_FLAG_NO_BUFFERING, FILE_FLAG_WRITE_THROUGH};
-
-        let mut write_opts = OpenOptions::new();
-        write_opts.write(true).create(true).truncate(true);
-
-        if options.use_direct_io {
-            write_opts.custom_flags(FILE_FLAG_NO_BUFFERING | FILE_FLAG_WRITE_THROUGH);
-        }
-
-        let mut write_file = write_opts.open(path)?;
-        write_file.write_all(data)?;
-        write_file.sync_all()?;
-        drop(write_file);
-
-        Self::new_windows(path, options)
-    }
-
-    fn read_sync(&self, offset: u64, len: u64) -> Result<Vec<u8>> {
-        let mut data = vec![0; len as usize];
-
-        #[cfg(unix)]
-        {
-            self.file.read_exact_at(&mut data, offset)?;
-        }
-
-        #[cfg(windows)]
-        {
-            self.file.seek_read(&mut data, offset)?;
-        }
-
-        Ok(data)
-    }
-
-    fn write_sync(&self, offset: u64, data: &[u8]) -> Result<()> {
-        #[cfg(unix)]
-        {
-            self.file.write_all_at(data, offset)?;
-        }
-
-        #[cfg(windows)]
-        {
-            use std::io::{Seek, SeekFrom, Write};
-            let mut file = &self.file;
-            file.seek(SeekFrom::Start(offset))?;
-            file.write_all(data)?;
-        }
-
-        Ok(())
+                Ok(buffers[i][skip..skip + *len as usize].to_vec())
+            })
+            .collect()
     }
 }
 
@@ -543,6 +261,9 @@ pub struct DiskManagerPool {
     managers: Vec<BktDiskManager>,
     #[cfg(target_os = "linux")]
     shared_ring: Option<std::sync::Arc<std::sync::Mutex<IoUring>>>,
+    /// Descriptors of the files submitted to the shared ring
+    #[cfg(target_os = "linux")]
+    fds: Vec<RawFd>,
 }
 
 impl DiskManagerPool {
@@ -559,23 +280,25 @@ impl DiskManagerPool {
                 options.queue_depth,
             )?)));
 
+            // Buffered, the batched reads of the pool are not aligned
+            let options = DiskManagerOptions {
+                use_direct_io: false,
+                ..options
+            };
+            let mut fds = Vec::with_capacity(paths.len());
             let managers = paths
                 .iter()
                 .map(|path| {
-                    let file = File::options().read(true).write(true).open(path)?;
-                    let size = file.metadata()?.len();
-
-                    Ok(BktDiskManager {
-                        file,
-                        size,
-                        io_uring: shared_ring.clone(),
-                    })
+                    let io = UringIo::with_ring(path, options, shared_ring.clone().unwrap())?;
+                    fds.push(io.as_raw_fd());
+                    Ok(BktDiskManager::from_io(Box::new(io)))
                 })
                 .collect::<Result<Vec<_>>>()?;
 
             Ok(Self {
                 managers,
                 shared_ring,
+                fds,
             })
         }
 
@@ -607,43 +330,36 @@ impl DiskManagerPool {
             .collect()
     }
 
+    /// Read every request through the shared ring, in rounds no larger than its queue.
+    ///
+    /// A short buffered read is finished by the disk manager of its file, a read past the
+    /// end of the file fails.
     #[cfg(target_os = "linux")]
     fn read_batch_multi_uring(
         &self,
         requests: &[(usize, u64, u64)],
-        ring: &std::sync::Arc<std::sync::Mutex<IoUring>>,
+        ring: &std::sync::Mutex<IoUring>,
     ) -> Result<Vec<Vec<u8>>> {
-        let mut ring = ring.lock().unwrap();
         let mut buffers: Vec<Vec<u8>> = requests
             .iter()
             .map(|(_, _, len)| vec![0u8; *len as usize])
             .collect();
-
-        // Submit all operations
-        unsafe {
-            let mut sq = ring.submission();
-            for (i, ((file_idx, offset, len), buf)) in
-                requests.iter().zip(buffers.iter_mut()).enumerate()
-            {
-                let fd = types::Fd(self.managers[*file_idx].file.as_raw_fd());
-                let read_op = opcode::Read::new(fd, buf.as_mut_ptr(), *len as u32).offset(*offset);
-
-                sq.push(&read_op.build().user_data(i as u64))
-                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        let buffer_ptrs: Vec<*mut u8> = buffers.iter_mut().map(|buf| buf.as_mut_ptr()).collect();
+        let reads = submit_all_on(ring, requests.len(), |i| {
+            let (file_idx, offset, len) = requests[i];
+            let fd = types::Fd(self.fds[file_idx]);
+            opcode::Read::new(fd, buffer_ptrs[i], len as u32)
+                .offset(offset)
+                .build()
+        })?;
+
+        for ((&(file_idx, offset, _), buf), read) in requests.iter().zip(&mut buffers).zip(reads) {
+            if read < buf.len() {
+                let rest = self.managers[file_idx]
+                    .read(offset + read as u64, (buf.len() - read) as u64)?;
+                buf[read..].copy_from_slice(&rest);
             }
         }
-
-        ring.submit_and_wait(requests.len())?;
-
-        // Collect completions
-        for _ in 0..requests.len() {
-            if let Some(cqe) = ring.completion().next() {
-                if cqe.result() < 0 {
-                    return Err(std::io::Error::from_raw_os_error(-cqe.result()));
-                }
-            }
-        }
-
         Ok(buffers)
     }
 
@@ -0,0 +1,265 @@
+use std::fmt::Debug;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Result};
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::bucket::disk_manager::DiskManagerOptions;
+pub use crate::config::IoBackend;
+
+#[cfg(any(test, feature = "fault-injection"))]
+pub mod fault;
+pub mod memory;
+#[cfg(unix)]
+pub mod mmap;
+pub mod pread;
+#[cfg(target_os = "linux")]
+pub mod uring;
+
+#[cfg(any(test, feature = "fault-injection"))]
+pub use fault::{FaultInjectionIo, FaultInjectionWriter, FaultInjector, FaultPoint};
+pub use memory::MemoryIo;
+#[cfg(unix)]
+pub use mmap::MmapIo;
+pub use pread::PreadIo;
+#[cfg(target_os = "linux")]
+pub use uring::UringIo;
+
+/// Positional I/O on a single bucket file.
+///
+/// Implementations only move bytes, the alignment of direct I/O is handled by
+/// [`BktDiskManager`](crate::bucket::disk_manager::BktDiskManager) on top of them.
+pub trait BucketIo: Send + Sync + Debug {
+    /// Current size of the file in bytes.
+    fn size(&self) -> u64;
+
+    /// Read into `buf` at `offset`, returning the bytes read, short at the end of the file.
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize>;
+
+    /// Write all of `data` at `offset`, extending the file if needed.
+    fn write_at(&self, offset: u64, data: &[u8]) -> Result<()>;
+
+    /// Truncate or extend the file to `len` bytes.
+    fn set_len(&self, len: u64) -> Result<()>;
+
+    /// Read every buffer at its offset, returning the bytes read into each.
+    fn read_batch_at(&self, offsets: &[u64], buffers: &mut [&mut [u8]]) -> Result<Vec<usize>> {
+        offsets
+            .iter()
+            .zip(buffers.iter_mut())
+            .map(|(offset, buf)| self.read_at(*offset, buf))
+            .collect()
+    }
+
+    /// Write every `(offset, data)` pair.
+    fn write_batch_at(&self, requests: &[(u64, Vec<u8>)]) -> Result<()> {
+        for (offset, data) in requests {
+            self.write_at(*offset, data)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the file bypasses the OS cache, reads and writes must then be aligned.
+    fn direct_io(&self) -> bool {
+        false
+    }
+
+    /// Stop bypassing the OS cache after the filesystem rejected direct I/O with `error`.
+    fn disable_direct_io(&self, error: std::io::Error) -> Result<()> {
+        Err(error)
+    }
+}
+
+/// Open an existing bucket file with the backend of the options.
+pub fn open(path: &Path, options: DiskManagerOptions) -> Result<Box<dyn BucketIo>> {
+    #[cfg(any(test, feature = "fault-injection"))]
+    if let Some(injector) = FaultInjector::find(path) {
+        let inner = open_backend(path, buffered(options))?;
+        return Ok(Box::new(FaultInjectionIo::new(inner, path, injector)?));
+    }
+    open_backend(path, options)
+}
+
+fn open_backend(path: &Path, options: DiskManagerOptions) -> Result<Box<dyn BucketIo>> {
+    match options.backend {
+        #[cfg(target_os = "linux")]
+        IoBackend::Uring => Ok(Box::new(UringIo::open(path, options)?)),
+        #[cfg(unix)]
+        IoBackend::Mmap => Ok(Box::new(MmapIo::open(path, options)?)),
+        IoBackend::Memory => Ok(Box::new(MemoryIo::open(path, options.read_only)?)),
+        _ => Ok(Box::new(PreadIo::open(path, options)?)),
+    }
+}
+
+/// Create a bucket file holding `data`, replacing any previous one, and open it.
+pub fn create(path: &Path, data: &[u8], options: DiskManagerOptions) -> Result<Box<dyn BucketIo>> {
+    // Written through the injector, so that a crash can tear the new file
+    #[cfg(any(test, feature = "fault-injection"))]
+    if let Some(injector) = FaultInjector::find(path) {
+        let inner = create_backend(path, &[], buffered(options))?;
+        let io = FaultInjectionIo::new(inner, path, injector)?;
+        io.write_at(0, data)?;
+        return Ok(Box::new(io));
+    }
+    create_backend(path, data, options)
+}
+
+fn create_backend(
+    path: &Path,
+    data: &[u8],
+    options: DiskManagerOptions,
+) -> Result<Box<dyn BucketIo>> {
+    if options.backend == IoBackend::Memory {
+        return Ok(Box::new(MemoryIo::create(path, data)));
+    }
+    write_new_file(path, data)?;
+
+    // Drop the written pages from the cache, later reads bypass it
+    #[cfg(target_os = "linux")]
+    if options.use_direct_io && options.backend != IoBackend::Mmap {
+        use std::os::unix::io::AsRawFd;
+
+        let file = File::open(path)?;
+        unsafe {
+            libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED);
+        }
+    }
+
+    open_backend(path, options)
+}
+
+/// The options without direct I/O, for files under a fault injector.
+#[cfg(any(test, feature = "fault-injection"))]
+fn buffered(options: DiskManagerOptions) -> DiskManagerOptions {
+    DiskManagerOptions {
+        use_direct_io: false,
+        ..options
+    }
+}
+
+/// Identity of a file's content as the backend sees it.
+///
+/// A file replaced by a rename keeps neither its inode nor its modification time, even if
+/// the new file has the same size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    len: u64,
+    inode: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Stamp of the file at `path`, changed whenever the file is rewritten or replaced.
+pub fn file_stamp(path: &Path, backend: IoBackend) -> Result<FileStamp> {
+    if backend == IoBackend::Memory {
+        let (len, inode) = MemoryIo::stamp(path).ok_or_else(|| {
+            std::io::Error::new(
+                ErrorKind::NotFound,
+                format!("No memory file at {}", path.display()),
+            )
+        })?;
+        return Ok(FileStamp {
+            len,
+            inode,
+            modified: None,
+        });
+    }
+    let metadata = std::fs::metadata(path)?;
+    #[cfg(unix)]
+    let inode = std::os::unix::fs::MetadataExt::ino(&metadata);
+    #[cfg(not(unix))]
+    let inode = 0;
+    Ok(FileStamp {
+        len: metadata.len(),
+        inode,
+        modified: metadata.modified().ok(),
+    })
+}
+
+/// Write a whole new file and sync it.
+///
+/// The file is written buffered, a bucket is rarely a multiple of the direct I/O alignment.
+fn write_new_file(path: &Path, data: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut write_file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    write_file.write_all(data)?;
+    write_file.sync_all()
+}
+
+/// Open a file for positional I/O, returning whether it bypasses the OS cache with aligned
+/// direct I/O.
+///
+/// - Linux and other Unix: `O_DIRECT`, dropped if the filesystem rejects it, e.g. tmpfs
+/// - macOS: `F_NOCACHE`, which needs no alignment
+/// - Windows: `FILE_FLAG_NO_BUFFERING`
+pub(crate) fn open_file(path: &Path, options: DiskManagerOptions) -> Result<(File, bool)> {
+    let mut opts = OpenOptions::new();
+    opts.read(true).write(!options.read_only);
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        if options.use_direct_io {
+            match opts.clone().custom_flags(libc::O_DIRECT).open(path) {
+                Err(error) if error.kind() == ErrorKind::InvalidInput => {}
+                result => return Ok((result?, true)),
+            }
+        }
+        Ok((opts.open(path)?, false))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::os::unix::io::AsRawFd;
+
+        let file = opts.open(path)?;
+        if options.use_direct_io {
+            unsafe {
+                let fd = file.as_raw_fd();
+                // F_NOCACHE: disable caching
+                libc::fcntl(fd, 48, 1);
+                // F_RDAHEAD: disable read-ahead for random access
+                libc::fcntl(fd, 45, 0);
+            }
+        }
+        Ok((file, false))
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        use winapi::um::winbase::FILE_FLAG_NO_BUFFERING;
+
+        if options.use_direct_io {
+            opts.custom_flags(FILE_FLAG_NO_BUFFERING);
+        }
+        Ok((opts.open(path)?, options.use_direct_io))
+    }
+}
+
+/// Clear `O_DIRECT` on an open file, see [`BucketIo::disable_direct_io`].
+pub(crate) fn disable_direct_io(file: &File, error: std::io::Error) -> Result<()> {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        use std::os::unix::io::AsRawFd;
+
+        let fd = file.as_raw_fd();
+        let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+        if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_DIRECT) } < 0 {
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    // The cache flags of other platforms cannot be cleared on an open file
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    {
+        let _ = file;
+        Err(error)
+    }
+}
@@ -0,0 +1,316 @@
+use std::io::{ErrorKind, Result, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use parking_lot::Mutex;
+
+use crate::bucket::io::BucketIo;
+
+/// Kind of file operation a fault is injected into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultPoint {
+    /// Opening or creating a file.
+    Open,
+    /// A read.
+    Read,
+    /// A write.
+    Write,
+    /// A truncation or extension.
+    SetLen,
+}
+
+/// Faults injected into the bucket files under a directory, for crash and corruption tests.
+///
+/// Only built for tests and with the `fault-injection` feature. An injector is attached to
+/// a directory with [`FaultInjector::attach`]. From then on, every bucket file opened or
+/// created under the directory, with any backend, goes through a [`FaultInjectionIo`] driven
+/// by the injector, and the vertex index through a [`FaultInjectionWriter`]. Files under an
+/// injector never use direct I/O, so that a torn write can stop at any byte.
+///
+/// The faults are:
+/// - failure points: operations of a kind fail after a number of successful ones
+/// - short writes: a write persists only a prefix of its data, then fails
+/// - bit flips: reads of a file return a byte with a flipped bit
+/// - crash after N bytes: the write crossing the limit is cut at it, then every operation
+///   fails, as if the process died, until [`FaultInjector::reset`]
+#[derive(Debug, Default)]
+pub struct FaultInjector {
+    state: Mutex<FaultState>,
+}
+
+#[derive(Debug, Default)]
+struct FaultState {
+    failures: Vec<Failure>,
+    short_writes: Vec<ShortWrite>,
+    bit_flips: Vec<BitFlip>,
+    /// Bytes left to write before the simulated crash.
+    crash_budget: Option<u64>,
+    crashed: bool,
+    bytes_written: u64,
+}
+
+#[derive(Debug)]
+struct Failure {
+    point: FaultPoint,
+    /// Operations to let through before failing.
+    skip: u64,
+    /// Operations left to fail.
+    count: u64,
+}
+
+#[derive(Debug)]
+struct ShortWrite {
+    /// Writes to let through before tearing one.
+    skip: u64,
+    /// Bytes of the torn write that persist.
+    keep: usize,
+}
+
+#[derive(Debug)]
+struct BitFlip {
+    path: PathBuf,
+    offset: u64,
+    mask: u8,
+}
+
+/// Injectors attached to directories.
+fn injectors() -> &'static Mutex<Vec<(PathBuf, Arc<FaultInjector>)>> {
+    static INJECTORS: OnceLock<Mutex<Vec<(PathBuf, Arc<FaultInjector>)>>> = OnceLock::new();
+    INJECTORS.get_or_init(Default::default)
+}
+
+impl FaultInjector {
+    /// Attach a new injector to the bucket files under `dir`, replacing any previous one.
+    pub fn attach(dir: &Path) -> Arc<Self> {
+        let injector = Arc::new(Self::default());
+        let mut injectors = injectors().lock();
+        injectors.retain(|(attached, _)| attached != dir);
+        injectors.push((dir.to_path_buf(), injector.clone()));
+        injector
+    }
+
+    /// Detach the injector of `dir`. Files already opened keep it.
+    pub fn detach(dir: &Path) {
+        injectors().lock().retain(|(attached, _)| attached != dir);
+    }
+
+    /// The injector of the directory holding `path`, if any.
+    pub fn find(path: &Path) -> Option<Arc<Self>> {
+        injectors()
+            .lock()
+            .iter()
+            .find(|(dir, _)| path.starts_with(dir))
+            .map(|(_, injector)| injector.clone())
+    }
+
+    /// Fail `count` operations of kind `point` after letting `skip` of them through.
+    pub fn fail(&self, point: FaultPoint, skip: u64, count: u64) {
+        self.state
+            .lock()
+            .failures
+            .push(Failure { point, skip, count });
+    }
+
+    /// Tear the write after `skip` successful ones, only its first `keep` bytes persist.
+    pub fn short_write(&self, skip: u64, keep: usize) {
+        self.state
+            .lock()
+            .short_writes
+            .push(ShortWrite { skip, keep });
+    }
+
+    /// Flip the bits of `mask` in the byte at `offset` of `path` whenever it is read.
+    pub fn flip_bits(&self, path: &Path, offset: u64, mask: u8) {
+        self.state.lock().bit_flips.push(BitFlip {
+            path: path.to_path_buf(),
+            offset,
+            mask,
+        });
+    }
+
+    /// Crash once `bytes` more bytes have been written.
+    pub fn crash_after_bytes(&self, bytes: u64) {
+        self.state.lock().crash_budget = Some(bytes);
+    }
+
+    /// Whether the simulated crash happened.
+    pub fn is_crashed(&self) -> bool {
+        self.state.lock().crashed
+    }
+
+    /// Total bytes written through the injector.
+    pub fn bytes_written(&self) -> u64 {
+        self.state.lock().bytes_written
+    }
+
+    /// Clear every fault and the crash, as after a restart.
+    pub fn reset(&self) {
+        let mut state = self.state.lock();
+        let bytes_written = state.bytes_written;
+        *state = FaultState {
+            bytes_written,
+            ..Default::default()
+        };
+    }
+
+    /// Check an operation of kind `point` against the failure points.
+    fn check(&self, point: FaultPoint) -> Result<()> {
+        self.state.lock().check(point)
+    }
+
+    /// Check a write of `len` bytes, returning how many of them persist and whether the
+    /// write then fails.
+    fn check_write(&self, len: usize) -> Result<(usize, Option<std::io::Error>)> {
+        let mut state = self.state.lock();
+        state.check(FaultPoint::Write)?;
+
+        let mut keep = len;
+        let mut error = None;
+        if let Some(index) = state.short_writes.iter().position(|short| short.skip == 0) {
+            keep = keep.min(state.short_writes.remove(index).keep);
+            error = Some(injected("short write"));
+        }
+        for short in &mut state.short_writes {
+            short.skip = short.skip.saturating_sub(1);
+        }
+        if let Some(budget) = state.crash_budget {
+            if (keep as u64) >= budget && len > 0 {
+                keep = budget as usize;
+                state.crashed = true;
+                error = Some(crash());
+            } else {
+                state.crash_budget = Some(budget - keep as u64);
+            }
+        }
+        state.bytes_written += keep as u64;
+        Ok((keep, error))
+    }
+
+    /// Flip the injected bits of the bytes of `path` read into `buf` at `offset`.
+    fn corrupt(&self, path: &Path, offset: u64, buf: &mut [u8]) {
+        let state = self.state.lock();
+        for flip in &state.bit_flips {
+            if flip.path == path && flip.offset >= offset && flip.offset < offset + buf.len() as u64
+            {
+                buf[(flip.offset - offset) as usize] ^= flip.mask;
+            }
+        }
+    }
+}
+
+impl FaultState {
+    fn check(&mut self, point: FaultPoint) -> Result<()> {
+        if self.crashed {
+            return Err(crash());
+        }
+        let mut result = Ok(());
+        for failure in self.failures.iter_mut().filter(|f| f.point == point) {
+            if failure.skip > 0 {
+                failure.skip -= 1;
+            } else if failure.count > 0 && result.is_ok() {
+                failure.count -= 1;
+                result = Err(injected(&format!("{:?} failure", point)));
+            }
+        }
+        self.failures.retain(|failure| failure.count > 0);
+        result
+    }
+}
+
+fn injected(what: &str) -> std::io::Error {
+    std::io::Error::new(ErrorKind::Other, format!("Injected {}", what))
+}
+
+fn crash() -> std::io::Error {
+    std::io::Error::new(ErrorKind::Other, "Injected crash")
+}
+
+/// A bucket file whose operations go through a [`FaultInjector`].
+#[derive(Debug)]
+pub struct FaultInjectionIo {
+    inner: Box<dyn BucketIo>,
+    path: PathBuf,
+    injector: Arc<FaultInjector>,
+}
+
+impl FaultInjectionIo {
+    /// Wrap the opened file at `path`, checking the open against the injector.
+    pub fn new(
+        inner: Box<dyn BucketIo>,
+        path: &Path,
+        injector: Arc<FaultInjector>,
+    ) -> Result<Self> {
+        injector.check(FaultPoint::Open)?;
+        Ok(Self {
+            inner,
+            path: path.to_path_buf(),
+            injector,
+        })
+    }
+}
+
+impl BucketIo for FaultInjectionIo {
+    fn size(&self) -> u64 {
+        self.inner.size()
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        self.injector.check(FaultPoint::Read)?;
+        let read = self.inner.read_at(offset, buf)?;
+        self.injector.corrupt(&self.path, offset, &mut buf[..read]);
+        Ok(read)
+    }
+
+    fn write_at(&self, offset: u64, data: &[u8]) -> Result<()> {
+        let (keep, error) = self.injector.check_write(data.len())?;
+        self.inner.write_at(offset, &data[..keep])?;
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    fn set_len(&self, len: u64) -> Result<()> {
+        self.injector.check(FaultPoint::SetLen)?;
+        self.inner.set_len(len)
+    }
+
+    fn direct_io(&self) -> bool {
+        self.inner.direct_io()
+    }
+
+    fn disable_direct_io(&self, error: std::io::Error) -> Result<()> {
+        self.inner.disable_direct_io(error)
+    }
+}
+
+/// A file written as a stream through a [`FaultInjector`], e.g. an index saved next to the
+/// bucket files.
+#[derive(Debug)]
+pub struct FaultInjectionWriter<W> {
+    inner: W,
+    injector: Arc<FaultInjector>,
+}
+
+impl<W: Write> FaultInjectionWriter<W> {
+    /// Wrap the created file `inner`, checking the open against the injector.
+    pub fn new(inner: W, injector: Arc<FaultInjector>) -> Result<Self> {
+        injector.check(FaultPoint::Open)?;
+        Ok(Self { inner, injector })
+    }
+}
+
+impl<W: Write> Write for FaultInjectionWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let (keep, error) = self.injector.check_write(buf.len())?;
+        self.inner.write_all(&buf[..keep])?;
+        match error {
+            Some(error) => Err(error),
+            None => Ok(keep),
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
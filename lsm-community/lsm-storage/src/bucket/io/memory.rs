@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::io::{ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use parking_lot::{Mutex, RwLock};
+
+use crate::bucket::io::BucketIo;
+
+type MemoryFile = Arc<RwLock<Vec<u8>>>;
+
+/// Files of the process, keyed by path.
+fn files() -> &'static Mutex<HashMap<PathBuf, MemoryFile>> {
+    static FILES: OnceLock<Mutex<HashMap<PathBuf, MemoryFile>>> = OnceLock::new();
+    FILES.get_or_init(Default::default)
+}
+
+/// A file kept in process memory, nothing reaches the disk.
+///
+/// Files live in a process-wide registry keyed by path, so a file created at a path can be
+/// opened again by that path until it is removed, like a file on disk but only within the
+/// process.
+#[derive(Debug)]
+pub struct MemoryIo {
+    data: MemoryFile,
+    read_only: bool,
+}
+
+impl MemoryIo {
+    /// Create a file holding `data` at `path`, replacing any previous one.
+    pub fn create(path: &Path, data: &[u8]) -> Self {
+        let data = Arc::new(RwLock::new(data.to_vec()));
+        files().lock().insert(path.to_path_buf(), data.clone());
+        Self {
+            data,
+            read_only: false,
+        }
+    }
+
+    /// Open the file created at `path`, writes fail if `read_only`.
+    pub fn open(path: &Path, read_only: bool) -> Result<Self> {
+        let data = files().lock().get(path).cloned().ok_or_else(|| {
+            std::io::Error::new(
+                ErrorKind::NotFound,
+                format!("No memory file at {}", path.display()),
+            )
+        })?;
+        Ok(Self { data, read_only })
+    }
+
+    /// Whether a file was created at `path`.
+    pub fn exists(path: &Path) -> bool {
+        files().lock().contains_key(path)
+    }
+
+    /// Size of the file at `path` and an id of the file, which changes when it is replaced.
+    pub fn stamp(path: &Path) -> Option<(u64, u64)> {
+        files()
+            .lock()
+            .get(path)
+            .map(|data| (data.read().len() as u64, Arc::as_ptr(data) as u64))
+    }
+
+    /// Paths of the files directly inside `dir`, sorted.
+    pub fn files_in(dir: &Path) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = files()
+            .lock()
+            .keys()
+            .filter(|path| path.parent() == Some(dir))
+            .cloned()
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Remove the file at `path`, returning whether it existed. Opened handles keep its data.
+    pub fn remove(path: &Path) -> bool {
+        files().lock().remove(path).is_some()
+    }
+
+    fn check_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(std::io::Error::new(
+                ErrorKind::PermissionDenied,
+                "Memory file opened read-only",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl BucketIo for MemoryIo {
+    fn size(&self) -> u64 {
+        self.data.read().len() as u64
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        let data = self.data.read();
+        let start = (offset as usize).min(data.len());
+        let read = buf.len().min(data.len() - start);
+        buf[..read].copy_from_slice(&data[start..start + read]);
+        Ok(read)
+    }
+
+    fn write_at(&self, offset: u64, bytes: &[u8]) -> Result<()> {
+        self.check_writable()?;
+        let mut data = self.data.write();
+        let end = offset as usize + bytes.len();
+        if end > data.len() {
+            data.resize(end, 0);
+        }
+        data[offset as usize..end].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn set_len(&self, len: u64) -> Result<()> {
+        self.check_writable()?;
+        self.data.write().resize(len as usize, 0);
+        Ok(())
+    }
+}
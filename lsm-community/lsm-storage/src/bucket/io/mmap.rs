@@ -0,0 +1,145 @@
+use std::fs::File;
+use std::io::Result;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::ptr::NonNull;
+
+use parking_lot::RwLock;
+
+use crate::bucket::disk_manager::DiskManagerOptions;
+use crate::bucket::io::{self, BucketIo, pread};
+
+/// Reads from a shared read-only memory map of the file, through the page cache.
+///
+/// Suited to read-mostly deployments: a read is a copy out of the map, without a system
+/// call once the pages are cached. Writes go through `pwrite`, the shared map sees them,
+/// and a change of the file size remaps it. Direct I/O is never used.
+#[derive(Debug)]
+pub struct MmapIo {
+    file: File,
+    map: RwLock<Mapping>,
+}
+
+impl MmapIo {
+    /// Open an existing file and map it.
+    pub fn open(path: &Path, options: DiskManagerOptions) -> Result<Self> {
+        let (file, _) = io::open_file(
+            path,
+            DiskManagerOptions {
+                use_direct_io: false,
+                ..options
+            },
+        )?;
+        let map = Mapping::new(&file, file.metadata()?.len())?;
+        Ok(Self {
+            file,
+            map: RwLock::new(map),
+        })
+    }
+
+    /// Remap the file after its size changed.
+    fn remap(&self) -> Result<()> {
+        let len = self.file.metadata()?.len();
+        let mut map = self.map.write();
+        if map.len != len {
+            *map = Mapping::new(&self.file, len)?;
+        }
+        Ok(())
+    }
+}
+
+impl BucketIo for MmapIo {
+    fn size(&self) -> u64 {
+        self.map.read().len
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        let map = self.map.read();
+        let bytes = map.as_slice();
+        let start = (offset.min(map.len)) as usize;
+        let read = buf.len().min(bytes.len() - start);
+        buf[..read].copy_from_slice(&bytes[start..start + read]);
+        Ok(read)
+    }
+
+    fn write_at(&self, offset: u64, data: &[u8]) -> Result<()> {
+        pread::write_file_at(&self.file, offset, data)?;
+        if offset + data.len() as u64 > self.map.read().len {
+            self.remap()?;
+        }
+        Ok(())
+    }
+
+    fn set_len(&self, len: u64) -> Result<()> {
+        // Unmap first, pages past the end of a shrunk file must not be touched
+        let mut map = self.map.write();
+        *map = Mapping::empty();
+        self.file.set_len(len)?;
+        *map = Mapping::new(&self.file, len)?;
+        Ok(())
+    }
+}
+
+/// A read-only shared mapping of a whole file, unmapped on drop.
+#[derive(Debug)]
+struct Mapping {
+    ptr: Option<NonNull<u8>>,
+    len: u64,
+}
+
+// The mapping is only read, and `MmapIo` guards remapping with its lock.
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    fn empty() -> Self {
+        Self { ptr: None, len: 0 }
+    }
+
+    fn new(file: &File, len: u64) -> Result<Self> {
+        // An empty file cannot be mapped
+        if len == 0 {
+            return Ok(Self::empty());
+        }
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len as usize,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        // Bucket reads hit blocks at random, read-ahead would waste the cache
+        unsafe {
+            libc::madvise(ptr, len as usize, libc::MADV_RANDOM);
+        }
+
+        Ok(Self {
+            ptr: NonNull::new(ptr as *mut u8),
+            len,
+        })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        match self.ptr {
+            Some(ptr) => unsafe { std::slice::from_raw_parts(ptr.as_ptr(), self.len as usize) },
+            None => &[],
+        }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if let Some(ptr) = self.ptr {
+            unsafe {
+                libc::munmap(ptr.as_ptr() as *mut libc::c_void, self.len as usize);
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::fs::File;
+use std::io::{ErrorKind, Result};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(unix)]
+use std::os::unix::fs::FileExt;
+#[cfg(windows)]
+use std::os::windows::fs::FileExt;
+
+use crate::bucket::disk_manager::DiskManagerOptions;
+use crate::bucket::io::{self, BucketIo};
+
+/// Positional reads and writes on a file, one system call each.
+///
+/// Works on every platform, with the platform cache bypass of [`io::open_file`].
+#[derive(Debug)]
+pub struct PreadIo {
+    file: File,
+    direct_io: AtomicBool,
+}
+
+impl PreadIo {
+    /// Open an existing file.
+    pub fn open(path: &Path, options: DiskManagerOptions) -> Result<Self> {
+        let (file, direct_io) = io::open_file(path, options)?;
+        Ok(Self {
+            file,
+            direct_io: AtomicBool::new(direct_io),
+        })
+    }
+}
+
+impl BucketIo for PreadIo {
+    fn size(&self) -> u64 {
+        self.file
+            .metadata()
+            .map(|metadata| metadata.len())
+            .unwrap_or(0)
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        read_file_at(&self.file, offset, buf)
+    }
+
+    fn write_at(&self, offset: u64, data: &[u8]) -> Result<()> {
+        write_file_at(&self.file, offset, data)
+    }
+
+    fn set_len(&self, len: u64) -> Result<()> {
+        self.file.set_len(len)
+    }
+
+    fn direct_io(&self) -> bool {
+        self.direct_io.load(Ordering::Relaxed)
+    }
+
+    fn disable_direct_io(&self, error: std::io::Error) -> Result<()> {
+        io::disable_direct_io(&self.file, error)?;
+        self.direct_io.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Read into `buf` at `offset` until it is full or the file ends.
+pub(crate) fn read_file_at(file: &File, offset: u64, buf: &mut [u8]) -> Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        #[cfg(unix)]
+        let result = file.read_at(&mut buf[read..], offset + read as u64);
+        #[cfg(windows)]
+        let result = file.seek_read(&mut buf[read..], offset + read as u64);
+
+        match result {
+            // End of the file
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(read)
+}
+
+/// Write all of `data` at `offset`.
+pub(crate) fn write_file_at(file: &File, offset: u64, data: &[u8]) -> Result<()> {
+    #[cfg(unix)]
+    {
+        file.write_all_at(data, offset)
+    }
+
+    #[cfg(windows)]
+    {
+        use std::io::{Seek, SeekFrom, Write};
+        let mut file = file;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(data)
+    }
+}
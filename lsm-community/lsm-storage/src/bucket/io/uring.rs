@@ -0,0 +1,222 @@
+use std::fs::File;
+use std::io::{ErrorKind, Result};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use io_uring::{IoUring, opcode, types};
+
+use crate::bucket::disk_manager::DiskManagerOptions;
+use crate::bucket::io::{self, BucketIo};
+
+/// Linux io_uring, a batch of reads or writes goes out in one submission.
+///
+/// The ring may be shared by several files, see [`UringIo::with_ring`].
+#[derive(Debug)]
+pub struct UringIo {
+    file: File,
+    direct_io: AtomicBool,
+    ring: Arc<Mutex<IoUring>>,
+}
+
+impl UringIo {
+    /// Open an existing file with a ring of its own.
+    pub fn open(path: &Path, options: DiskManagerOptions) -> Result<Self> {
+        let ring = Arc::new(Mutex::new(IoUring::new(options.queue_depth)?));
+        Self::with_ring(path, options, ring)
+    }
+
+    /// Open an existing file that submits to `ring`.
+    pub fn with_ring(
+        path: &Path,
+        options: DiskManagerOptions,
+        ring: Arc<Mutex<IoUring>>,
+    ) -> Result<Self> {
+        let (file, direct_io) = io::open_file(path, options)?;
+
+        // Advise kernel for random access (typical for LSM-Tree)
+        unsafe {
+            libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_RANDOM);
+        }
+
+        Ok(Self {
+            file,
+            direct_io: AtomicBool::new(direct_io),
+            ring,
+        })
+    }
+
+    /// Submit one operation per entry to the ring of the file, see [`submit_all_on`].
+    pub(crate) fn submit_all(
+        &self,
+        len: usize,
+        entry: impl FnMut(usize) -> io_uring::squeue::Entry,
+    ) -> Result<Vec<usize>> {
+        submit_all_on(&self.ring, len, entry)
+    }
+
+    /// Write every `(offset, data)` pair in one submission, short buffered writes are
+    /// finished with `pwrite`.
+    fn write_slices(&self, requests: &[(u64, &[u8])]) -> Result<()> {
+        let fd = types::Fd(self.file.as_raw_fd());
+        let writes = self.submit_all(requests.len(), |i| {
+            let (offset, data) = requests[i];
+            opcode::Write::new(fd, data.as_ptr(), data.len() as u32)
+                .offset(offset)
+                .build()
+        })?;
+
+        for ((offset, data), written) in requests.iter().zip(writes) {
+            if written == data.len() {
+                continue;
+            }
+            if self.direct_io() {
+                return Err(std::io::Error::new(ErrorKind::WriteZero, "Short write"));
+            }
+            io::pread::write_file_at(&self.file, offset + written as u64, &data[written..])?;
+        }
+        Ok(())
+    }
+}
+
+impl AsRawFd for UringIo {
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+impl BucketIo for UringIo {
+    fn size(&self) -> u64 {
+        self.file
+            .metadata()
+            .map(|metadata| metadata.len())
+            .unwrap_or(0)
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        Ok(self.read_batch_at(&[offset], &mut [buf])?[0])
+    }
+
+    fn write_at(&self, offset: u64, data: &[u8]) -> Result<()> {
+        self.write_slices(&[(offset, data)])
+    }
+
+    fn set_len(&self, len: u64) -> Result<()> {
+        self.file.set_len(len)
+    }
+
+    fn read_batch_at(&self, offsets: &[u64], buffers: &mut [&mut [u8]]) -> Result<Vec<usize>> {
+        let fd = types::Fd(self.file.as_raw_fd());
+        let reads = self.submit_all(offsets.len(), |i| {
+            let buf = &mut *buffers[i];
+            opcode::Read::new(fd, buf.as_mut_ptr(), buf.len() as u32)
+                .offset(offsets[i])
+                .build()
+        })?;
+
+        // A read is short at the end of the file, or if the kernel split a buffered read
+        reads
+            .into_iter()
+            .enumerate()
+            .map(|(i, read)| {
+                if read == 0 || read == buffers[i].len() || self.direct_io() {
+                    return Ok(read);
+                }
+                let rest = io::pread::read_file_at(
+                    &self.file,
+                    offsets[i] + read as u64,
+                    &mut buffers[i][read..],
+                )?;
+                Ok(read + rest)
+            })
+            .collect()
+    }
+
+    fn write_batch_at(&self, requests: &[(u64, Vec<u8>)]) -> Result<()> {
+        let requests: Vec<(u64, &[u8])> = requests
+            .iter()
+            .map(|(offset, data)| (*offset, data.as_slice()))
+            .collect();
+        self.write_slices(&requests)
+    }
+
+    fn direct_io(&self) -> bool {
+        self.direct_io.load(Ordering::Relaxed)
+    }
+
+    fn disable_direct_io(&self, error: std::io::Error) -> Result<()> {
+        io::disable_direct_io(&self.file, error)?;
+        self.direct_io.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Submit one operation per entry to `ring` in rounds no larger than its submission queue,
+/// returning the result of each.
+///
+/// If an operation fails, the rest of its round is still reaped before the first error
+/// is returned, so no completion is left behind in a shared ring.
+pub(crate) fn submit_all_on(
+    ring: &Mutex<IoUring>,
+    len: usize,
+    mut entry: impl FnMut(usize) -> io_uring::squeue::Entry,
+) -> Result<Vec<usize>> {
+    let mut ring = ring.lock().unwrap();
+    let mut results = vec![None; len];
+
+    let queue_capacity = (ring.params().sq_entries() as usize).max(1);
+    for round_start in (0..len).step_by(queue_capacity) {
+        let round_end = (round_start + queue_capacity).min(len);
+        let mut first_error = None;
+
+        // Submit all operations of this round
+        let mut submitted = 0;
+        unsafe {
+            let mut sq = ring.submission();
+            for i in round_start..round_end {
+                if let Err(e) = sq.push(&entry(i).user_data(i as u64)) {
+                    first_error = Some(std::io::Error::new(ErrorKind::Other, e));
+                    break;
+                }
+                submitted += 1;
+            }
+        }
+
+        // Collect results of every submitted operation
+        let mut completed = 0;
+        while completed < submitted {
+            match ring.submit_and_wait(submitted - completed) {
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                    break;
+                }
+                Ok(_) => {}
+            }
+            for cqe in ring.completion() {
+                completed += 1;
+                let result = cqe.result();
+                if result < 0 {
+                    first_error.get_or_insert(std::io::Error::from_raw_os_error(-result));
+                } else {
+                    results[cqe.user_data() as usize] = Some(result as usize);
+                }
+            }
+        }
+        if let Some(error) = first_error {
+            return Err(error);
+        }
+    }
+
+    // Verify all completed
+    results
+        .into_iter()
+        .enumerate()
+        .map(|(i, result)| {
+            result.ok_or_else(|| {
+                std::io::Error::new(ErrorKind::Other, format!("Request {} not completed", i))
+            })
+        })
+        .collect()
+}
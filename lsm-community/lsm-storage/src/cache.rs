@@ -39,3 +39,20 @@ impl CacheKey {
 
 /// Block cache type using the packed cache key.
 pub type BlockCache = moka::sync::Cache<CacheKey, Arc<Block>>;
+
+/// Build a block cache bounded by the total bytes of the cached blocks.
+///
+/// # Arguments
+/// * `capacity_bytes` - Maximum weighted size of the cache in bytes
+///
+/// # Returns
+/// * `BlockCache` - Cache that evicts blocks once their total size exceeds the capacity
+pub fn new_block_cache(capacity_bytes: u64) -> BlockCache {
+    moka::sync::Cache::builder()
+        .weigher(|_key: &CacheKey, block: &Arc<Block>| {
+            let bytes = size_of::<CacheKey>() + size_of::<Block>() + block.data.len();
+            bytes.try_into().unwrap_or(u32::MAX)
+        })
+        .max_capacity(capacity_bytes)
+        .build()
+}
@@ -0,0 +1,443 @@
+use crate::{
+    comm_io::{LsmCommunity, LsmCommunityStorageInner},
+    config::{ConfigManager, LsmCommunityStorageOptions},
+    graph::CsrGraph,
+    utils::generate_timestamp_micros,
+    vertex_index::write_file_atomically,
+};
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Weak},
+};
+
+/// File name of the catalog inside the workspace.
+const CATALOG_FILE_NAME: &str = "catalog.yaml";
+
+/// How a graph is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OpenMode {
+    /// Only reads are allowed, every mutation fails.
+    ReadOnly,
+    /// Reads and mutations are allowed.
+    ReadWrite,
+}
+
+/// A graph tracked by the catalog, persisted in the catalog file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEntry {
+    /// Options the graph is opened with. `graph_name` and `work_space_dir` always match the
+    /// catalog.
+    pub options: LsmCommunityStorageOptions,
+    /// Creation time in microseconds since the Unix epoch.
+    pub created_at: u64,
+}
+
+/// Description of a graph in the catalog.
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphInfo {
+    /// Graph name, also the name of its directory in the workspace.
+    pub name: String,
+    /// Options the graph is opened with.
+    pub options: LsmCommunityStorageOptions,
+    /// Creation time in microseconds since the Unix epoch.
+    pub created_at: u64,
+    /// Number of bucket files.
+    pub bucket_count: usize,
+    /// Bytes of all files of the graph, buckets, vertex index and RocksDB.
+    pub disk_size: u64,
+    /// Mode the graph is opened in through the catalog, `None` if it is closed.
+    pub open_mode: Option<OpenMode>,
+}
+
+/// Content of the catalog file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CatalogFile {
+    #[serde(default)]
+    graphs: BTreeMap<String, GraphEntry>,
+}
+
+/// Catalog state, guarded by one lock so that lifecycle operations do not interleave.
+struct CatalogState {
+    graphs: BTreeMap<String, GraphEntry>,
+    /// Graphs opened through the catalog. Held weakly, a graph closes when its last handle
+    /// is dropped.
+    opened: FxHashMap<String, (Weak<LsmCommunity>, OpenMode)>,
+}
+
+impl CatalogState {
+    /// The live handle of an opened graph.
+    fn opened(&self, name: &str) -> Option<(Arc<LsmCommunity>, OpenMode)> {
+        let (graph, mode) = self.opened.get(name)?;
+        Some((graph.upgrade()?, *mode))
+    }
+
+    fn check_closed(&self, name: &str) -> anyhow::Result<()> {
+        match self.opened(name) {
+            Some(_) => Err(anyhow::anyhow!("Graph {} is in use", name)),
+            None => Ok(()),
+        }
+    }
+
+    fn entry(&self, name: &str) -> anyhow::Result<&GraphEntry> {
+        self.graphs
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("Graph {} not exists", name))
+    }
+}
+
+/// Catalog of the graphs in a workspace directory.
+///
+/// Every graph lives in `work_space_dir/graph_name`. The catalog records the options of each
+/// graph in `work_space_dir/catalog.yaml`, creates, renames and drops graphs, and opens
+/// them read-only or read-write. Graphs found in the workspace but not in the catalog file,
+/// e.g. built by [`LsmCommunity::open`], are left out until [`GraphCatalog::adopt`] records
+/// them with the options they were built with.
+///
+/// Opening a graph twice in the same mode returns the same instance. A graph opened
+/// through the catalog cannot be renamed or dropped until every handle is dropped; graphs
+/// opened directly with [`LsmCommunity::open`] are not tracked.
+pub struct GraphCatalog {
+    work_space_dir: String,
+    state: Mutex<CatalogState>,
+}
+
+impl GraphCatalog {
+    /// Opens the catalog of a workspace, creating the workspace directory if needed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the catalog, or an error if the workspace or its catalog file cannot be read.
+    pub fn open(work_space_dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let work_space_path = work_space_dir.as_ref();
+        std::fs::create_dir_all(work_space_path)?;
+        let catalog_path = work_space_path.join(CATALOG_FILE_NAME);
+        let mut catalog_file: CatalogFile = if catalog_path.exists() {
+            ConfigManager::load_from_yaml(&catalog_path)?
+        } else {
+            CatalogFile::default()
+        };
+
+        let work_space_dir = work_space_path.to_string_lossy().into_owned();
+        // Follow a moved workspace
+        for (name, entry) in catalog_file.graphs.iter_mut() {
+            entry.options = Self::graph_options(&work_space_dir, name, entry.options.clone());
+        }
+
+        let catalog = Self {
+            work_space_dir,
+            state: Mutex::new(CatalogState {
+                graphs: catalog_file.graphs,
+                opened: FxHashMap::default(),
+            }),
+        };
+        catalog.save(&catalog.state.lock())?;
+        Ok(catalog)
+    }
+
+    /// The workspace directory of the catalog.
+    pub fn work_space_dir(&self) -> &Path {
+        Path::new(&self.work_space_dir)
+    }
+
+    /// Whether a graph is in the catalog.
+    pub fn contains(&self, name: &str) -> bool {
+        self.state.lock().graphs.contains_key(name)
+    }
+
+    /// Describes every graph in the catalog, in name order.
+    pub fn list(&self) -> Vec<GraphInfo> {
+        let state = self.state.lock();
+        state
+            .graphs
+            .iter()
+            .map(|(name, entry)| self.describe(&state, name, entry))
+            .collect()
+    }
+
+    /// Describes a graph.
+    ///
+    /// # Returns
+    ///
+    /// Returns the description, or an error if the graph is not in the catalog.
+    pub fn info(&self, name: &str) -> anyhow::Result<GraphInfo> {
+        let state = self.state.lock();
+        let entry = state.entry(name)?;
+        Ok(self.describe(&state, name, entry))
+    }
+
+    /// Creates an empty graph and opens it read-write.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Graph name, a plain directory name
+    /// * `options` - Options of the graph, `graph_name` and `work_space_dir` are replaced
+    ///
+    /// # Returns
+    ///
+    /// Returns the opened graph, or an error if the name is invalid or already taken.
+    pub fn create(
+        &self,
+        name: &str,
+        options: LsmCommunityStorageOptions,
+    ) -> anyhow::Result<Arc<LsmCommunity>> {
+        self.create_from_graph(name, CsrGraph::new(), options)
+    }
+
+    /// Creates a graph from a graph file and opens it read-write.
+    ///
+    /// See [`GraphCatalog::create`], the file has the format read by [`CsrGraph::from_file`].
+    pub fn import(
+        &self,
+        name: &str,
+        graph_path: impl AsRef<Path>,
+        options: LsmCommunityStorageOptions,
+    ) -> anyhow::Result<Arc<LsmCommunity>> {
+        self.create_from_graph(name, CsrGraph::from_file(graph_path)?, options)
+    }
+
+    /// Creates a graph from an in-memory graph and opens it read-write.
+    ///
+    /// See [`GraphCatalog::create`].
+    pub fn create_from_graph(
+        &self,
+        name: &str,
+        graph: CsrGraph,
+        options: LsmCommunityStorageOptions,
+    ) -> anyhow::Result<Arc<LsmCommunity>> {
+        let mut state = self.state.lock();
+        self.check_new_name(&state, name)?;
+        let options = Self::graph_options(&self.work_space_dir, name, options);
+        let lsm_community = match LsmCommunity::create(options.clone(), graph) {
+            Ok(lsm_community) => lsm_community,
+            Err(e) => {
+                // Do not leave a half built graph behind
+                let _ = std::fs::remove_dir_all(self.graph_dir(name));
+                return Err(e);
+            }
+        };
+
+        state.graphs.insert(
+            name.to_owned(),
+            GraphEntry {
+                options,
+                created_at: generate_timestamp_micros(),
+            },
+        );
+        state.opened.insert(
+            name.to_owned(),
+            (Arc::downgrade(&lsm_community), OpenMode::ReadWrite),
+        );
+        self.save(&state)?;
+        Ok(lsm_community)
+    }
+
+    /// Records a graph built outside the catalog, e.g. by [`LsmCommunity::open`].
+    ///
+    /// The options are not read back from the graph, they must be the ones it was built
+    /// with, e.g. the same block size.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the graph directory in the workspace
+    /// * `options` - Options of the graph, `graph_name` and `work_space_dir` are replaced
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the graph is already in the catalog or has no data in the
+    /// workspace.
+    pub fn adopt(&self, name: &str, options: LsmCommunityStorageOptions) -> anyhow::Result<()> {
+        Self::check_plain_name(name)?;
+        let mut state = self.state.lock();
+        if state.graphs.contains_key(name) {
+            return Err(anyhow::anyhow!("Graph {} already exists", name));
+        }
+        let options = Self::graph_options(&self.work_space_dir, name, options);
+        if LsmCommunityStorageInner::check_recover(options.clone()).is_none() {
+            return Err(anyhow::anyhow!(
+                "Graph {} has no data in the workspace",
+                name
+            ));
+        }
+
+        state.graphs.insert(
+            name.to_owned(),
+            GraphEntry {
+                options,
+                created_at: generate_timestamp_micros(),
+            },
+        );
+        self.save(&state)
+    }
+
+    /// Opens a graph of the catalog.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Graph name
+    /// * `mode` - Whether mutations are allowed
+    ///
+    /// # Returns
+    ///
+    /// Returns the opened graph, the same instance if it is already opened in this mode, or
+    /// an error if the graph does not exist or is already opened in the other mode.
+    pub fn open_graph(&self, name: &str, mode: OpenMode) -> anyhow::Result<Arc<LsmCommunity>> {
+        let mut state = self.state.lock();
+        let options = state.entry(name)?.options.clone();
+        if let Some((lsm_community, opened_mode)) = state.opened(name) {
+            return if opened_mode == mode {
+                Ok(lsm_community)
+            } else {
+                Err(anyhow::anyhow!(
+                    "Graph {} is already opened {:?}",
+                    name,
+                    opened_mode
+                ))
+            };
+        }
+        // Never build a catalog graph from a graph file behind the caller's back
+        if LsmCommunityStorageInner::check_recover(options.clone()).is_none() {
+            return Err(anyhow::anyhow!(
+                "Graph {} has no data in the workspace",
+                name
+            ));
+        }
+
+        let lsm_community = LsmCommunity::open_with_mode(options, None, mode)?;
+        state
+            .opened
+            .insert(name.to_owned(), (Arc::downgrade(&lsm_community), mode));
+        Ok(lsm_community)
+    }
+
+    /// Renames a closed graph, moving its directory.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the graph does not exist, is in use, or the new name is invalid
+    /// or already taken.
+    pub fn rename(&self, name: &str, new_name: &str) -> anyhow::Result<()> {
+        let mut state = self.state.lock();
+        state.entry(name)?;
+        state.check_closed(name)?;
+        self.check_new_name(&state, new_name)?;
+
+        let graph_dir = self.graph_dir(name);
+        if graph_dir.exists() {
+            std::fs::rename(&graph_dir, self.graph_dir(new_name))?;
+        }
+        let mut entry = state.graphs.remove(name).unwrap();
+        entry.options = Self::graph_options(&self.work_space_dir, new_name, entry.options);
+        state.graphs.insert(new_name.to_owned(), entry);
+        state.opened.remove(name);
+        self.save(&state)
+    }
+
+    /// Drops a closed graph, deleting its buckets, vertex index, RocksDB and every other
+    /// file in its directory.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the graph does not exist or is in use.
+    pub fn drop_graph(&self, name: &str) -> anyhow::Result<()> {
+        let mut state = self.state.lock();
+        state.entry(name)?;
+        state.check_closed(name)?;
+
+        let graph_dir = self.graph_dir(name);
+        if graph_dir.exists() {
+            std::fs::remove_dir_all(&graph_dir)?;
+        }
+        state.graphs.remove(name);
+        state.opened.remove(name);
+        self.save(&state)
+    }
+
+    /// Options of a graph of this workspace.
+    fn graph_options(
+        work_space_dir: &str,
+        name: &str,
+        mut options: LsmCommunityStorageOptions,
+    ) -> LsmCommunityStorageOptions {
+        options.graph_name = name.to_owned();
+        options.work_space_dir = work_space_dir.to_owned();
+        options
+    }
+
+    fn graph_dir(&self, name: &str) -> PathBuf {
+        Path::new(&self.work_space_dir).join(name)
+    }
+
+    fn check_plain_name(name: &str) -> anyhow::Result<()> {
+        let is_plain_name = !name.is_empty()
+            && name != "."
+            && name != ".."
+            && !name.contains(['/', '\\'])
+            && name != CATALOG_FILE_NAME;
+        if !is_plain_name {
+            return Err(anyhow::anyhow!("Invalid graph name {:?}", name));
+        }
+        Ok(())
+    }
+
+    fn check_new_name(&self, state: &CatalogState, name: &str) -> anyhow::Result<()> {
+        Self::check_plain_name(name)?;
+        if state.graphs.contains_key(name) || self.graph_dir(name).exists() {
+            return Err(anyhow::anyhow!("Graph {} already exists", name));
+        }
+        Ok(())
+    }
+
+    fn describe(&self, state: &CatalogState, name: &str, entry: &GraphEntry) -> GraphInfo {
+        let graph_dir = self.graph_dir(name);
+        let bucket_count = std::fs::read_dir(&graph_dir)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .filter(|entry| {
+                        let file_name = entry.file_name().to_string_lossy().to_string();
+                        file_name.starts_with("bucket_") && file_name.contains(".bkt")
+                    })
+                    .count()
+            })
+            .unwrap_or(0);
+        GraphInfo {
+            name: name.to_owned(),
+            options: entry.options.clone(),
+            created_at: entry.created_at,
+            bucket_count,
+            disk_size: dir_size(&graph_dir),
+            open_mode: state.opened(name).map(|(_, mode)| mode),
+        }
+    }
+
+    fn save(&self, state: &CatalogState) -> anyhow::Result<()> {
+        let catalog_file = CatalogFile {
+            graphs: state.graphs.clone(),
+        };
+        // Write aside, sync and rename, so that a crash never leaves a truncated catalog
+        let catalog_path = Path::new(&self.work_space_dir).join(CATALOG_FILE_NAME);
+        write_file_atomically(&catalog_path, |writer| {
+            serde_yaml::to_writer(writer, &catalog_file).map_err(std::io::Error::other)
+        })?;
+        Ok(())
+    }
+}
+
+/// Total bytes of the files under a directory, 0 if it does not exist.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
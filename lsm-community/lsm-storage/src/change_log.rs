@@ -0,0 +1,395 @@
+use parking_lot::{Condvar, Mutex};
+use rocksdb::{DB, WriteBatch, WriteOptions};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use crate::{config::ChangeLogOptions, types::VId, utils::generate_timestamp_micros};
+
+/// Kind of a graph mutation recorded in the change log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeKind {
+    /// A vertex was inserted
+    AddVertex,
+    /// An edge was inserted
+    AddEdge,
+    /// An edge was removed
+    RemoveEdge,
+    /// A vertex property was put
+    PutVertexProperty,
+    /// A vertex property was removed
+    RemoveVertexProperty,
+    /// An edge property was put
+    PutEdgeProperty,
+    /// An edge property was removed
+    RemoveEdgeProperty,
+}
+
+/// A graph mutation recorded in the change log.
+///
+/// Events carry the IDs and property names of the mutation, not the values, readers
+/// fetch the current state from the graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    /// Position in the change log, consecutive in commit order
+    pub seq: u64,
+    /// Commit time in microseconds since the Unix epoch
+    pub timestamp: u64,
+    /// Kind of the mutation
+    pub kind: ChangeKind,
+    /// The vertex, or the source vertex of an edge
+    pub vertex_id: VId,
+    /// The destination vertex of an edge, None for a vertex
+    pub neighbor: Option<VId>,
+    /// The property name of a property mutation
+    pub property_name: Option<String>,
+}
+
+impl ChangeEvent {
+    /// A mutation of a vertex, numbered when written.
+    pub(crate) fn vertex(kind: ChangeKind, vertex_id: VId) -> Self {
+        Self {
+            seq: 0,
+            timestamp: 0,
+            kind,
+            vertex_id,
+            neighbor: None,
+            property_name: None,
+        }
+    }
+
+    /// A mutation of an edge, numbered when written.
+    pub(crate) fn edge(kind: ChangeKind, src_vertex: VId, dst_vertex: VId) -> Self {
+        Self {
+            neighbor: Some(dst_vertex),
+            ..Self::vertex(kind, src_vertex)
+        }
+    }
+
+    /// A mutation of a vertex property, numbered when written.
+    pub(crate) fn vertex_property(kind: ChangeKind, vertex_id: VId, property_name: &str) -> Self {
+        Self {
+            property_name: Some(property_name.to_owned()),
+            ..Self::vertex(kind, vertex_id)
+        }
+    }
+
+    /// A mutation of an edge property, numbered when written.
+    pub(crate) fn edge_property(
+        kind: ChangeKind,
+        src_vertex: VId,
+        dst_vertex: VId,
+        property_name: &str,
+    ) -> Self {
+        Self {
+            property_name: Some(property_name.to_owned()),
+            ..Self::edge(kind, src_vertex, dst_vertex)
+        }
+    }
+
+    fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    fn decode(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
+
+/// Wakes up the subscribers waiting for new changes.
+#[derive(Debug, Default)]
+struct ChangeNotifier {
+    /// Sequence number of the next change to be written
+    next_seq: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl ChangeNotifier {
+    fn publish(&self, next_seq: u64) {
+        *self.next_seq.lock() = next_seq;
+        self.changed.notify_all();
+    }
+
+    /// Wait until the change `seq` is written, at most `timeout`. Returns whether it is.
+    fn wait(&self, seq: u64, timeout: Duration) -> bool {
+        let mut next_seq = self.next_seq.lock();
+        self.changed
+            .wait_while_for(&mut next_seq, |next_seq| *next_seq <= seq, timeout);
+        *next_seq > seq
+    }
+}
+
+/// Durable, ordered log of the graph mutations, for change data capture.
+///
+/// The changes live in a column family of the external storage, keyed by sequence number.
+/// A change is written in the same batch as the mutation it records, so that it is exactly
+/// as durable as the mutation, and the sequence numbers follow the commit order.
+///
+/// The retention drops the changes older than `retention_secs` or beyond the latest
+/// `max_changes`, every [`ChangeLog::TRIM_INTERVAL`] changes and on
+/// [`ChangeLog::trim`]. The latest change is always retained, so that the sequence numbers
+/// go on after a restart.
+pub(crate) struct ChangeLog {
+    db: Arc<DB>,
+    options: ChangeLogOptions,
+    /// Sequence number of the next change, locked over a write
+    next_seq: Mutex<u64>,
+    notifier: Arc<ChangeNotifier>,
+}
+
+impl ChangeLog {
+    /// Column family name of the change log
+    pub(crate) const CF_NAME: &'static str = "change_log";
+    /// Changes written between two automatic trims
+    const TRIM_INTERVAL: u64 = 1024;
+
+    /// Open the change log of the external DB, continuing its sequence numbers.
+    pub(crate) fn open(db: Arc<DB>, options: ChangeLogOptions) -> anyhow::Result<Self> {
+        let next_seq = match last_change(&db)? {
+            Some(change) => change.seq + 1,
+            None => 0,
+        };
+        let notifier = Arc::new(ChangeNotifier::default());
+        notifier.publish(next_seq);
+        Ok(Self {
+            db,
+            options,
+            next_seq: Mutex::new(next_seq),
+            notifier,
+        })
+    }
+
+    /// Sequence number of the next change to be written.
+    pub(crate) fn next_seq(&self) -> u64 {
+        *self.next_seq.lock()
+    }
+
+    /// Write a batch with the changes it makes, numbering and timestamping the changes.
+    pub(crate) fn write(
+        &self,
+        mut batch: WriteBatch,
+        mut changes: Vec<ChangeEvent>,
+        write_options: &WriteOptions,
+    ) -> anyhow::Result<()> {
+        let cf = self
+            .db
+            .cf_handle(Self::CF_NAME)
+            .ok_or_else(|| anyhow::anyhow!("Change log CF not found"))?;
+        let timestamp = generate_timestamp_micros();
+
+        // Hold the sequence over the write, so that a reader never sees a gap
+        let mut next_seq = self.next_seq.lock();
+        let first_seq = *next_seq;
+        for (change, seq) in changes.iter_mut().zip(first_seq..) {
+            change.seq = seq;
+            change.timestamp = timestamp;
+            batch.put_cf(&cf, seq.to_be_bytes(), change.encode()?);
+        }
+        self.db.write_opt(batch, write_options)?;
+        *next_seq += changes.len() as u64;
+        self.notifier.publish(*next_seq);
+        let last_seq = *next_seq;
+        drop(next_seq);
+
+        // The batch is committed, a failed trim is retried at the next interval
+        if first_seq / Self::TRIM_INTERVAL != last_seq / Self::TRIM_INTERVAL {
+            if let Err(e) = self.trim(write_options) {
+                eprintln!("Failed to trim the change log: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop the changes beyond the retention.
+    ///
+    /// # Returns
+    ///
+    /// Returns the sequence number of the oldest change retained.
+    pub(crate) fn trim(&self, write_options: &WriteOptions) -> anyhow::Result<u64> {
+        let next_seq = self.next_seq();
+        let cf = self
+            .db
+            .cf_handle(Self::CF_NAME)
+            .ok_or_else(|| anyhow::anyhow!("Change log CF not found"))?;
+        let mut iter = self.db.raw_iterator_cf(&cf);
+        iter.seek_to_first();
+        let Some(first_seq) = iter.key().map(decode_seq).transpose()? else {
+            return Ok(next_seq);
+        };
+
+        let mut retain_from = first_seq;
+        if self.options.max_changes > 0 {
+            retain_from = retain_from.max(next_seq.saturating_sub(self.options.max_changes));
+        }
+        if self.options.retention_secs > 0 {
+            let cutoff = generate_timestamp_micros()
+                .saturating_sub(self.options.retention_secs.saturating_mul(1_000_000));
+            while let Some(value) = iter.value() {
+                let change = ChangeEvent::decode(value)?;
+                if change.timestamp >= cutoff {
+                    break;
+                }
+                retain_from = retain_from.max(change.seq + 1);
+                iter.next();
+            }
+            iter.status()?;
+        }
+        // The latest change keeps the sequence numbers over a restart
+        retain_from = retain_from.min(next_seq.saturating_sub(1)).max(first_seq);
+
+        if retain_from > first_seq {
+            let mut batch = WriteBatch::default();
+            batch.delete_range_cf(&cf, first_seq.to_be_bytes(), retain_from.to_be_bytes());
+            self.db.write_opt(batch, write_options)?;
+        }
+        Ok(retain_from)
+    }
+
+    /// Subscribe to the changes from `from_seq` on.
+    ///
+    /// Returns an error if the retention already dropped the change `from_seq`.
+    pub(crate) fn subscribe(&self, from_seq: u64) -> anyhow::Result<ChangeSubscription> {
+        let mut subscription = ChangeSubscription {
+            db: Arc::downgrade(&self.db),
+            notifier: self.notifier.clone(),
+            next_seq: from_seq,
+            buffer: VecDeque::new(),
+        };
+        // Fail now rather than on the first poll
+        subscription.buffer.extend(subscription.read(1)?);
+        Ok(subscription)
+    }
+}
+
+/// The latest change of the change log.
+fn last_change(db: &DB) -> anyhow::Result<Option<ChangeEvent>> {
+    let cf = db
+        .cf_handle(ChangeLog::CF_NAME)
+        .ok_or_else(|| anyhow::anyhow!("Change log CF not found"))?;
+    let mut iter = db.raw_iterator_cf(&cf);
+    iter.seek_to_last();
+    iter.status()?;
+    iter.value().map(ChangeEvent::decode).transpose()
+}
+
+fn decode_seq(key: &[u8]) -> anyhow::Result<u64> {
+    Ok(u64::from_be_bytes(key.try_into().map_err(|_| {
+        anyhow::anyhow!("Invalid change log key length: {}", key.len())
+    })?))
+}
+
+/// A reader of the change log, from a sequence number on.
+///
+/// [`ChangeSubscription::poll`] returns the next changes in sequence order, waiting for
+/// them at most a timeout. As an iterator, a subscription blocks until the next change and
+/// ends once the graph is closed. Reading a change the retention already dropped is an
+/// error, the reader then has to resynchronize from the graph itself.
+///
+/// A subscription does not keep the graph open.
+pub struct ChangeSubscription {
+    db: Weak<DB>,
+    notifier: Arc<ChangeNotifier>,
+    /// Sequence number of the next change to read from the DB
+    next_seq: u64,
+    /// Changes read ahead by the iterator
+    buffer: VecDeque<ChangeEvent>,
+}
+
+impl ChangeSubscription {
+    /// Changes read at once by the iterator
+    const READ_AHEAD: usize = 256;
+    /// Interval between two checks for a closed graph while waiting
+    const WAIT_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Sequence number of the next change returned.
+    pub fn next_seq(&self) -> u64 {
+        self.buffer
+            .front()
+            .map_or(self.next_seq, |change| change.seq)
+    }
+
+    /// Read the next changes, at most `max_changes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_changes` - Maximum number of changes returned
+    /// * `timeout` - How long to wait if no change is available, zero returns at once
+    ///
+    /// # Returns
+    ///
+    /// Returns the changes in sequence order, empty if none came before the timeout.
+    /// Returns an error if the graph is closed or the retention dropped the next change.
+    pub fn poll(
+        &mut self,
+        max_changes: usize,
+        timeout: Duration,
+    ) -> anyhow::Result<Vec<ChangeEvent>> {
+        let buffered = max_changes.min(self.buffer.len());
+        let mut changes: Vec<ChangeEvent> = self.buffer.drain(..buffered).collect();
+        if changes.len() < max_changes {
+            changes.extend(self.read(max_changes - changes.len())?);
+        }
+        if changes.is_empty() && max_changes > 0 && self.notifier.wait(self.next_seq, timeout) {
+            changes = self.read(max_changes)?;
+        }
+        Ok(changes)
+    }
+
+    /// Read the changes from `next_seq` in the DB, at most `max_changes`.
+    fn read(&mut self, max_changes: usize) -> anyhow::Result<Vec<ChangeEvent>> {
+        let db = self
+            .db
+            .upgrade()
+            .ok_or_else(|| anyhow::anyhow!("Graph is closed"))?;
+        let cf = db
+            .cf_handle(ChangeLog::CF_NAME)
+            .ok_or_else(|| anyhow::anyhow!("Change log CF not found"))?;
+        let mut iter = db.raw_iterator_cf(&cf);
+        iter.seek(self.next_seq.to_be_bytes());
+
+        let mut changes = Vec::new();
+        while changes.len() < max_changes {
+            let Some(value) = iter.value() else {
+                break;
+            };
+            let change = ChangeEvent::decode(value)?;
+            // Sequence numbers are consecutive, a gap is a trimmed change
+            if change.seq != self.next_seq {
+                return Err(anyhow::anyhow!(
+                    "Changes {} to {} were dropped by the retention of the change log",
+                    self.next_seq,
+                    change.seq - 1
+                ));
+            }
+            self.next_seq += 1;
+            changes.push(change);
+            iter.next();
+        }
+        iter.status()?;
+        Ok(changes)
+    }
+}
+
+impl Iterator for ChangeSubscription {
+    type Item = anyhow::Result<ChangeEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(change) = self.buffer.pop_front() {
+                return Some(Ok(change));
+            }
+            match self.read(Self::READ_AHEAD) {
+                Ok(changes) if changes.is_empty() => {
+                    self.notifier.wait(self.next_seq, Self::WAIT_INTERVAL);
+                }
+                Ok(changes) => self.buffer.extend(changes),
+                // The graph is closed, nothing more to read
+                Err(_) if self.db.strong_count() == 0 => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
@@ -1,22 +1,37 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
+use crate::algorithms::bfs::InAdjacency;
+use crate::algorithms::reachability::{ReachabilityIndex, ReachabilityUpdate};
+use crate::algorithms::wcc::IncrementalWcc;
+use crate::block::Block;
 use crate::cache::CacheKey;
+use crate::catalog::OpenMode;
+use crate::change_log::{ChangeEvent, ChangeKind, ChangeSubscription};
 use crate::config::LsmCommunityStorageOptions;
-use crate::delta::{DeltaLog, DeltaOpType, DeltaOperation};
+use crate::delta::{DeltaLog, DeltaOpType, DeltaOperation, EdgeInterval};
 use crate::external::ExternalStorage;
-use crate::iterator::{GlobalNeighborIterator, UnifiedNeighborIterator};
+use crate::iterator::{GlobalNeighborIterator, MergedNeighborIterator, UnifiedNeighborIterator};
+use crate::memory::MemoryBudget;
 use crate::types::{EdgeList, PageId, VIdList};
 use crate::utils::generate_timestamp_micros;
 use crate::{
-    bucket::{Bucket, builder::BucketBuilder, disk_manager::BktDiskManager},
-    cache::BlockCache,
+    bucket::{
+        Bucket,
+        builder::BucketBuilder,
+        disk_manager::{BktDiskManager, DiskManagerOptions},
+        io::{self, FileStamp, IoBackend, MemoryIo},
+    },
+    cache::{BlockCache, new_block_cache},
     graph::CsrGraph,
-    mem_graph::MemGraph,
+    mem_graph::{MemGraph, SharedNeighborList},
     types::{VId, VirtualCommId},
     vertex_index::VertexIndex,
 };
 use anyhow::{Ok, Result};
-use moka::sync::Cache;
 use parking_lot::{Mutex, RwLock};
 use rayon::prelude::*;
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -39,7 +54,7 @@ impl LsmCommunityStorageState {
     pub fn create_with_graph_file(
         mut graph: CsrGraph,
         lsm_community_storage_option: LsmCommunityStorageOptions,
-    ) -> (Self, FxHashMap<VId, VIdList>, VertexIndex) {
+    ) -> Result<(Self, FxHashMap<VId, VIdList>, VertexIndex)> {
         // Step 1 - Build vertex index to compute the virtual community id.
         println!("Building Vertex Index");
         let (mut vertex_index, _) = VertexIndex::build_from_graph(
@@ -76,7 +91,18 @@ impl LsmCommunityStorageState {
             .join(&lsm_community_storage_option.graph_name);
 
         // Create directory if not exists
-        std::fs::create_dir_all(&bucket_dir).unwrap();
+        std::fs::create_dir_all(&bucket_dir)?;
+        // Bucket files left by an interrupted build are stale
+        for bucket_path in LsmCommunityStorageInner::find_bucket_files(
+            &bucket_dir,
+            lsm_community_storage_option.io_backend,
+        )? {
+            if lsm_community_storage_option.io_backend == IoBackend::Memory {
+                MemoryIo::remove(&bucket_path);
+            } else {
+                std::fs::remove_file(&bucket_path)?;
+            }
+        }
         println!("Building Buckets");
         let mut buckets = FxHashMap::<VirtualCommId, Arc<Bucket>>::default();
 
@@ -108,7 +134,14 @@ impl LsmCommunityStorageState {
             }
             // The file full path is `./{work_space_dir}/{graph_name}/bucket_{virtual_comm_id}.bkt`
             let bucket_path = bucket_dir.join(format!("bucket_{}.bkt", virtual_comm_id));
-            let bucket_build = bucket_builder.build(*virtual_comm_id, bucket_path).unwrap();
+            let bucket_build = bucket_builder.build_with_options(
+                *virtual_comm_id,
+                bucket_path,
+                DiskManagerOptions {
+                    backend: lsm_community_storage_option.io_backend,
+                    ..Default::default()
+                },
+            )?;
 
             for vertex_meta in &bucket_build.vertex_metas {
                 // Update the vertex index.
@@ -127,11 +160,9 @@ impl LsmCommunityStorageState {
         // Save vertex index to file;
         let vertex_index_path = bucket_dir.join("vertex_index.bin.zst");
         println!("Saving Vertex Index");
-        vertex_index
-            .serialize_to_file(vertex_index_path, 3)
-            .unwrap();
+        vertex_index.serialize_to_file(vertex_index_path, 3)?;
         println!("Saving Vertex Index - [OK]");
-        (
+        Ok((
             Self {
                 mem_graph: Arc::new(MemGraph::new(0)),
                 imm_mem_graphs: vec![],
@@ -139,7 +170,7 @@ impl LsmCommunityStorageState {
             },
             giant_vertex_map,
             vertex_index,
-        )
+        ))
     }
 }
 
@@ -147,7 +178,11 @@ impl LsmCommunityStorageState {
 pub struct LsmCommunityStorageInner {
     pub state: Arc<RwLock<Arc<LsmCommunityStorageState>>>,
     pub state_lock: Mutex<()>,
+    /// Stamps of the bucket files when they were opened, to find the files another process
+    /// replaced. Only recovered buckets have one.
+    bucket_stamps: Mutex<FxHashMap<VirtualCommId, FileStamp>>,
     block_cache: Arc<BlockCache>,
+    pub memory_budget: MemoryBudget,
     pub options: Arc<LsmCommunityStorageOptions>,
 }
 
@@ -160,49 +195,147 @@ impl LsmCommunityStorageInner {
         vertex_index_opt: Option<PathBuf>,
         bucket_path_opt: Option<Vec<PathBuf>>,
         options: LsmCommunityStorageOptions,
+    ) -> Result<(Self, FxHashMap<VId, VIdList>, VertexIndex)> {
+        Self::open_with_graph(is_recover, vertex_index_opt, bucket_path_opt, None, options)
+    }
+
+    /// Same as [`LsmCommunityStorageInner::open`], but a build from scratch loads `graph`
+    /// instead of the graph file named after the graph.
+    pub(crate) fn open_with_graph(
+        is_recover: bool,
+        vertex_index_opt: Option<PathBuf>,
+        bucket_path_opt: Option<Vec<PathBuf>>,
+        graph: Option<CsrGraph>,
+        options: LsmCommunityStorageOptions,
     ) -> Result<(Self, FxHashMap<VId, VIdList>, VertexIndex)> {
         if is_recover {
-            println!("Perform Recovering");
-            // Recover from files.
-            // Step 1 - Recover the buckets.
-            let mut buckets = FxHashMap::<VirtualCommId, Arc<Bucket>>::default();
-            let bucket_path_list = bucket_path_opt.unwrap();
-            // Travel each bucket.
-            for bucket_path in bucket_path_list {
-                // Step 2: Open the bucket from file
-                let file = BktDiskManager::new(&bucket_path)?;
-                let virtual_comm_id = Self::extract_community_id(&bucket_path).unwrap();
-                let bucket = Bucket::open(virtual_comm_id, file, true)?;
-                // Push into buckets.
-                buckets.insert(virtual_comm_id, Arc::new(bucket));
-            }
+            Self::recover(
+                &vertex_index_opt.unwrap(),
+                &bucket_path_opt.unwrap(),
+                options,
+                false,
+            )
+        } else {
+            // Build from scratch.
+            let graph = match graph {
+                Some(graph) => graph,
+                None => Self::load_graph_file(&options)?,
+            };
+            Self::build_from_csr_graph(graph, options)
+        }
+    }
 
-            // Step 3 - Recover the vertex index.
-            let vertex_index_path = vertex_index_opt.unwrap();
-            let vertex_index = VertexIndex::deserialize_from_file(&vertex_index_path)?;
+    /// Recover the storage from the files of an existing graph.
+    ///
+    /// With `read_only`, the bucket files are opened without write access, so that the
+    /// graph can be read while another process writes it.
+    pub(crate) fn recover(
+        vertex_index_path: &Path,
+        bucket_paths: &[PathBuf],
+        options: LsmCommunityStorageOptions,
+        read_only: bool,
+    ) -> Result<(Self, FxHashMap<VId, VIdList>, VertexIndex)> {
+        println!("Perform Recovering");
+        // Step 1 - Recover the buckets.
+        let mut buckets = FxHashMap::<VirtualCommId, Arc<Bucket>>::default();
+        let mut bucket_stamps = FxHashMap::<VirtualCommId, FileStamp>::default();
+        // Travel each bucket.
+        for bucket_path in bucket_paths {
+            // Step 2: Open the bucket from file, stamped before so a later replacement shows
+            let stamp = io::file_stamp(bucket_path, options.io_backend)?;
+            let (virtual_comm_id, bucket) =
+                Self::open_bucket(bucket_path, options.io_backend, read_only)?;
+            // Push into buckets.
+            buckets.insert(virtual_comm_id, Arc::new(bucket));
+            bucket_stamps.insert(virtual_comm_id, stamp);
+        }
 
-            // Build lsm storage state.
-            let state = LsmCommunityStorageState {
-                mem_graph: Arc::new(MemGraph::new(0)),
-                imm_mem_graphs: Vec::new(),
+        // Step 3 - Recover the vertex index.
+        let vertex_index = VertexIndex::deserialize_from_file(vertex_index_path)?;
+
+        // Step 4 - Partition the memory budget.
+        let memory_budget = Self::partition_memory_budget(&options, &buckets);
+
+        // Build lsm storage state.
+        let state = LsmCommunityStorageState {
+            mem_graph: Arc::new(MemGraph::new(0)),
+            imm_mem_graphs: Vec::new(),
+            buckets,
+        };
+        println!("Perform Recovering - [OK]");
+
+        Ok((
+            Self {
+                state: Arc::new(RwLock::new(Arc::new(state))),
+                state_lock: Mutex::new(()),
+                bucket_stamps: Mutex::new(bucket_stamps),
+                block_cache: Arc::new(new_block_cache(memory_budget.block_cache)),
+                memory_budget,
+                options: Arc::new(options),
+            },
+            FxHashMap::<VId, VIdList>::default(),
+            vertex_index,
+        ))
+    }
+
+    /// Open a bucket file, returning its virtual community id.
+    fn open_bucket(
+        bucket_path: &Path,
+        backend: IoBackend,
+        read_only: bool,
+    ) -> Result<(VirtualCommId, Bucket)> {
+        let disk_options = DiskManagerOptions {
+            read_only,
+            backend,
+            ..Default::default()
+        };
+        let file = BktDiskManager::with_options(bucket_path, disk_options)?;
+        let virtual_comm_id = Self::extract_community_id(bucket_path)
+            .ok_or_else(|| anyhow::anyhow!("Invalid bucket file {}", bucket_path.display()))?;
+        Ok((virtual_comm_id, Bucket::open(virtual_comm_id, file, true)?))
+    }
+
+    /// Reopen the buckets after another process changed the bucket files.
+    ///
+    /// New bucket files are opened read-only, buckets whose file was rewritten or replaced,
+    /// as told by its size, inode and modification time, are reopened and buckets whose file
+    /// is gone are dropped. The block cache is cleared if any bucket changed, since cached
+    /// blocks may belong to a replaced file.
+    pub(crate) fn catch_up_buckets(&self, bucket_paths: &[PathBuf]) -> Result<()> {
+        let _state_lock = self.state_lock.lock();
+        let state = self.state.read().clone();
+        let mut bucket_stamps = self.bucket_stamps.lock();
+        let mut buckets = FxHashMap::<VirtualCommId, Arc<Bucket>>::default();
+        let mut stamps = FxHashMap::<VirtualCommId, FileStamp>::default();
+        let mut changed = false;
+        for bucket_path in bucket_paths {
+            let virtual_comm_id = Self::extract_community_id(bucket_path)
+                .ok_or_else(|| anyhow::anyhow!("Invalid bucket file {}", bucket_path.display()))?;
+            let stamp = io::file_stamp(bucket_path, self.options.io_backend)?;
+            match state.buckets.get(&virtual_comm_id) {
+                Some(bucket) if bucket_stamps.get(&virtual_comm_id) == Some(&stamp) => {
+                    buckets.insert(virtual_comm_id, bucket.clone());
+                }
+                _ => {
+                    let (_, bucket) =
+                        Self::open_bucket(bucket_path, self.options.io_backend, true)?;
+                    buckets.insert(virtual_comm_id, Arc::new(bucket));
+                    changed = true;
+                }
+            }
+            stamps.insert(virtual_comm_id, stamp);
+        }
+        changed |= buckets.len() != state.buckets.len();
+        if changed {
+            *self.state.write() = Arc::new(LsmCommunityStorageState {
+                mem_graph: state.mem_graph.clone(),
+                imm_mem_graphs: state.imm_mem_graphs.clone(),
                 buckets,
-            };
-            println!("Perform Recovering - [OK]");
-
-            Ok((
-                Self {
-                    state: Arc::new(RwLock::new(Arc::new(state))),
-                    state_lock: Mutex::new(()),
-                    block_cache: Arc::new(Cache::new(options.block_cache_capacity)),
-                    options: Arc::new(options),
-                },
-                FxHashMap::<VId, VIdList>::default(),
-                vertex_index,
-            ))
-        } else {
-            // Build from scratch.
-            Self::build_from_csr_graph(options)
+            });
+            self.block_cache.invalidate_all();
         }
+        *bucket_stamps = stamps;
+        Ok(())
     }
 
     /// Get the neighbor of a vertex.
@@ -213,12 +346,7 @@ impl LsmCommunityStorageInner {
     ) -> GlobalNeighborIterator {
         // Step 1 - Get mem neighbors
         let state = self.state.read();
-        let mem_neighbors = state
-            .mem_graph
-            .map
-            .get(&vertex_id)
-            .map(|entry| entry.value().read().unwrap().clone())
-            .unwrap_or_else(Vec::new);
+        let mem_neighbors = Self::get_mem_neighbors(&state, vertex_id);
 
         // Step 2 - Get the neighbor in bucket.
         let vertex_item = vertex_index.vertex_array[vertex_id as usize];
@@ -246,6 +374,112 @@ impl LsmCommunityStorageInner {
         GlobalNeighborIterator::new(mem_neighbors, block_arc, vertex_offset)
     }
 
+    /// Get the neighbor iterators of several vertices with batched block reads.
+    ///
+    /// Vertices are grouped by (virtual community, page), every missing page is read once
+    /// and each bucket issues a single batched read for its pages. Loaded blocks are pushed
+    /// into the block cache.
+    ///
+    /// # Arguments
+    /// * `vertex_ids` - The vertices to read, must be normal (non-giant) vertices
+    /// * `vertex_index` - The vertex index held by the caller
+    ///
+    /// # Returns
+    /// * `Result<Vec<GlobalNeighborIterator>>` - One iterator per input vertex, in input order
+    pub fn get_neighbor_iters_batch(
+        &self,
+        vertex_ids: &[VId],
+        vertex_index: &VertexIndex,
+    ) -> Result<Vec<GlobalNeighborIterator>> {
+        let state = self.state.read();
+
+        // Step 1 - Collect the blocks, grouping cache misses by bucket.
+        let mut blocks = FxHashMap::<CacheKey, Arc<Block>>::default();
+        let mut missing_pages = FxHashMap::<VirtualCommId, Vec<PageId>>::default();
+        for &vertex_id in vertex_ids {
+            let vertex_item = vertex_index.vertex_array[vertex_id as usize];
+            let Some(cache_key) = vertex_item.to_cache_key() else {
+                continue;
+            };
+            if blocks.contains_key(&cache_key) {
+                continue;
+            }
+            if let Some(cached_block) = self.block_cache.get(&cache_key) {
+                blocks.insert(cache_key, cached_block);
+            } else {
+                missing_pages
+                    .entry(vertex_item.virtual_comm_id())
+                    .or_default()
+                    .push(vertex_item.page_id());
+            }
+        }
+
+        // Step 2 - One batched read per bucket for the deduplicated pages.
+        let loaded = missing_pages
+            .into_par_iter()
+            .map(|(virtual_comm_id, mut page_ids)| {
+                page_ids.sort_unstable();
+                page_ids.dedup();
+                let bucket = state
+                    .buckets
+                    .get(&virtual_comm_id)
+                    .ok_or_else(|| anyhow::anyhow!("Bucket {} not found", virtual_comm_id))?;
+                let loaded_blocks = bucket.read_blocks(&page_ids)?;
+                Ok(page_ids
+                    .into_iter()
+                    .zip(loaded_blocks)
+                    .map(|(page_id, block)| (CacheKey::new(virtual_comm_id, page_id), block))
+                    .collect::<Vec<_>>())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for (cache_key, block) in loaded.into_iter().flatten() {
+            self.block_cache.insert(cache_key, block.clone());
+            blocks.insert(cache_key, block);
+        }
+
+        // Step 3 - Combine mem neighbors and blocks into iterators.
+        let iters = vertex_ids
+            .iter()
+            .map(|&vertex_id| {
+                let mem_neighbors = Self::get_mem_neighbors(&state, vertex_id);
+                let vertex_item = vertex_index.vertex_array[vertex_id as usize];
+                match vertex_item.to_cache_key() {
+                    Some(cache_key) => GlobalNeighborIterator::new(
+                        mem_neighbors,
+                        blocks.get(&cache_key).cloned(),
+                        Some(vertex_item.offset() as usize),
+                    ),
+                    None => GlobalNeighborIterator::new(mem_neighbors, None, None),
+                }
+            })
+            .collect();
+        Ok(iters)
+    }
+
+    /// Get the shared neighbor list of a vertex recorded in the mem graph.
+    fn get_mem_neighbors(
+        state: &LsmCommunityStorageState,
+        vertex_id: VId,
+    ) -> Option<SharedNeighborList> {
+        state.mem_graph.get_neighbor_list(vertex_id)
+    }
+
+    /// Partition the memory budget, rebalancing it against the bucket sizes if enabled.
+    fn partition_memory_budget(
+        options: &LsmCommunityStorageOptions,
+        buckets: &FxHashMap<VirtualCommId, Arc<Bucket>>,
+    ) -> MemoryBudget {
+        let memory_budget = MemoryBudget::from_options(options);
+        if !options.memory_rebalance {
+            return memory_budget;
+        }
+        let block_working_set: u64 = buckets
+            .values()
+            .map(|bucket| bucket.block_segment_size() as u64)
+            .sum();
+        memory_budget.rebalance(options, block_working_set)
+    }
+
     /// Extract the virtual community id from the bucket path.
     fn extract_community_id(bucket_path: &Path) -> Option<VirtualCommId> {
         let filename = bucket_path.file_name()?.to_string_lossy();
@@ -261,33 +495,38 @@ impl LsmCommunityStorageInner {
         None
     }
 
+    /// Load the graph file named after the graph, `./data/{graph_name}.graph`.
+    fn load_graph_file(options: &LsmCommunityStorageOptions) -> Result<CsrGraph> {
+        #[cfg(test)]
+        let graph = CsrGraph::from_file(format!("../data/{}.graph", options.graph_name))?;
+
+        #[cfg(not(test))]
+        let graph = CsrGraph::from_file(format!("./data/{}.graph", options.graph_name))?;
+        Ok(graph)
+    }
+
     // Build from beginning.
     fn build_from_csr_graph(
+        graph: CsrGraph,
         options: LsmCommunityStorageOptions,
     ) -> Result<(Self, FxHashMap<VId, VIdList>, VertexIndex)> {
         println!("Build from Scratch");
-        // Two cases: Build from scratch, or recover from files.
         // Step 1 - Build lsm storage state;
-        let block_cache_capacity = options.block_cache_capacity;
-        // println!("Before creating graph from file.");
-        // Create the graph from the graph file.
-        #[cfg(test)]
-        let graph = CsrGraph::from_file(format!("../data/{}.graph", options.graph_name))?;
-
-        #[cfg(not(test))]
-        let graph = CsrGraph::from_file(format!("./data/{}.graph", options.graph_name))?;
         let (state, giant_vertex_map, vertex_index) =
-            LsmCommunityStorageState::create_with_graph_file(graph, options.clone());
+            LsmCommunityStorageState::create_with_graph_file(graph, options.clone())?;
 
         // Step 2 - Build block cache;
-        let block_cache = BlockCache::new(block_cache_capacity);
+        let memory_budget = Self::partition_memory_budget(&options, &state.buckets);
+        let block_cache = new_block_cache(memory_budget.block_cache);
 
         // Step 4 - Return Inner;
         Ok((
             Self {
                 state: Arc::new(RwLock::new(Arc::new(state))),
                 state_lock: Mutex::new(()),
+                bucket_stamps: Mutex::default(),
                 block_cache: Arc::new(block_cache),
+                memory_budget,
                 options: Arc::new(options),
             },
             giant_vertex_map,
@@ -361,7 +600,9 @@ impl LsmCommunityStorageInner {
     }
 
     // Check can recover from files.
-    fn check_recover(options: LsmCommunityStorageOptions) -> Option<(PathBuf, Vec<PathBuf>)> {
+    pub(crate) fn check_recover(
+        options: LsmCommunityStorageOptions,
+    ) -> Option<(PathBuf, Vec<PathBuf>)> {
         let graph_name = &options.graph_name;
         let work_space_dir = &options.work_space_dir;
 
@@ -386,26 +627,53 @@ impl LsmCommunityStorageInner {
             .map(|entry| entry.path())?;
 
         // Find all bucket_*.bkt* files
-        let bucket_paths: Vec<PathBuf> = std::fs::read_dir(&graph_dir)
-            .ok()?
-            .filter_map(Result::ok)
-            .filter(|entry| {
-                let filename = entry.file_name().to_string_lossy().to_string();
+        let bucket_paths = Self::find_bucket_files(&graph_dir, options.io_backend).ok()?;
+
+        // An empty graph has a vertex index but no bucket file
+        // Return the vertex index path and all bucket paths
+        Some((vertex_index_path, bucket_paths))
+    }
+
+    /// Find the `bucket_*.bkt*` files of a graph directory, in memory with the memory backend.
+    fn find_bucket_files(graph_dir: &Path, backend: IoBackend) -> Result<Vec<PathBuf>> {
+        let files: Vec<PathBuf> = if backend == IoBackend::Memory {
+            MemoryIo::files_in(graph_dir)
+        } else {
+            std::fs::read_dir(graph_dir)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<std::io::Result<_>>()?
+        };
+        Ok(files
+            .into_iter()
+            .filter(|path| {
+                let filename = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
                 filename.starts_with("bucket_") && filename.contains(".bkt")
             })
-            .map(|entry| entry.path())
-            .collect();
+            .collect())
+    }
+}
 
-        // Check if we found at least one bucket file
-        if bucket_paths.is_empty() {
-            return None;
-        }
+/// Error of a mutation on a graph opened read-only.
+///
+/// Returned inside the `anyhow::Error` of every mutation, find it with
+/// `error.downcast_ref::<ReadOnlyError>()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadOnlyError {
+    /// Name of the graph.
+    pub graph_name: String,
+}
 
-        // Return the vertex index path and all bucket paths
-        Some((vertex_index_path, bucket_paths))
+impl std::fmt::Display for ReadOnlyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Graph {} is opened read-only", self.graph_name)
     }
 }
 
+impl std::error::Error for ReadOnlyError {}
+
 #[allow(dead_code)]
 pub struct LsmCommunity {
     /// Inner storage.
@@ -414,6 +682,21 @@ pub struct LsmCommunity {
     pub(crate) external_db: Arc<ExternalStorage>,
     /// Vertex Index.
     pub(crate) vertex_index: Arc<RwLock<VertexIndex>>,
+    /// Weakly connected components maintained on edge updates, built on first query.
+    pub(crate) incremental_wcc: Mutex<Option<IncrementalWcc>>,
+    /// Reachability index patched or dropped on edge updates, built on first query.
+    pub(crate) reachability_index: Mutex<Option<ReachabilityIndex>>,
+    /// In-neighbor lists of all vertices dropped on edge updates, built on first query.
+    pub(crate) in_adjacency: Mutex<Option<Arc<InAdjacency>>>,
+    /// Number of edge updates since the open, tells whether the in-neighbor lists were
+    /// built across an update.
+    pub(crate) edge_generation: AtomicU64,
+    /// Expiry times of the edges inserted with a time-to-live, with their source and
+    /// neighbor, read from the delta logs on open. The derived indexes are dropped once one
+    /// of them passes.
+    pub(crate) edge_expiries: Mutex<BinaryHeap<Reverse<(u64, VId, VId)>>>,
+    /// Rejects every mutation when set.
+    read_only: bool,
     /// Notifies the L0 flush thread to stop working.
     flush_notifier: crossbeam_channel::Sender<()>,
     /// The handle for the flush thread.
@@ -421,30 +704,120 @@ pub struct LsmCommunity {
 }
 
 impl LsmCommunity {
+    /// Name of the edge property holding the label of an edge.
+    pub const EDGE_LABEL_PROPERTY: &'static str = "label";
+
+    /// Opens the graph `work_space_dir/graph_name`, building it from
+    /// `./data/{graph_name}.graph` if it does not exist yet.
     pub fn open(options: LsmCommunityStorageOptions) -> anyhow::Result<Arc<Self>> {
-        // Create external storage
-        let external_db = Arc::new(ExternalStorage::new(options.clone())?);
+        Self::open_with_mode(options, None, OpenMode::ReadWrite)
+    }
+
+    /// Creates the graph `work_space_dir/graph_name` from an in-memory graph.
+    ///
+    /// An empty [`CsrGraph`] creates a graph without vertices, to be filled with
+    /// [`LsmCommunity::insert_vertex`] and [`LsmCommunity::insert_edge`].
+    ///
+    /// # Returns
+    ///
+    /// Returns the opened graph, or an error if the graph already exists.
+    pub fn create(
+        options: LsmCommunityStorageOptions,
+        graph: CsrGraph,
+    ) -> anyhow::Result<Arc<Self>> {
+        if LsmCommunityStorageInner::check_recover(options.clone()).is_some() {
+            return Err(anyhow::anyhow!(
+                "Graph {} already exists",
+                options.graph_name
+            ));
+        }
+        Self::open_with_mode(options, Some(graph), OpenMode::ReadWrite)
+    }
+
+    /// Opens an existing graph read-only.
+    ///
+    /// Buckets are opened without write access and RocksDB as a secondary instance, so the
+    /// graph can be read while another process, the primary, writes it. Every mutation
+    /// fails with a [`ReadOnlyError`]. The graph shows the primary's state at the open,
+    /// and later commits after [`LsmCommunity::catch_up`].
+    ///
+    /// # Returns
+    ///
+    /// Returns the opened graph, or an error if the graph does not exist.
+    pub fn open_read_only(options: LsmCommunityStorageOptions) -> anyhow::Result<Arc<Self>> {
+        Self::open_with_mode(options, None, OpenMode::ReadOnly)
+    }
+
+    /// Opens the graph in the given mode.
+    ///
+    /// A missing graph is built from `graph`, or from its graph file if `graph` is `None`.
+    /// A read-only open never builds and fails if the graph does not exist.
+    pub(crate) fn open_with_mode(
+        mut options: LsmCommunityStorageOptions,
+        graph: Option<CsrGraph>,
+        mode: OpenMode,
+    ) -> anyhow::Result<Arc<Self>> {
+        options.derive_defaults();
+        options.validate()?;
 
         // Check if we can recover from files
         let giant_vertex_map: FxHashMap<VId, VIdList>;
         let inner: LsmCommunityStorageInner;
         let vertex_index: VertexIndex;
+        let recovered;
         if let Some((vertex_index_path, bucket_paths)) =
             LsmCommunityStorageInner::check_recover(options.clone())
         {
+            recovered = true;
             // Create state
-            (inner, giant_vertex_map, vertex_index) = LsmCommunityStorageInner::open(
-                true,
-                Some(vertex_index_path.clone()),
-                Some(bucket_paths.clone()),
+            (inner, giant_vertex_map, vertex_index) = LsmCommunityStorageInner::recover(
+                &vertex_index_path,
+                &bucket_paths,
                 options,
+                mode == OpenMode::ReadOnly,
             )?;
+        } else if mode == OpenMode::ReadOnly {
+            return Err(anyhow::anyhow!("Graph {} not exists", options.graph_name));
         } else {
+            recovered = false;
             // Create state
             (inner, giant_vertex_map, vertex_index) =
-                LsmCommunityStorageInner::open(false, None, None, options)?;
+                LsmCommunityStorageInner::open_with_graph(false, None, None, graph, options)?;
         }
 
+        // Create external storage with the memory left to it by the budget.
+        let external_db = Arc::new(match mode {
+            OpenMode::ReadOnly => {
+                ExternalStorage::open_secondary((*inner.options).clone(), &inner.memory_budget)?
+            }
+            OpenMode::ReadWrite => {
+                ExternalStorage::with_memory_budget((*inner.options).clone(), &inner.memory_budget)?
+            }
+        });
+
+        // A saved reachability index is kept only while the storage has not moved on since
+        // it was built. Without a write-ahead log a crash can take the sequence number back
+        // to one the index was not built at, it is rebuilt then.
+        let options = &inner.options;
+        let reachability_index =
+            if recovered && options.reachability_index && !options.external_storage.wal.disable_wal
+            {
+                ReachabilityIndex::load(
+                    &ReachabilityIndex::path(&options.work_space_dir, &options.graph_name),
+                    external_db.latest_sequence_number(),
+                )
+            } else {
+                None
+            };
+
+        // Edges expired since the loaded reachability index was built drop it on its first
+        // query, like the ones expiring later
+        let expiries_after = match &reachability_index {
+            Some(index) => index.built_at(),
+            None => generate_timestamp_micros(),
+        };
+        let edge_expiries = external_db.delta_expiries(expiries_after)?;
+
         #[cfg(test)]
         println!("Handle Giant Vertex {}", giant_vertex_map.len());
 
@@ -459,11 +832,191 @@ impl LsmCommunity {
             inner: Arc::new(inner),
             external_db,
             vertex_index: Arc::new(RwLock::new(vertex_index)),
+            incremental_wcc: Mutex::new(None),
+            reachability_index: Mutex::new(reachability_index),
+            in_adjacency: Mutex::new(None),
+            edge_generation: AtomicU64::new(0),
+            edge_expiries: Mutex::new(edge_expiries.into_iter().map(Reverse).collect()),
+            read_only: mode == OpenMode::ReadOnly,
             flush_notifier: tx,
             flush_thread: Mutex::new(None),
         }))
     }
 
+    /// The options the graph was opened with.
+    pub fn options(&self) -> &LsmCommunityStorageOptions {
+        &self.inner.options
+    }
+
+    /// Whether the graph was opened read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Fails with a [`ReadOnlyError`] if the graph was opened read-only, called before any
+    /// mutation.
+    pub(crate) fn check_writable(&self) -> anyhow::Result<()> {
+        if self.read_only {
+            return Err(ReadOnlyError {
+                graph_name: self.inner.options.graph_name.clone(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Returns an error if the graph is opened without the `edge_history` option, the past
+    /// windows of the temporal queries would be answered with the latest operations only.
+    pub(crate) fn check_edge_history(&self) -> anyhow::Result<()> {
+        if !self.inner.options.edge_history {
+            anyhow::bail!(
+                "Graph {} is opened without the edge_history option",
+                self.inner.options.graph_name
+            );
+        }
+        Ok(())
+    }
+
+    /// Drops the derived indexes, the weakly connected components, the reachability index
+    /// and the in-neighbor lists, if an edge inserted with a time-to-live expired since they
+    /// were built. Called before they are queried, they are rebuilt without the expired edges.
+    ///
+    /// The labels of the expired edges are removed with them.
+    pub(crate) fn expire_derived_indexes(&self) {
+        let now = generate_timestamp_micros();
+        let mut expired_edges = Vec::new();
+        {
+            let mut edge_expiries = self.edge_expiries.lock();
+            while let Some(Reverse((expires_at, src, dst))) = edge_expiries.peek().copied() {
+                if expires_at > now {
+                    break;
+                }
+                edge_expiries.pop();
+                expired_edges.push((src, dst));
+            }
+        }
+        if expired_edges.is_empty() {
+            return;
+        }
+        {
+            let mut reachability_index = self.reachability_index.lock();
+            let mut incremental_wcc = self.incremental_wcc.lock();
+            self.apply_reachability_update(&mut reachability_index, ReachabilityUpdate::Invalidate);
+            *incremental_wcc = None;
+            self.invalidate_in_adjacency();
+        }
+        if !self.read_only {
+            for (src, dst) in expired_edges {
+                if let Err(e) = self.remove_expired_edge_label(src, dst) {
+                    eprintln!(
+                        "Failed to remove the label of expired edge {} -> {}: {}",
+                        src, dst, e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Removes the label of an expired edge, unless the edge was inserted again since.
+    ///
+    /// Like the expiry, the removal is not recorded in the change log.
+    fn remove_expired_edge_label(&self, src_vertex: VId, dst_vertex: VId) -> anyhow::Result<()> {
+        if !self.is_edge_removed(src_vertex, dst_vertex)? {
+            return Ok(());
+        }
+        let label_property = Self::EDGE_LABEL_PROPERTY.to_owned();
+        if self
+            .external_db
+            .get_edge_property(src_vertex, dst_vertex, label_property.clone())?
+            .is_some()
+        {
+            self.external_db.remove_expired_edge_property(
+                src_vertex,
+                dst_vertex,
+                label_property,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Whether the latest delta operation of an edge removes it, an expired add included, or
+    /// without one whether the base graph does not hold it.
+    fn is_edge_removed(&self, src_vertex: VId, dst_vertex: VId) -> anyhow::Result<bool> {
+        let latest = self
+            .external_db
+            .read_delta_log(src_vertex)?
+            .and_then(|log| {
+                log.ops()
+                    .iter()
+                    .rev()
+                    .find(|op| op.neighbor == dst_vertex)
+                    .copied()
+            });
+        match latest.map(|op| op.get_op_type()) {
+            Some(Some(DeltaOpType::AddNeighbor)) => Ok(false),
+            Some(Some(DeltaOpType::RemoveNeighbor)) => Ok(true),
+            // The removes of edges outside the base graph are dropped from the delta log
+            Some(None) | None => Ok(!self.is_base_edge(src_vertex, dst_vertex)?),
+        }
+    }
+
+    /// Whether the base graph, the buckets and the giant vertices, holds an edge.
+    fn is_base_edge(&self, src_vertex: VId, dst_vertex: VId) -> anyhow::Result<bool> {
+        let (neighbors, _) = self.read_neighbor(src_vertex, false)?;
+        Ok(neighbors.is_some_and(|mut neighbors| neighbors.any(|vid| vid == dst_vertex)))
+    }
+
+    /// Drops the in-neighbor lists after an edge update, called once the update is logged.
+    fn invalidate_in_adjacency(&self) {
+        self.edge_generation.fetch_add(1, Ordering::AcqRel);
+        *self.in_adjacency.lock() = None;
+    }
+
+    /// Catches up with the primary that writes a graph opened read-only.
+    ///
+    /// Reloads the buckets and the vertex index from their files, then replays the deltas
+    /// and properties the primary committed to RocksDB. Derived indexes, the weakly connected
+    /// components and the reachability index, are rebuilt on their next query. If the
+    /// primary is writing the vertex index at the same time, the reload fails and can be
+    /// retried.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the graph is not opened read-only or its files cannot be read.
+    pub fn catch_up(&self) -> anyhow::Result<()> {
+        if !self.read_only {
+            return Err(anyhow::anyhow!(
+                "Graph {} is not opened read-only",
+                self.inner.options.graph_name
+            ));
+        }
+        let (vertex_index_path, bucket_paths) = LsmCommunityStorageInner::check_recover(
+            (*self.inner.options).clone(),
+        )
+        .ok_or_else(|| anyhow::anyhow!("Graph {} not exists", self.inner.options.graph_name))?;
+        // Buckets and vertices first, the deltas replayed next may refer to new vertices
+        self.inner.catch_up_buckets(&bucket_paths)?;
+        let vertex_index = VertexIndex::deserialize_from_file(&vertex_index_path)?;
+        let mut reachability_index = self.reachability_index.lock();
+        let mut incremental_wcc = self.incremental_wcc.lock();
+        // Readers wait until the vertices and the deltas they refer to are both caught up
+        let mut vertex_index_guard = self.vertex_index.write();
+        self.external_db.try_catch_up_with_primary()?;
+        *vertex_index_guard = vertex_index;
+        drop(vertex_index_guard);
+        // The primary may have inserted edges with a time-to-live
+        *self.edge_expiries.lock() = self
+            .external_db
+            .delta_expiries(generate_timestamp_micros())?
+            .into_iter()
+            .map(Reverse)
+            .collect();
+        *reachability_index = None;
+        *incremental_wcc = None;
+        self.invalidate_in_adjacency();
+        Ok(())
+    }
+
     /// Get the vertex count in this storage engine
     pub fn vertex_count(&self) -> usize {
         let vertex_index_state = self.vertex_index.read();
@@ -486,18 +1039,129 @@ impl LsmCommunity {
     }
 
     /// Insert an edge from src_vertex to dst_vertex.
+    ///
+    /// The edge expires after the `edge_ttl.default_ttl_secs` option, if set.
     pub fn insert_edge(&self, src_vertex: VId, dst_vertex: VId) -> anyhow::Result<()> {
+        let ttl_secs = self.inner.options.edge_ttl.default_ttl_secs;
+        self.insert_edge_expiring(src_vertex, dst_vertex, ttl_secs, None)
+    }
+
+    /// Insert an edge from src_vertex to dst_vertex that expires after `ttl`.
+    ///
+    /// An expired edge is removed, also if it is in the base graph, and its label with it.
+    /// The time-to-live is rounded up to seconds, a zero `ttl` never expires.
+    pub fn insert_edge_with_ttl(
+        &self,
+        src_vertex: VId,
+        dst_vertex: VId,
+        ttl: Duration,
+    ) -> anyhow::Result<()> {
+        let ttl_secs = ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0);
+        if ttl_secs > DeltaOperation::MAX_TTL_SECS {
+            return Err(anyhow::anyhow!(
+                "Edge time-to-live {}s exceeds {}s",
+                ttl_secs,
+                DeltaOperation::MAX_TTL_SECS
+            ));
+        }
+        self.insert_edge_expiring(src_vertex, dst_vertex, ttl_secs, None)
+    }
+
+    /// Insert an edge from src_vertex to dst_vertex with a label.
+    ///
+    /// The edge expires after the time-to-live of its label in the `edge_ttl.labels`
+    /// option, or the default one. The label is kept as the edge property named
+    /// [`Self::EDGE_LABEL_PROPERTY`], written with the edge, and is removed when the edge
+    /// expires.
+    pub fn insert_labeled_edge(
+        &self,
+        src_vertex: VId,
+        dst_vertex: VId,
+        label: &str,
+    ) -> anyhow::Result<()> {
+        let ttl_secs = self.inner.options.edge_ttl.ttl_secs(Some(label));
+        self.insert_edge_expiring(src_vertex, dst_vertex, ttl_secs, Some(label))
+    }
+
+    /// Get the label of an edge inserted with [`LsmCommunity::insert_labeled_edge`].
+    ///
+    /// A removed or expired edge has no label.
+    pub fn get_edge_label(
+        &self,
+        src_vertex: VId,
+        dst_vertex: VId,
+    ) -> anyhow::Result<Option<String>> {
+        if self.is_edge_removed(src_vertex, dst_vertex)? {
+            return Ok(None);
+        }
+        let label = self.external_db.get_edge_property(
+            src_vertex,
+            dst_vertex,
+            Self::EDGE_LABEL_PROPERTY.to_owned(),
+        )?;
+        Ok(label.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    /// Insert an edge whose add operation expires after `ttl_secs` seconds, 0 never, and its
+    /// label in the same write if any.
+    fn insert_edge_expiring(
+        &self,
+        src_vertex: VId,
+        dst_vertex: VId,
+        ttl_secs: u64,
+        label: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.check_writable()?;
         // Check the type of source vertex.
         if let Some(_) = self.check_vertex_state(src_vertex) {
             if let Some(_) = self.check_vertex_state(dst_vertex) {
-                self.external_db.append_delta(
-                    src_vertex,
-                    DeltaOperation::new(
-                        generate_timestamp_micros(),
-                        DeltaOpType::AddNeighbor,
-                        dst_vertex,
-                    ),
+                let outside_base = !self.is_base_edge(src_vertex, dst_vertex)?;
+                // Hold the derived indexes while logging, so that they follow the delta order
+                let mut reachability_index = self
+                    .inner
+                    .options
+                    .reachability_index
+                    .then(|| self.reachability_index.lock());
+                let reachability_update = match reachability_index.as_deref() {
+                    Some(state) => {
+                        self.prepare_reachability_update(state, src_vertex, dst_vertex, true)?
+                    }
+                    None => ReachabilityUpdate::Unchanged,
+                };
+                let mut incremental_wcc = self
+                    .inner
+                    .options
+                    .incremental_wcc
+                    .then(|| self.incremental_wcc.lock());
+                let op = DeltaOperation::with_ttl(
+                    generate_timestamp_micros(),
+                    DeltaOpType::AddNeighbor,
+                    dst_vertex,
+                    ttl_secs,
                 )
+                .with_outside_base(outside_base);
+                match label {
+                    Some(label) => self.external_db.append_delta_with_edge_property(
+                        src_vertex,
+                        op,
+                        Self::EDGE_LABEL_PROPERTY.to_owned(),
+                        label.as_bytes(),
+                    )?,
+                    None => self.external_db.append_delta(src_vertex, op)?,
+                }
+                if let Some(wcc) = incremental_wcc.as_mut().and_then(|state| state.as_mut()) {
+                    wcc.on_edge_inserted(src_vertex, dst_vertex);
+                }
+                self.invalidate_in_adjacency();
+                if let Some(expires_at) = op.expires_at() {
+                    self.edge_expiries
+                        .lock()
+                        .push(Reverse((expires_at, src_vertex, dst_vertex)));
+                }
+                if let Some(state) = reachability_index.as_deref_mut() {
+                    self.apply_reachability_update(state, reachability_update);
+                }
+                Ok(())
             } else {
                 // If the vertex not exists, return error.
                 Err(anyhow::anyhow!("Vertex not exists"))
@@ -510,17 +1174,45 @@ impl LsmCommunity {
 
     /// Remove an edge from src_vertex to dst_vertex.
     pub fn remove_edge(&self, src_vertex: VId, dst_vertex: VId) -> anyhow::Result<()> {
+        self.check_writable()?;
         // Check the type of source vertex.
         if let Some(_) = self.check_vertex_state(src_vertex) {
             if let Some(_) = self.check_vertex_state(dst_vertex) {
+                let outside_base = !self.is_base_edge(src_vertex, dst_vertex)?;
+                // Hold the derived indexes while logging, so that they follow the delta order
+                let mut reachability_index = self
+                    .inner
+                    .options
+                    .reachability_index
+                    .then(|| self.reachability_index.lock());
+                let reachability_update = match reachability_index.as_deref() {
+                    Some(state) => {
+                        self.prepare_reachability_update(state, src_vertex, dst_vertex, false)?
+                    }
+                    None => ReachabilityUpdate::Unchanged,
+                };
+                let mut incremental_wcc = self
+                    .inner
+                    .options
+                    .incremental_wcc
+                    .then(|| self.incremental_wcc.lock());
                 self.external_db.append_delta(
                     src_vertex,
                     DeltaOperation::new(
                         generate_timestamp_micros(),
                         DeltaOpType::RemoveNeighbor,
                         dst_vertex,
-                    ),
-                )
+                    )
+                    .with_outside_base(outside_base),
+                )?;
+                if let Some(wcc) = incremental_wcc.as_mut().and_then(|state| state.as_mut()) {
+                    wcc.on_edge_removed(src_vertex, dst_vertex);
+                }
+                self.invalidate_in_adjacency();
+                if let Some(state) = reachability_index.as_deref_mut() {
+                    self.apply_reachability_update(state, reachability_update);
+                }
+                Ok(())
             } else {
                 // If the vertex not exists, return error.
                 Err(anyhow::anyhow!("Vertex not exists"))
@@ -533,6 +1225,7 @@ impl LsmCommunity {
 
     /// Put the vertex property, through all field.
     pub fn put_vertex_property(&self, vertex_id: VId, property_bytes: &[u8]) -> anyhow::Result<()> {
+        self.check_writable()?;
         // Check the type of source vertex.
         if let Some(_) = self.check_vertex_state(vertex_id) {
             self.external_db
@@ -562,6 +1255,7 @@ impl LsmCommunity {
         dst_vertex: VId,
         property_bytes: &[u8],
     ) -> anyhow::Result<()> {
+        self.check_writable()?;
         // Check the type of source vertex.
         if let Some(_) = self.check_vertex_state(src_vertex) {
             if let Some(_) = self.check_vertex_state(dst_vertex) {
@@ -602,8 +1296,44 @@ impl LsmCommunity {
         }
     }
 
+    /// Subscribe to the change log of the graph from a sequence number on.
+    ///
+    /// Needs the `change_log` option. The subscription returns every vertex insertion, edge
+    /// insertion and removal, and property put and removal from `from_seq` on, in commit
+    /// order. Edges that expire are not logged, nor the labels removed with them.
+    ///
+    /// # Arguments
+    /// * `from_seq` - Sequence number of the first change, e.g. the next one of the last
+    ///   change processed, or [`LsmCommunity::next_change_seq`] for new changes only
+    ///
+    /// # Returns
+    /// * `Result<ChangeSubscription>` - The subscription, or an error if the change log is
+    ///   disabled or the retention already dropped the change `from_seq`
+    pub fn subscribe_changes(&self, from_seq: u64) -> anyhow::Result<ChangeSubscription> {
+        self.external_db.subscribe_changes(from_seq)
+    }
+
+    /// Get the sequence number of the next change written to the change log.
+    pub fn next_change_seq(&self) -> anyhow::Result<u64> {
+        self.external_db.next_change_seq()
+    }
+
+    /// Drop the changes beyond the retention of the change log now.
+    ///
+    /// # Returns
+    /// * `Result<u64>` - The sequence number of the oldest change retained
+    pub fn trim_change_log(&self) -> anyhow::Result<u64> {
+        self.check_writable()?;
+        self.external_db.trim_change_log()
+    }
+
     /// Insert a new vertex in async manner.
+    ///
+    /// The `AddVertex` change is logged before the vertex index is saved in the background,
+    /// the vertex index file and the change log cannot be written atomically. A crash before
+    /// the save completes leaves a logged vertex that is missing once the graph is reopened.
     pub fn insert_vertex_async(&self, vertex_id: VId) -> anyhow::Result<VId> {
+        self.check_writable()?;
         // Check if the vertex already exists
         if let Some(_) = self.check_vertex_state(vertex_id) {
             return Err(anyhow::anyhow!("Vertex already exists"));
@@ -611,6 +1341,9 @@ impl LsmCommunity {
 
         let mut vertex_index_state = self.vertex_index.write();
         let result_vertex_id = vertex_index_state.add_giant_vertex()?;
+        // Logged under the write lock, so that the changes follow the vertex IDs
+        self.external_db
+            .log_change(ChangeEvent::vertex(ChangeKind::AddVertex, result_vertex_id))?;
 
         // Drop write lock immediately after modification
         drop(vertex_index_state);
@@ -637,7 +1370,12 @@ impl LsmCommunity {
     }
 
     /// Insert a new vertex.
+    ///
+    /// The vertex index is saved before the `AddVertex` change is logged, the vertex index
+    /// file and the change log cannot be written atomically. A crash in between leaves a
+    /// vertex without its change, subscribers see its first edge or property change only.
     pub fn insert_vertex(&self) -> anyhow::Result<VId> {
+        self.check_writable()?;
         let mut vertex_index_state = self.vertex_index.write();
         let result_vertex_id = vertex_index_state.add_giant_vertex()?;
 
@@ -648,6 +1386,8 @@ impl LsmCommunity {
 
         // Save immediately while holding the write lock
         vertex_index_state.serialize_to_file(&vertex_index_path, 3)?;
+        self.external_db
+            .log_change(ChangeEvent::vertex(ChangeKind::AddVertex, result_vertex_id))?;
 
         Ok(result_vertex_id)
     }
@@ -710,26 +1450,211 @@ impl LsmCommunity {
 
     /// Read the out neighbors of a vertex.
     pub fn read_out_neighbor_clone(&self, vertex_id: VId) -> anyhow::Result<VIdList> {
+        Ok(self.read_out_neighbor_iter(vertex_id)?.collect())
+    }
+
+    /// Stream the out neighbors of a vertex with its delta log applied.
+    ///
+    /// # Arguments
+    /// * `vertex_id` - The vertex ID to query
+    ///
+    /// # Returns
+    /// * `Result<MergedNeighborIterator<UnifiedNeighborIterator>>` - The effective neighbors
+    ///   in ascending order, without duplicates
+    pub fn read_out_neighbor_iter(
+        &self,
+        vertex_id: VId,
+    ) -> anyhow::Result<MergedNeighborIterator<UnifiedNeighborIterator>> {
         let (iter, delta_opt) = self.read_neighbor(vertex_id, true)?;
+        Ok(MergedNeighborIterator::new(
+            iter.unwrap_or_else(UnifiedNeighborIterator::empty),
+            delta_opt,
+        ))
+    }
 
-        // Collect base neighbors
-        let mut base_neighbors = match iter {
-            Some(iter) => iter.collect::<Vec<_>>(),
-            None => Vec::new(),
-        };
+    /// Stream the out neighbors of a vertex with its delta log applied.
+    /// Used for graph analytic algorithms that hold the vertex index in memory.
+    ///
+    /// # Arguments
+    /// * `vertex_id` - The vertex ID to query
+    /// * `vertex_index` - The vertex index held by the caller
+    ///
+    /// # Returns
+    /// * `Result<MergedNeighborIterator<UnifiedNeighborIterator>>` - The effective neighbors
+    ///   in ascending order, without duplicates
+    pub fn read_out_neighbor_iter_hold_index(
+        &self,
+        vertex_id: VId,
+        vertex_index: &VertexIndex,
+    ) -> anyhow::Result<MergedNeighborIterator<UnifiedNeighborIterator>> {
+        let (iter, delta_opt) =
+            self.read_neighbor_hold_index_vertex(vertex_id, true, vertex_index)?;
+        Ok(MergedNeighborIterator::new(
+            iter.unwrap_or_else(UnifiedNeighborIterator::empty),
+            delta_opt,
+        ))
+    }
 
-        // If no delta, return base directly
-        let Some(delta) = delta_opt else {
-            return Ok(base_neighbors);
-        };
+    /// Read the validity intervals of the out edges of a vertex.
+    ///
+    /// Edges of the base graph are valid from 0, the adds and removes of the delta log move
+    /// the bounds, see [`EdgeInterval::from_history`]. With the `edge_history` option every
+    /// past interval is known, otherwise only the latest one of each edge left in the delta
+    /// log.
+    ///
+    /// # Arguments
+    /// * `vertex_id` - The vertex ID to query
+    ///
+    /// # Returns
+    /// * `Result<Vec<EdgeInterval>>` - The intervals ordered by neighbor then time, empty
+    ///   for an invalid vertex
+    pub fn read_out_edge_intervals(&self, vertex_id: VId) -> anyhow::Result<Vec<EdgeInterval>> {
+        let vertex_index_state = self.vertex_index.read();
+        self.read_out_edge_intervals_hold_index(vertex_id, &vertex_index_state)
+    }
 
-        // Apply delta operations efficiently
-        #[cfg(test)]
-        println!("Applying delta to neighbors");
+    /// Read the validity intervals of the out edges of a vertex.
+    /// Used for graph analytic algorithms that hold the vertex index in memory.
+    ///
+    /// # Arguments
+    /// * `vertex_id` - The vertex ID to query
+    /// * `vertex_index` - The vertex index held by the caller
+    ///
+    /// # Returns
+    /// * `Result<Vec<EdgeInterval>>` - The intervals ordered by neighbor then time, empty
+    ///   for an invalid vertex
+    pub fn read_out_edge_intervals_hold_index(
+        &self,
+        vertex_id: VId,
+        vertex_index: &VertexIndex,
+    ) -> anyhow::Result<Vec<EdgeInterval>> {
+        let (iter, _) = self.read_neighbor_hold_index_vertex(vertex_id, false, vertex_index)?;
+        let history = self.external_db.read_edge_history(vertex_id)?;
+        Ok(EdgeInterval::from_history(
+            iter.unwrap_or_else(UnifiedNeighborIterator::empty),
+            history,
+        ))
+    }
+
+    /// Read the out neighbors of a vertex over an edge valid at some time of a window.
+    ///
+    /// # Arguments
+    /// * `vertex_id` - The vertex ID to query
+    /// * `from` - Start of the window, included, in microseconds since the Unix epoch
+    /// * `to` - End of the window, excluded
+    ///
+    /// # Returns
+    /// * `Result<VIdList>` - The neighbors in ascending order, without duplicates, or an
+    ///   error without the `edge_history` option
+    pub fn read_out_neighbor_in_window(
+        &self,
+        vertex_id: VId,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<VIdList> {
+        self.check_edge_history()?;
+        let mut neighbors: VIdList = self
+            .read_out_edge_intervals(vertex_id)?
+            .into_iter()
+            .filter(|interval| interval.overlaps(from, to))
+            .map(|interval| interval.neighbor)
+            .collect();
+        neighbors.dedup();
+        Ok(neighbors)
+    }
 
-        Self::apply_delta_to_neighbors(&mut base_neighbors, &delta);
+    /// Read the out neighbors of several vertices at once.
+    ///
+    /// Block reads of normal vertices are grouped by (virtual community, page), deduplicated
+    /// and issued as one batched read per bucket, the loaded blocks fill the block cache.
+    ///
+    /// # Arguments
+    /// * `vertex_ids` - The vertices to read
+    ///
+    /// # Returns
+    /// * `Result<Vec<VIdList>>` - Neighbor lists with deltas applied, in input order.
+    ///   Invalid vertices get an empty list.
+    pub fn read_out_neighbors_batch(&self, vertex_ids: &[VId]) -> anyhow::Result<Vec<VIdList>> {
+        let vertex_index_state = self.vertex_index.read();
+        self.read_out_neighbors_batch_hold_index(vertex_ids, &vertex_index_state)
+    }
+
+    /// Read the out neighbors of several vertices at once.
+    /// Used for graph analytic algorithms that hold the vertex index in memory.
+    ///
+    /// # Arguments
+    /// * `vertex_ids` - The vertices to read
+    /// * `vertex_index` - The vertex index held by the caller
+    ///
+    /// # Returns
+    /// * `Result<Vec<VIdList>>` - Neighbor lists with deltas applied, in input order.
+    ///   Invalid vertices get an empty list.
+    pub fn read_out_neighbors_batch_hold_index(
+        &self,
+        vertex_ids: &[VId],
+        vertex_index: &VertexIndex,
+    ) -> anyhow::Result<Vec<VIdList>> {
+        let vertex_count = vertex_index.vertex_array.len();
+        let normal_vertices = vertex_ids
+            .iter()
+            .copied()
+            .filter(|&vid| {
+                (vid as usize) < vertex_count && !vertex_index.vertex_array[vid as usize].is_giant()
+            })
+            .collect::<Vec<_>>();
+        let mut normal_iters = self
+            .inner
+            .get_neighbor_iters_batch(&normal_vertices, vertex_index)?
+            .into_iter();
+
+        let mut neighbor_lists = Vec::with_capacity(vertex_ids.len());
+        for &vertex_id in vertex_ids {
+            if vertex_id as usize >= vertex_count {
+                neighbor_lists.push(Vec::new());
+                continue;
+            }
+            let delta = self.external_db.read_delta_log(vertex_id)?;
+            let neighbors = if vertex_index.vertex_array[vertex_id as usize].is_giant() {
+                let giant_neighbors = self.external_db.get_giant_vertex(vertex_id);
+                let base = giant_neighbors.iter().flat_map(|list| list.iter().copied());
+                MergedNeighborIterator::new(base, delta).collect()
+            } else {
+                let base = normal_iters.next().into_iter().flatten();
+                MergedNeighborIterator::new(base, delta).collect()
+            };
+            neighbor_lists.push(neighbors);
+        }
+        Ok(neighbor_lists)
+    }
 
-        Ok(base_neighbors)
+    /// Read the out neighbors of several vertices at once, falling back to one read per
+    /// vertex when the batched read fails, so a single unreadable vertex does not drop the
+    /// neighbors of the whole batch. Used for graph analytic algorithms that hold the
+    /// vertex index in memory.
+    ///
+    /// # Arguments
+    /// * `vertex_ids` - The vertices to read
+    /// * `vertex_index` - The vertex index held by the caller
+    ///
+    /// # Returns
+    /// * `Vec<VIdList>` - Neighbor lists with deltas applied, in input order.
+    ///   Invalid and unreadable vertices get an empty list.
+    pub(crate) fn read_out_neighbors_batch_or_each_hold_index(
+        &self,
+        vertex_ids: &[VId],
+        vertex_index: &VertexIndex,
+    ) -> Vec<VIdList> {
+        self.read_out_neighbors_batch_hold_index(vertex_ids, vertex_index)
+            .unwrap_or_else(|_| {
+                vertex_ids
+                    .iter()
+                    .map(|&vertex_id| {
+                        self.read_out_neighbor_iter_hold_index(vertex_id, vertex_index)
+                            .map(|neighbors| neighbors.collect())
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
     }
 
     /// Apply delta operations to the neighbor list in-place
@@ -751,16 +1676,14 @@ impl LsmCommunity {
 
         // Apply each delta operation in order (already sorted by timestamp)
         for op in delta.ops() {
-            match op.op_type {
-                0 => {
-                    // AddNeighbor
+            match op.get_op_type() {
+                Some(DeltaOpType::AddNeighbor) => {
                     neighbor_state.insert(op.neighbor);
                 }
-                1 => {
-                    // RemoveNeighbor
+                Some(DeltaOpType::RemoveNeighbor) => {
                     neighbor_state.remove(&op.neighbor);
                 }
-                _ => {
+                None => {
                     // Reserved for future use, ignore unknown operations
                 }
             }
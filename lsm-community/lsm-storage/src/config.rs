@@ -1,15 +1,17 @@
+use crate::memory::MIN_MEMTABLE_BYTES;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{BufReader, Write};
 use std::path::Path;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LsmCommunityStorageOptions {
-    // Block size in bytes
+    // Block size in bytes, 0 derives the smallest block holding a vertex below the giant boundary
     #[serde(default = "default_block_size")]
     pub block_size: usize,
 
-    // SST size in bytes, also the approximate memtable capacity limit
+    // SST size in bytes, also the approximate memtable capacity limit, 0 derives it from the block size
     #[serde(default = "default_min_bucket_size")]
     pub min_bucket_size: usize,
 
@@ -29,13 +31,253 @@ pub struct LsmCommunityStorageOptions {
     #[serde(default = "default_work_space_dir")]
     pub work_space_dir: String,
 
-    // Cache capacity
-    #[serde(default = "default_block_cache_capacity")]
+    // Block cache capacity in bytes, 0 derives it from the memory budget
+    #[serde(default)]
     pub block_cache_capacity: u64,
 
-    // Giant cache capacity
-    #[serde(default = "default_giant_cache_capacity")]
+    // Giant cache capacity in bytes, 0 derives it from the memory budget
+    #[serde(default)]
     pub giant_cache_capacity: u64,
+
+    // Total memory budget in bytes, shared by the block cache, giant cache, RocksDB cache and memtables
+    #[serde(default = "default_memory_budget")]
+    pub memory_budget: u64,
+
+    // Rebalance the memory budget once the size of the buckets is known
+    #[serde(default)]
+    pub memory_rebalance: bool,
+
+    // Maintain the weakly connected components incrementally on edge updates
+    #[serde(default)]
+    pub incremental_wcc: bool,
+
+    // Maintain the reachability index on edge updates and save it with the graph, every
+    // edge update then reads the neighbors of its source under the index lock
+    #[serde(default)]
+    pub reachability_index: bool,
+
+    // RocksDB tuning of the external storage
+    #[serde(default)]
+    pub external_storage: ExternalStorageOptions,
+
+    // I/O backend of the bucket files: uring, pread, mmap or memory
+    #[serde(default)]
+    pub io_backend: IoBackend,
+
+    // Time-to-live of the inserted edges, by default and per edge label
+    #[serde(default)]
+    pub edge_ttl: EdgeTtlOptions,
+
+    // Retain every add and remove of the edges for temporal queries, otherwise only the
+    // latest operation of each edge is known and temporal queries fail
+    #[serde(default)]
+    pub edge_history: bool,
+
+    // Seconds the operations of the edge history are retained, 0 retains them forever.
+    // Older windows only see the latest operation of each edge
+    #[serde(default)]
+    pub edge_history_retention_secs: u64,
+
+    // Change data capture of the vertex, edge and property mutations
+    #[serde(default)]
+    pub change_log: ChangeLogOptions,
+}
+
+/// RocksDB tuning of the external storage, one section per column family.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExternalStorageOptions {
+    // Column family of the giant vertex adjacency lists
+    #[serde(default = "ColumnFamilyOptions::giant_vertices")]
+    pub giant_vertices: ColumnFamilyOptions,
+
+    // Column family of the delta logs
+    #[serde(default = "ColumnFamilyOptions::deltas")]
+    pub deltas: ColumnFamilyOptions,
+
+    // Column family of the vertex properties
+    #[serde(default = "ColumnFamilyOptions::vertex_properties")]
+    pub vertex_properties: ColumnFamilyOptions,
+
+    // Column family of the edge properties
+    #[serde(default = "ColumnFamilyOptions::edge_properties")]
+    pub edge_properties: ColumnFamilyOptions,
+
+    // Column family of the edge history, written with the edge_history option
+    #[serde(default = "ColumnFamilyOptions::edge_history")]
+    pub edge_history: ColumnFamilyOptions,
+
+    // Column family of the change log, written with the change_log option
+    #[serde(default = "ColumnFamilyOptions::change_log")]
+    pub change_log: ColumnFamilyOptions,
+
+    // Write-ahead log and sync policy of every write
+    #[serde(default)]
+    pub wal: WalOptions,
+}
+
+/// RocksDB tuning of a single column family.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ColumnFamilyOptions {
+    // Memtable size in bytes, 0 derives it from the memtable share of the memory budget
+    #[serde(default)]
+    pub write_buffer_size: u64,
+
+    // Maximum number of memtables, including the one being written
+    #[serde(default = "default_max_write_buffer_number")]
+    pub max_write_buffer_number: i32,
+
+    // Target size of the SST files in bytes
+    #[serde(default = "default_target_file_size_base")]
+    pub target_file_size_base: u64,
+
+    // Number of L0 files that triggers a compaction
+    #[serde(default = "default_level_zero_compaction_trigger")]
+    pub level_zero_compaction_trigger: i32,
+
+    // Number of L0 files that slows down the writes
+    #[serde(default = "default_level_zero_slowdown_trigger")]
+    pub level_zero_slowdown_trigger: i32,
+
+    // Private block cache size in bytes, 0 shares the RocksDB cache of the memory budget
+    #[serde(default)]
+    pub block_cache_size: u64,
+
+    // Compression of the SST blocks
+    #[serde(default)]
+    pub compression: CompressionType,
+
+    // Bits per key of the bloom filter, 0 disables the filter
+    #[serde(default)]
+    pub bloom_filter_bits: f64,
+}
+
+/// Compression algorithm of the SST blocks of a column family.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionType {
+    None,
+    #[default]
+    Snappy,
+    Zlib,
+    Bz2,
+    Lz4,
+    Lz4hc,
+    Zstd,
+}
+
+/// I/O backend of the bucket files, see [`crate::bucket::io::BucketIo`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IoBackend {
+    /// Linux io_uring, batched reads in one submission. Falls back to `pread` elsewhere.
+    Uring,
+    /// Positional reads and writes, one system call each.
+    Pread,
+    /// Reads from a shared memory map through the page cache, for read-mostly deployments.
+    /// Never uses direct I/O, falls back to `pread` on platforms without `mmap`.
+    Mmap,
+    /// Files kept in process memory and keyed by path, nothing reaches the disk.
+    Memory,
+}
+
+impl Default for IoBackend {
+    fn default() -> Self {
+        if cfg!(target_os = "linux") {
+            IoBackend::Uring
+        } else {
+            IoBackend::Pread
+        }
+    }
+}
+
+impl std::str::FromStr for IoBackend {
+    type Err = ConfigError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "uring" => Ok(IoBackend::Uring),
+            "pread" => Ok(IoBackend::Pread),
+            "mmap" => Ok(IoBackend::Mmap),
+            "memory" => Ok(IoBackend::Memory),
+            _ => Err(ConfigError::InvalidValue(format!(
+                "unknown io_backend {:?}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Write-ahead log and sync policy of the external storage.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalOptions {
+    // Skip the write-ahead log, writes since the last memtable flush are lost on a crash
+    #[serde(default)]
+    pub disable_wal: bool,
+
+    // Sync the write-ahead log to disk before a write returns
+    #[serde(default)]
+    pub sync: bool,
+
+    // Total size of the write-ahead log files in bytes that forces a memtable flush,
+    // 0 lets RocksDB derive it from the write buffers
+    #[serde(default)]
+    pub max_total_wal_size: u64,
+}
+
+/// Time-to-live of the inserted edges, see [`crate::comm_io::LsmCommunity::insert_labeled_edge`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeTtlOptions {
+    // Time-to-live in seconds of the edges inserted without a label, 0 keeps them forever
+    #[serde(default)]
+    pub default_ttl_secs: u64,
+
+    // Time-to-live in seconds per edge label, overriding the default, 0 keeps them forever
+    #[serde(default)]
+    pub labels: BTreeMap<String, u64>,
+}
+
+impl EdgeTtlOptions {
+    /// Time-to-live in seconds of the edges inserted with `label`, 0 if they never expire.
+    pub fn ttl_secs(&self, label: Option<&str>) -> u64 {
+        label
+            .and_then(|label| self.labels.get(label))
+            .copied()
+            .unwrap_or(self.default_ttl_secs)
+    }
+
+    /// Check that every time-to-live fits a delta operation.
+    fn validate(&self) -> Result<(), ConfigError> {
+        let max_ttl_secs = crate::delta::DeltaOperation::MAX_TTL_SECS;
+        if self.default_ttl_secs > max_ttl_secs {
+            return Err(ConfigError::InvalidValue(format!(
+                "edge_ttl.default_ttl_secs {} exceeds {}",
+                self.default_ttl_secs, max_ttl_secs
+            )));
+        }
+        if let Some((label, ttl_secs)) = self.labels.iter().find(|(_, ttl)| **ttl > max_ttl_secs) {
+            return Err(ConfigError::InvalidValue(format!(
+                "edge_ttl.labels.{} {} exceeds {}",
+                label, ttl_secs, max_ttl_secs
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Change data capture of the graph mutations, see [`crate::change_log`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeLogOptions {
+    // Record every vertex, edge and property mutation in the change log
+    #[serde(default)]
+    pub enabled: bool,
+
+    // Seconds a change is retained, 0 retains the changes forever
+    #[serde(default)]
+    pub retention_secs: u64,
+
+    // Number of changes retained, 0 retains any number
+    #[serde(default)]
+    pub max_changes: u64,
 }
 
 // Default value functions for serde
@@ -54,11 +296,20 @@ fn default_giant_vertex_boundary() -> usize {
 fn default_work_space_dir() -> String {
     "workspace".to_owned()
 }
-fn default_block_cache_capacity() -> u64 {
-    1 << 20
+fn default_memory_budget() -> u64 {
+    1 << 30
+}
+fn default_max_write_buffer_number() -> i32 {
+    3
+}
+fn default_target_file_size_base() -> u64 {
+    64 * 1024 * 1024
 }
-fn default_giant_cache_capacity() -> u64 {
-    10_000
+fn default_level_zero_compaction_trigger() -> i32 {
+    4
+}
+fn default_level_zero_slowdown_trigger() -> i32 {
+    20
 }
 
 impl Default for LsmCommunityStorageOptions {
@@ -70,10 +321,365 @@ impl Default for LsmCommunityStorageOptions {
             giant_vertex_boundary: default_giant_vertex_boundary(),
             graph_name: String::new(),
             work_space_dir: default_work_space_dir(),
-            block_cache_capacity: default_block_cache_capacity(),
-            giant_cache_capacity: default_giant_cache_capacity(),
+            block_cache_capacity: 0,
+            giant_cache_capacity: 0,
+            memory_budget: default_memory_budget(),
+            memory_rebalance: false,
+            incremental_wcc: false,
+            reachability_index: false,
+            external_storage: ExternalStorageOptions::default(),
+            io_backend: IoBackend::default(),
+            edge_ttl: EdgeTtlOptions::default(),
+            edge_history: false,
+            edge_history_retention_secs: 0,
+            change_log: ChangeLogOptions::default(),
+        }
+    }
+}
+
+impl LsmCommunityStorageOptions {
+    /// Prefix of the environment variables overriding the options.
+    pub const ENV_PREFIX: &'static str = "LSM_COMMUNITY_";
+    /// Bytes of a block header, vertex count and edge count.
+    const BLOCK_HEADER_SIZE: usize = 4;
+    /// Bytes of a vertex entry in a block, vertex id and edge offset.
+    const BLOCK_VERTEX_SIZE: usize = 8;
+    /// Bytes of an edge in a block.
+    const BLOCK_EDGE_SIZE: usize = 4;
+    /// Blocks are multiples of the sector size, so that direct I/O reads stay aligned.
+    const SECTOR_SIZE: usize = 512;
+    /// Largest block whose edge count fits the u16 of the block header.
+    const MAX_BLOCK_SIZE: usize = 256 * 1024;
+    /// Largest bucket whose offsets fit the u32 of the bucket footer.
+    const MAX_BUCKET_SIZE: usize = u32::MAX as usize;
+    /// Number of blocks of a derived minimum bucket size.
+    const DERIVED_BLOCKS_PER_BUCKET: usize = 2048;
+
+    /// Smallest block size holding a vertex of degree `giant_vertex_boundary - 1`, the
+    /// largest vertex kept in the buckets.
+    pub fn min_block_size(giant_vertex_boundary: usize) -> usize {
+        Self::BLOCK_HEADER_SIZE
+            + Self::BLOCK_VERTEX_SIZE
+            + giant_vertex_boundary.saturating_sub(1) * Self::BLOCK_EDGE_SIZE
+    }
+
+    /// Replace the options set to 0 by values derived from the others.
+    ///
+    /// * `block_size` - The smallest power of two, at least the default, that holds a vertex
+    ///   of degree `giant_vertex_boundary - 1`
+    /// * `min_bucket_size` - A fixed number of blocks
+    pub fn derive_defaults(&mut self) {
+        if self.block_size == 0 {
+            self.block_size = Self::min_block_size(self.giant_vertex_boundary)
+                .next_power_of_two()
+                .max(default_block_size());
+        }
+        if self.min_bucket_size == 0 {
+            self.min_bucket_size = self.block_size * Self::DERIVED_BLOCKS_PER_BUCKET;
+        }
+    }
+
+    /// Check that the storage can be built with the options.
+    ///
+    /// # Returns
+    /// * `Result<(), ConfigError>` - Success, or the first invalid value
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |message: String| Err(ConfigError::InvalidValue(message));
+        if self.giant_vertex_boundary == 0 {
+            return invalid("giant_vertex_boundary must be at least 1".to_owned());
+        }
+        if self.block_size == 0
+            || self.block_size % Self::SECTOR_SIZE != 0
+            || self.block_size > Self::MAX_BLOCK_SIZE
+        {
+            return invalid(format!(
+                "block_size {} must be a multiple of {} up to {}",
+                self.block_size,
+                Self::SECTOR_SIZE,
+                Self::MAX_BLOCK_SIZE
+            ));
+        }
+        let min_block_size = Self::min_block_size(self.giant_vertex_boundary);
+        if self.block_size < min_block_size {
+            return invalid(format!(
+                "block_size {} cannot hold a vertex of degree {} below giant_vertex_boundary, \
+                 it needs at least {} bytes",
+                self.block_size,
+                self.giant_vertex_boundary - 1,
+                min_block_size
+            ));
+        }
+        if self.min_bucket_size < self.block_size || self.min_bucket_size > Self::MAX_BUCKET_SIZE {
+            return invalid(format!(
+                "min_bucket_size {} must be between block_size {} and {}",
+                self.min_bucket_size,
+                self.block_size,
+                Self::MAX_BUCKET_SIZE
+            ));
+        }
+        if self.num_mem_graph_limit == 0 {
+            return invalid("num_mem_graph_limit must be at least 1".to_owned());
+        }
+        if self.work_space_dir.is_empty() {
+            return invalid("work_space_dir must not be empty".to_owned());
+        }
+        if self.graph_name.contains(['/', '\\'])
+            || self.graph_name == "."
+            || self.graph_name == ".."
+        {
+            return invalid(format!(
+                "graph_name {} must be a single directory name",
+                self.graph_name
+            ));
+        }
+        if self.memory_budget == 0 {
+            return invalid("memory_budget must be positive".to_owned());
+        }
+        let pinned_caches = self
+            .block_cache_capacity
+            .saturating_add(self.giant_cache_capacity);
+        if pinned_caches.saturating_add(MIN_MEMTABLE_BYTES) > self.memory_budget {
+            return invalid(format!(
+                "block_cache_capacity and giant_cache_capacity {} leave less than {} bytes of memory_budget {} for the memtables",
+                pinned_caches, MIN_MEMTABLE_BYTES, self.memory_budget
+            ));
+        }
+        self.edge_ttl.validate()?;
+        self.external_storage.validate()
+    }
+
+    /// Override the options by the `LSM_COMMUNITY_*` environment variables.
+    ///
+    /// Each top-level option is overridden by its upper-case name, e.g. `block_size` by
+    /// `LSM_COMMUNITY_BLOCK_SIZE`, the write-ahead log policy by
+    /// `LSM_COMMUNITY_WAL_DISABLE` and `LSM_COMMUNITY_WAL_SYNC`, the default edge
+    /// time-to-live by `LSM_COMMUNITY_EDGE_TTL_SECS`, and the change log by
+    /// `LSM_COMMUNITY_CHANGE_LOG_ENABLED` and `LSM_COMMUNITY_CHANGE_LOG_RETENTION_SECS`.
+    ///
+    /// # Returns
+    /// * `Result<(), ConfigError>` - Success, or the first variable that does not parse
+    pub fn apply_env_overrides(&mut self) -> Result<(), ConfigError> {
+        self.apply_overrides(|name| std::env::var(name).ok())
+    }
+
+    /// Override the options by the variables found by `lookup`, see
+    /// [`LsmCommunityStorageOptions::apply_env_overrides`].
+    pub(crate) fn apply_overrides(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), ConfigError> {
+        fn apply<T: std::str::FromStr>(
+            lookup: &impl Fn(&str) -> Option<String>,
+            name: &str,
+            target: &mut T,
+        ) -> Result<(), ConfigError> {
+            let variable = format!("{}{}", LsmCommunityStorageOptions::ENV_PREFIX, name);
+            if let Some(value) = lookup(&variable) {
+                *target = value.trim().parse().map_err(|_| {
+                    ConfigError::InvalidValue(format!("{} cannot parse {:?}", variable, value))
+                })?;
+            }
+            Ok(())
+        }
+
+        apply(&lookup, "BLOCK_SIZE", &mut self.block_size)?;
+        apply(&lookup, "MIN_BUCKET_SIZE", &mut self.min_bucket_size)?;
+        apply(
+            &lookup,
+            "NUM_MEM_GRAPH_LIMIT",
+            &mut self.num_mem_graph_limit,
+        )?;
+        apply(
+            &lookup,
+            "GIANT_VERTEX_BOUNDARY",
+            &mut self.giant_vertex_boundary,
+        )?;
+        apply(&lookup, "GRAPH_NAME", &mut self.graph_name)?;
+        apply(&lookup, "WORK_SPACE_DIR", &mut self.work_space_dir)?;
+        apply(
+            &lookup,
+            "BLOCK_CACHE_CAPACITY",
+            &mut self.block_cache_capacity,
+        )?;
+        apply(
+            &lookup,
+            "GIANT_CACHE_CAPACITY",
+            &mut self.giant_cache_capacity,
+        )?;
+        apply(&lookup, "MEMORY_BUDGET", &mut self.memory_budget)?;
+        apply(&lookup, "MEMORY_REBALANCE", &mut self.memory_rebalance)?;
+        apply(&lookup, "INCREMENTAL_WCC", &mut self.incremental_wcc)?;
+        apply(&lookup, "REACHABILITY_INDEX", &mut self.reachability_index)?;
+        apply(&lookup, "IO_BACKEND", &mut self.io_backend)?;
+        apply(&lookup, "EDGE_HISTORY", &mut self.edge_history)?;
+        apply(
+            &lookup,
+            "EDGE_HISTORY_RETENTION_SECS",
+            &mut self.edge_history_retention_secs,
+        )?;
+        let wal = &mut self.external_storage.wal;
+        apply(&lookup, "WAL_DISABLE", &mut wal.disable_wal)?;
+        apply(&lookup, "WAL_SYNC", &mut wal.sync)?;
+        apply(
+            &lookup,
+            "EDGE_TTL_SECS",
+            &mut self.edge_ttl.default_ttl_secs,
+        )?;
+        let change_log = &mut self.change_log;
+        apply(&lookup, "CHANGE_LOG_ENABLED", &mut change_log.enabled)?;
+        apply(
+            &lookup,
+            "CHANGE_LOG_RETENTION_SECS",
+            &mut change_log.retention_secs,
+        )?;
+        Ok(())
+    }
+}
+
+impl Default for ExternalStorageOptions {
+    fn default() -> Self {
+        ExternalStorageOptions {
+            giant_vertices: ColumnFamilyOptions::giant_vertices(),
+            deltas: ColumnFamilyOptions::deltas(),
+            vertex_properties: ColumnFamilyOptions::vertex_properties(),
+            edge_properties: ColumnFamilyOptions::edge_properties(),
+            edge_history: ColumnFamilyOptions::edge_history(),
+            change_log: ColumnFamilyOptions::change_log(),
+            wal: WalOptions::default(),
+        }
+    }
+}
+
+impl ExternalStorageOptions {
+    /// Check that RocksDB accepts the options, called when the external storage opens.
+    ///
+    /// # Returns
+    /// * `Result<(), ConfigError>` - Success, or the first invalid value
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.giant_vertices.validate("giant_vertices")?;
+        self.deltas.validate("deltas")?;
+        self.vertex_properties.validate("vertex_properties")?;
+        self.edge_properties.validate("edge_properties")?;
+        self.edge_history.validate("edge_history")?;
+        self.change_log.validate("change_log")?;
+        if self.wal.disable_wal && self.wal.sync {
+            return Err(ConfigError::InvalidValue(
+                "external_storage.wal.sync requires the write-ahead log".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ColumnFamilyOptions {
+    fn default() -> Self {
+        ColumnFamilyOptions {
+            write_buffer_size: 0,
+            max_write_buffer_number: default_max_write_buffer_number(),
+            target_file_size_base: default_target_file_size_base(),
+            level_zero_compaction_trigger: default_level_zero_compaction_trigger(),
+            level_zero_slowdown_trigger: default_level_zero_slowdown_trigger(),
+            block_cache_size: 0,
+            compression: CompressionType::default(),
+            bloom_filter_bits: 0.0,
+        }
+    }
+}
+
+impl ColumnFamilyOptions {
+    /// Smallest memtable size RocksDB is given.
+    const MIN_WRITE_BUFFER_SIZE: u64 = 64 * 1024;
+    /// Largest bloom filter RocksDB builds, in bits per key.
+    const MAX_BLOOM_FILTER_BITS: f64 = 100.0;
+
+    /// Defaults of the giant vertices, read-heavy with large values.
+    fn giant_vertices() -> Self {
+        ColumnFamilyOptions {
+            target_file_size_base: 128 * 1024 * 1024,
+            ..Default::default()
+        }
+    }
+
+    /// Defaults of the deltas, write-heavy through the merge operator.
+    fn deltas() -> Self {
+        ColumnFamilyOptions {
+            max_write_buffer_number: 5,
+            level_zero_compaction_trigger: 8,
+            ..Default::default()
+        }
+    }
+
+    /// Defaults of the vertex properties, point lookups.
+    fn vertex_properties() -> Self {
+        ColumnFamilyOptions {
+            bloom_filter_bits: 10.0,
+            ..Default::default()
+        }
+    }
+
+    /// Defaults of the edge properties, point lookups over many small values.
+    fn edge_properties() -> Self {
+        ColumnFamilyOptions {
+            max_write_buffer_number: 4,
+            compression: CompressionType::Lz4,
+            bloom_filter_bits: 10.0,
+            ..Default::default()
+        }
+    }
+
+    /// Defaults of the edge history, append-only through the merge operator.
+    fn edge_history() -> Self {
+        ColumnFamilyOptions {
+            level_zero_compaction_trigger: 8,
+            compression: CompressionType::Lz4,
+            ..Default::default()
         }
     }
+
+    /// Defaults of the change log, appended in sequence order and read in ranges.
+    fn change_log() -> Self {
+        ColumnFamilyOptions {
+            compression: CompressionType::Lz4,
+            ..Default::default()
+        }
+    }
+
+    fn validate(&self, name: &str) -> Result<(), ConfigError> {
+        let invalid = |field: &str, reason: &str| {
+            Err(ConfigError::InvalidValue(format!(
+                "external_storage.{}.{} {}",
+                name, field, reason
+            )))
+        };
+        if self.write_buffer_size != 0 && self.write_buffer_size < Self::MIN_WRITE_BUFFER_SIZE {
+            return invalid(
+                "write_buffer_size",
+                &format!("must be 0 or at least {}", Self::MIN_WRITE_BUFFER_SIZE),
+            );
+        }
+        if self.max_write_buffer_number < 1 {
+            return invalid("max_write_buffer_number", "must be at least 1");
+        }
+        if self.target_file_size_base == 0 {
+            return invalid("target_file_size_base", "must be positive");
+        }
+        if self.level_zero_compaction_trigger < 1 {
+            return invalid("level_zero_compaction_trigger", "must be at least 1");
+        }
+        if self.level_zero_slowdown_trigger < self.level_zero_compaction_trigger {
+            return invalid(
+                "level_zero_slowdown_trigger",
+                "must not be below level_zero_compaction_trigger",
+            );
+        }
+        if !(0.0..=Self::MAX_BLOOM_FILTER_BITS).contains(&self.bloom_filter_bits) {
+            return invalid(
+                "bloom_filter_bits",
+                &format!("must be between 0 and {}", Self::MAX_BLOOM_FILTER_BITS),
+            );
+        }
+        Ok(())
+    }
 }
 
 /// Configuration manager for loading and saving YAML config files
@@ -113,6 +719,27 @@ impl ConfigManager {
         Ok(config)
     }
 
+    /// Load storage options from a YAML file, overridden by the environment
+    ///
+    /// The `LSM_COMMUNITY_*` variables override the values of the file, options set to 0
+    /// are derived from the others, then the result is validated.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the YAML configuration file
+    ///
+    /// # Returns
+    /// * `Result<LsmCommunityStorageOptions, ConfigError>` - Valid options or error
+    pub fn load_storage_options<P>(path: P) -> Result<LsmCommunityStorageOptions, ConfigError>
+    where
+        P: AsRef<Path>,
+    {
+        let mut options: LsmCommunityStorageOptions = Self::load_from_yaml(path)?;
+        options.apply_env_overrides()?;
+        options.derive_defaults();
+        options.validate()?;
+        Ok(options)
+    }
+
     /// Load configuration from YAML file, or return default if file doesn't exist
     ///
     /// # Arguments
@@ -193,6 +820,8 @@ pub enum ConfigError {
     ParseError(String),
     /// Serialize error
     SerializeError(String),
+    /// Invalid configuration value
+    InvalidValue(String),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -205,6 +834,7 @@ impl std::fmt::Display for ConfigError {
             ConfigError::IoError(e) => write!(f, "I/O error: {}", e),
             ConfigError::ParseError(e) => write!(f, "Parse error: {}", e),
             ConfigError::SerializeError(e) => write!(f, "Serialize error: {}", e),
+            ConfigError::InvalidValue(e) => write!(f, "Invalid value: {}", e),
         }
     }
 }
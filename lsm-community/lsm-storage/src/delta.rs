@@ -2,23 +2,35 @@ use std::collections::hash_map::Entry;
 
 use rustc_hash::FxHashMap;
 
-use crate::types::VId;
+use crate::{types::VId, utils::generate_timestamp_micros};
 
 /// A single delta operation representing a change to a vertex's neighbor list.
 ///
 /// Each operation is encoded as 16 bytes (128 bits) for memory alignment:
 /// - timestamp: u64 (8 bytes) - Logical or physical timestamp for ordering
 /// - neighbor: u32 (4 bytes) - The neighbor vertex ID being added or removed
-/// - op_type: u32 (4 bytes) - Operation type (0=Add, 1=Remove, rest reserved)
+/// - op_type: u32 (4 bytes) - Operation type (0=Add, 1=Remove, rest reserved) in the low
+///   7 bits, bit 7 set if the base graph does not hold the edge, time-to-live in seconds in
+///   the high 24 bits (0 = never expires)
 ///
 /// The 128-bit alignment ensures efficient memory access and cache performance.
+///
+/// An operation with a time-to-live expires `ttl` seconds after its timestamp, taken as
+/// microseconds since the Unix epoch. An expired add turns into a remove of its neighbor,
+/// when the log is read and when RocksDB merges it, so that the edge is gone even if it is
+/// in the base graph. The remove keeps the timestamp and time-to-live of the add.
+///
+/// A remove of an edge outside the base graph hides nothing, it is dropped on the next merge
+/// once it overrode the older operations of its neighbor, as is an expired add of such an
+/// edge. Operations written without the flag are taken as edges of the base graph.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct DeltaOperation {
     /// Timestamp for ordering operations (monotonically increasing)
     pub timestamp: u64,
     /// The neighbor vertex ID involved in this operation
     pub neighbor: VId,
-    /// Operation type: 0=AddNeighbor, 1=RemoveNeighbor, others reserved for future use
+    /// Operation type: 0=AddNeighbor, 1=RemoveNeighbor, others reserved for future use,
+    /// with the outside base flag in bit 7 and the time-to-live in seconds in the high 24 bits
     pub op_type: u32,
 }
 
@@ -60,6 +72,18 @@ impl DeltaOperation {
     /// Size of encoded delta operation in bytes (128 bits = 16 bytes)
     pub const ENCODED_SIZE: usize = 16;
 
+    /// Bits of `op_type` holding the operation type
+    const OP_TYPE_MASK: u32 = 0x7F;
+
+    /// Bit of `op_type` set if the base graph does not hold the edge
+    const OUTSIDE_BASE_FLAG: u32 = 0x80;
+
+    /// Shift of the time-to-live in `op_type`
+    const TTL_SHIFT: u32 = 8;
+
+    /// Longest time-to-live in seconds, about 194 days
+    pub const MAX_TTL_SECS: u64 = (u32::MAX >> Self::TTL_SHIFT) as u64;
+
     /// Create a new delta operation
     #[inline]
     pub fn new(timestamp: u64, op_type: DeltaOpType, neighbor: VId) -> Self {
@@ -70,10 +94,82 @@ impl DeltaOperation {
         }
     }
 
+    /// Create a new delta operation expiring `ttl_secs` seconds after `timestamp`
+    ///
+    /// A time-to-live of 0 never expires, longer ones than [`Self::MAX_TTL_SECS`] are cut
+    /// to it.
+    #[inline]
+    pub fn with_ttl(timestamp: u64, op_type: DeltaOpType, neighbor: VId, ttl_secs: u64) -> Self {
+        let ttl_secs = ttl_secs.min(Self::MAX_TTL_SECS) as u32;
+        Self {
+            timestamp,
+            neighbor,
+            op_type: op_type.as_u32() | (ttl_secs << Self::TTL_SHIFT),
+        }
+    }
+
+    /// Mark whether the base graph does not hold the edge of the operation
+    #[inline]
+    pub fn with_outside_base(self, outside_base: bool) -> Self {
+        let flag = if outside_base {
+            Self::OUTSIDE_BASE_FLAG
+        } else {
+            0
+        };
+        Self {
+            op_type: (self.op_type & !Self::OUTSIDE_BASE_FLAG) | flag,
+            ..self
+        }
+    }
+
+    /// Check if the operation is marked as an edge the base graph does not hold
+    #[inline]
+    pub fn is_outside_base(&self) -> bool {
+        self.op_type & Self::OUTSIDE_BASE_FLAG != 0
+    }
+
+    /// Check if the operation removes an edge outside the base graph, so hides nothing
+    #[inline]
+    fn hides_nothing(&self) -> bool {
+        self.is_outside_base() && self.get_op_type() == Some(DeltaOpType::RemoveNeighbor)
+    }
+
     /// Get the operation type
     #[inline]
     pub fn get_op_type(&self) -> Option<DeltaOpType> {
-        DeltaOpType::from_u32(self.op_type)
+        DeltaOpType::from_u32(self.op_type & Self::OP_TYPE_MASK)
+    }
+
+    /// Get the time-to-live in seconds, 0 if the operation never expires
+    #[inline]
+    pub fn ttl_secs(&self) -> u64 {
+        (self.op_type >> Self::TTL_SHIFT) as u64
+    }
+
+    /// Get the expiry time in microseconds, None if the operation never expires
+    #[inline]
+    pub fn expires_at(&self) -> Option<u64> {
+        match self.ttl_secs() {
+            0 => None,
+            ttl_secs => Some(self.timestamp.saturating_add(ttl_secs * 1_000_000)),
+        }
+    }
+
+    /// Check if the operation expired at `now`, in microseconds since the Unix epoch
+    #[inline]
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at()
+            .is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// The remove an add turns into once it expired, keeping its timestamp, time-to-live and
+    /// outside base flag
+    #[inline]
+    pub fn to_tombstone(&self) -> Self {
+        Self {
+            op_type: (self.op_type & !Self::OP_TYPE_MASK) | DeltaOpType::RemoveNeighbor.as_u32(),
+            ..*self
+        }
     }
 
     /// Encode delta operation to 16 bytes
@@ -133,8 +229,8 @@ impl DeltaOperation {
                 .expect("slice with incorrect length"),
         );
 
-        // Validate op_type
-        if DeltaOpType::from_u32(op_type).is_none() {
+        // Validate op_type, the time-to-live bits take any value
+        if DeltaOpType::from_u32(op_type & Self::OP_TYPE_MASK).is_none() {
             anyhow::bail!("Invalid operation type: {}", op_type);
         }
 
@@ -244,6 +340,27 @@ impl DeltaLog {
         self.ops.insert(pos, op);
     }
 
+    /// Turn the adds expired at `now`, in microseconds since the Unix epoch, into removes,
+    /// and drop the removes of edges outside the base graph.
+    ///
+    /// The log must hold the latest operation of each neighbor only, as a merged log does,
+    /// or a dropped remove would let an older add of its neighbor through.
+    ///
+    /// # Returns
+    ///
+    /// The number of expired adds.
+    pub fn expire(&mut self, now: u64) -> usize {
+        let mut expired = 0;
+        for op in &mut self.ops {
+            if op.get_op_type() == Some(DeltaOpType::AddNeighbor) && op.is_expired(now) {
+                *op = op.to_tombstone();
+                expired += 1;
+            }
+        }
+        self.ops.retain(|op| !op.hides_nothing());
+        expired
+    }
+
     /// Encode the delta log to bytes
     ///
     /// Format: [count: 4 bytes][op1: 16 bytes][op2: 16 bytes]...
@@ -367,7 +484,8 @@ impl DeltaLog {
     ///
     /// # Returns
     ///
-    /// Encoded bytes of the merged DeltaLog, or None if merge fails.
+    /// Encoded bytes of the merged DeltaLog with the expired adds turned into removes and the
+    /// removes of edges outside the base graph dropped, or None if merge fails.
     pub fn merge_for_rocksdb(base: Option<&[u8]>, operands: &[&[u8]]) -> Option<Vec<u8>> {
         // Decode base log if it exists
         let mut logs = Vec::with_capacity(1 + operands.len());
@@ -392,8 +510,10 @@ impl DeltaLog {
             }
         }
 
-        // Merge all logs
-        let merged = Self::merge(&logs);
+        // Merge all logs, an expired add overrides the older operations of its neighbor before
+        // it is dropped if it hides nothing
+        let mut merged = Self::merge(&logs);
+        merged.expire(generate_timestamp_micros());
         Some(merged.encode())
     }
 
@@ -427,4 +547,199 @@ impl DeltaLog {
 
         Some(result)
     }
+
+    /// Full merge operation for the edge history in RocksDB.
+    ///
+    /// Unlike [`DeltaLog::merge_for_rocksdb`], every operation is kept: the base value
+    /// (raw operations) and the operands are concatenated. Only operations older than the
+    /// retention are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - Optional base value (raw encoded operations)
+    /// * `operands` - Slice of operand byte slices (each containing encoded operations)
+    /// * `retention_secs` - Seconds the operations are retained, 0 retains them forever
+    ///
+    /// # Returns
+    ///
+    /// Concatenated raw operation bytes, or None if merge fails.
+    pub fn append_for_rocksdb(
+        base: Option<&[u8]>,
+        operands: &[&[u8]],
+        retention_secs: u64,
+    ) -> Option<Vec<u8>> {
+        let mut slices = Vec::with_capacity(1 + operands.len());
+        slices.extend(base);
+        slices.extend_from_slice(operands);
+        let appended = Self::partial_merge_for_rocksdb(&slices)?;
+        let Some(oldest) = Self::oldest_retained(retention_secs) else {
+            return Some(appended);
+        };
+        let mut ops = DeltaOperation::decode_batch(&appended).ok()?;
+        ops.retain(|op| op.timestamp >= oldest);
+        Some(DeltaOperation::encode_batch(&ops))
+    }
+
+    /// Compaction filter check of the edge history for RocksDB.
+    ///
+    /// A history whose operations are all older than the retention can be dropped, the
+    /// latest operation of each edge stays in the delta log.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Raw encoded operations
+    /// * `retention_secs` - Seconds the operations are retained, 0 retains them forever
+    ///
+    /// # Returns
+    ///
+    /// Whether the history can be removed, false if it does not decode.
+    pub fn is_history_expired_for_rocksdb(value: &[u8], retention_secs: u64) -> bool {
+        Self::oldest_retained(retention_secs).is_some_and(|oldest| {
+            DeltaOperation::decode_batch(value)
+                .is_ok_and(|ops| ops.iter().all(|op| op.timestamp < oldest))
+        })
+    }
+
+    /// Timestamp of the oldest operation retained for `retention_secs`, None if they are
+    /// retained forever.
+    fn oldest_retained(retention_secs: u64) -> Option<u64> {
+        (retention_secs > 0).then(|| {
+            generate_timestamp_micros().saturating_sub(retention_secs.saturating_mul(1_000_000))
+        })
+    }
+
+    /// Compaction filter check for RocksDB.
+    ///
+    /// A stored delta log can be dropped by compaction once it is empty after
+    /// [`DeltaLog::expire`], that is every operation is a remove or an expired add of an edge
+    /// outside the base graph. An expired add of an edge of the base graph must stay as a
+    /// remove to hide it, it is turned into one when the log is read and on the next merge.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Encoded DeltaLog
+    ///
+    /// # Returns
+    ///
+    /// Whether the log can be removed, false if it does not decode.
+    pub fn is_expired_for_rocksdb(value: &[u8]) -> bool {
+        Self::decode(value).is_ok_and(|mut log| {
+            log.expire(generate_timestamp_micros());
+            log.is_empty()
+        })
+    }
+}
+
+/// Validity interval of an edge, from `valid_from` included to `valid_to` excluded, in
+/// microseconds since the Unix epoch.
+///
+/// Edges of the base graph are valid from 0, edges still valid end at
+/// [`EdgeInterval::OPEN_END`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgeInterval {
+    /// The neighbor vertex ID at the end of the edge
+    pub neighbor: VId,
+    /// First time the edge is valid
+    pub valid_from: u64,
+    /// First time the edge is no longer valid
+    pub valid_to: u64,
+}
+
+impl EdgeInterval {
+    /// End of an interval still open
+    pub const OPEN_END: u64 = u64::MAX;
+
+    /// Check if the edge is valid at `time`
+    #[inline]
+    pub fn contains(&self, time: u64) -> bool {
+        self.valid_from <= time && time < self.valid_to
+    }
+
+    /// Check if the edge is valid at some time of the window `[from, to)`
+    #[inline]
+    pub fn overlaps(&self, from: u64, to: u64) -> bool {
+        self.valid_from < to && from < self.valid_to
+    }
+
+    /// Derive the validity intervals of the out edges of a vertex.
+    ///
+    /// At any time, the state of an edge is that of its latest operation, as in a merged
+    /// [`DeltaLog`]: an add makes it valid until it expires, a remove or an expired add
+    /// invalid, and without operation the edge is valid if it is in the base graph. A remove
+    /// with a time-to-live is an add that expired in the delta log, valid until its expiry.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - Base neighbors in ascending order
+    /// * `ops` - Operations of the vertex, in any order
+    ///
+    /// # Returns
+    ///
+    /// The intervals ordered by neighbor then time, an edge gets one interval per period
+    /// of validity.
+    pub fn from_history(
+        base: impl IntoIterator<Item = VId>,
+        mut ops: Vec<DeltaOperation>,
+    ) -> Vec<EdgeInterval> {
+        ops.sort_by_key(|op| (op.neighbor, op.timestamp));
+        let mut base = base.into_iter().peekable();
+        let mut intervals = Vec::new();
+        let mut pos = 0;
+        loop {
+            // Next neighbor, from the base or the operations
+            let op_neighbor = ops.get(pos).map(|op| op.neighbor);
+            let neighbor = match (base.peek().copied(), op_neighbor) {
+                (Some(base_vid), Some(op_vid)) => base_vid.min(op_vid),
+                (Some(vid), None) | (None, Some(vid)) => vid,
+                (None, None) => break,
+            };
+            let mut in_base = false;
+            while base.peek() == Some(&neighbor) {
+                base.next();
+                in_base = true;
+            }
+            let end = pos + ops[pos..].partition_point(|op| op.neighbor == neighbor);
+            let neighbor_ops = &ops[pos..end];
+            pos = end;
+
+            let mut open = in_base.then_some(0);
+            let mut set = |time: u64, valid: bool| match (open, valid) {
+                (None, true) => open = Some(time),
+                (Some(valid_from), false) => {
+                    if valid_from < time {
+                        intervals.push(EdgeInterval {
+                            neighbor,
+                            valid_from,
+                            valid_to: time,
+                        });
+                    }
+                    open = None;
+                }
+                _ => {}
+            };
+            for (i, op) in neighbor_ops.iter().enumerate() {
+                match op.get_op_type() {
+                    Some(DeltaOpType::AddNeighbor) => set(op.timestamp, true),
+                    Some(DeltaOpType::RemoveNeighbor) => set(op.timestamp, op.ttl_secs() > 0),
+                    // Reserved for future use, ignore unknown operations
+                    None => continue,
+                }
+                // An add expiring before the next operation removes the edge
+                let next = neighbor_ops.get(i + 1).map(|next| next.timestamp);
+                if let Some(expires_at) = op.expires_at() {
+                    if next.is_none_or(|next| expires_at < next) {
+                        set(expires_at, false);
+                    }
+                }
+            }
+            if let Some(valid_from) = open {
+                intervals.push(EdgeInterval {
+                    neighbor,
+                    valid_from,
+                    valid_to: Self::OPEN_END,
+                });
+            }
+        }
+        intervals
+    }
 }
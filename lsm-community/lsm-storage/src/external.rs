@@ -1,11 +1,19 @@
-use rocksdb::{BlockBasedOptions, Cache, ColumnFamilyDescriptor, DB, MergeOperands, Options};
+use rocksdb::{
+    AsColumnFamilyRef, BlockBasedOptions, Cache, ColumnFamilyDescriptor, DB, DBCompressionType,
+    MergeOperands, Options, WriteBatch, WriteBufferManager, WriteOptions,
+    compaction_filter::Decision,
+};
+use rustc_hash::FxHashSet;
 use std::{path::Path, sync::Arc};
 
 use crate::{
-    config::LsmCommunityStorageOptions,
-    delta::{DeltaLog, DeltaOperation},
+    change_log::{ChangeEvent, ChangeKind, ChangeLog, ChangeSubscription},
+    config::{ColumnFamilyOptions, CompressionType, LsmCommunityStorageOptions},
+    delta::{DeltaLog, DeltaOpType, DeltaOperation},
+    memory::MemoryBudget,
     property::{EdgePropertyKey, VertexPropertyKey},
     types::{VId, VIdList},
+    utils::generate_timestamp_micros,
 };
 
 pub type GiantVertexCache = moka::sync::Cache<VId, Arc<VIdList>>;
@@ -36,6 +44,14 @@ pub enum DeltaOp {
 ///    - Key (Vertex): vertex_id | property_name (u64 + string)
 ///    - Key (Edge): src_id | dst_id | property_name (u64 + u64 + string)
 ///    - Value: property value (serialized binary)
+///
+/// 4. **Edge History**: Every delta operation, kept with the `edge_history` option
+///    - Key: vertex_id (u64, 8 bytes)
+///    - Value: delta operations in append order
+///
+/// 5. **Change Log**: Every mutation, kept with the `change_log` option
+///    - Key: sequence number (u64, 8 bytes)
+///    - Value: change event (serialized binary)
 #[allow(dead_code)]
 pub struct ExternalStorage {
     /// RocksDB instance with three column families
@@ -43,6 +59,18 @@ pub struct ExternalStorage {
 
     /// The giant vertex cache.
     giant_cache: GiantVertexCache,
+
+    /// WAL and sync policy of every write
+    write_options: WriteOptions,
+
+    /// Whether every delta operation is also appended to the edge history
+    edge_history: bool,
+
+    /// Change log of the mutations, with the `change_log` option
+    change_log: Option<ChangeLog>,
+
+    /// Private directory of a secondary instance, removed after the DB is closed
+    secondary_dir: Option<tempfile::TempDir>,
 }
 
 impl ExternalStorage {
@@ -54,8 +82,14 @@ impl ExternalStorage {
     const CF_VERTEX_PROPERTIES: &'static str = "vertex_properties";
     /// Column family name for edge properties
     const CF_EDGE_PROPERTIES: &'static str = "edge_properties";
-
-    /// Creates a new ExternalStorage instance with custom giant vertex cache capacity.
+    /// Column family name for the edge history
+    const CF_EDGE_HISTORY: &'static str = "edge_history";
+    /// Smallest write buffer derived from the memtable budget
+    const MIN_DERIVED_WRITE_BUFFER_SIZE: usize = 1024 * 1024;
+    /// Largest write buffer derived from the memtable budget, per unit of weight
+    const MAX_DERIVED_WRITE_BUFFER_SIZE: usize = 64 * 1024 * 1024;
+
+    /// Creates a new ExternalStorage instance sized by the memory budget of the options.
     ///
     /// # Arguments
     ///
@@ -65,92 +99,302 @@ impl ExternalStorage {
     ///
     /// Returns a new `ExternalStorage` instance or an error if initialization fails.
     pub fn new(options: LsmCommunityStorageOptions) -> anyhow::Result<Self> {
+        let memory_budget = MemoryBudget::from_options(&options);
+        Self::with_memory_budget(options, &memory_budget)
+    }
+
+    /// Creates a new ExternalStorage instance with an already partitioned memory budget.
+    ///
+    /// The giant vertex cache is weighed by bytes, all column families share one RocksDB
+    /// LRU cache and the memtables are bounded by a write buffer manager.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Configuration options for the storage system
+    /// * `memory_budget` - Byte budget of the giant cache, RocksDB cache and memtables
+    ///
+    /// # Returns
+    ///
+    /// Returns a new `ExternalStorage` instance or an error if initialization fails.
+    pub fn with_memory_budget(
+        options: LsmCommunityStorageOptions,
+        memory_budget: &MemoryBudget,
+    ) -> anyhow::Result<Self> {
+        Self::open(options, memory_budget, false)
+    }
+
+    /// Opens the external storage of an existing graph as a RocksDB secondary instance.
+    ///
+    /// A secondary instance only reads, and may run in another process than the primary
+    /// that writes the graph. It sees the writes of the primary up to the open, and later
+    /// ones after [`ExternalStorage::try_catch_up_with_primary`].
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Configuration options for the storage system
+    /// * `memory_budget` - Byte budget of the giant cache, RocksDB cache and memtables
+    ///
+    /// # Returns
+    ///
+    /// Returns a new read-only `ExternalStorage` instance, or an error if the graph has no
+    /// external storage.
+    pub fn open_secondary(
+        options: LsmCommunityStorageOptions,
+        memory_budget: &MemoryBudget,
+    ) -> anyhow::Result<Self> {
+        Self::open(options, memory_budget, true)
+    }
+
+    fn open(
+        options: LsmCommunityStorageOptions,
+        memory_budget: &MemoryBudget,
+        secondary: bool,
+    ) -> anyhow::Result<Self> {
+        options.external_storage.validate()?;
+
         let db_path = Path::new(&options.work_space_dir)
             .join(&options.graph_name)
             .join("external_db");
 
-        // Create directory if it doesn't exist
-        std::fs::create_dir_all(&db_path)?;
+        if secondary {
+            // A secondary instance never creates the DB of the primary
+            if !db_path.exists() {
+                return Err(anyhow::anyhow!(
+                    "External DB {} not exists",
+                    db_path.display()
+                ));
+            }
+        } else {
+            // Create directory if it doesn't exist
+            std::fs::create_dir_all(&db_path)?;
+        }
+
+        // Shared block cache and memtable limit of all column families
+        let external_options = &options.external_storage;
+        let shared_cache = Cache::new_lru_cache(memory_budget.rocksdb_cache as usize);
+        let write_buffer_manager =
+            WriteBufferManager::new_write_buffer_manager(memory_budget.memtable as usize, false);
+        let column_families = [
+            (Self::CF_GIANT_VERTICES, &external_options.giant_vertices, 1),
+            (Self::CF_DELTAS, &external_options.deltas, 2),
+            (
+                Self::CF_VERTEX_PROPERTIES,
+                &external_options.vertex_properties,
+                1,
+            ),
+            (
+                Self::CF_EDGE_PROPERTIES,
+                &external_options.edge_properties,
+                2,
+            ),
+            (Self::CF_EDGE_HISTORY, &external_options.edge_history, 1),
+            (ChangeLog::CF_NAME, &external_options.change_log, 1),
+        ];
+        // Sum of write buffer weight times write buffer number over the derived memtables
+        let memtable_weight_units: u64 = column_families
+            .iter()
+            .filter(|(_, cf_options, _)| cf_options.write_buffer_size == 0)
+            .map(|(_, cf_options, weight)| weight * cf_options.max_write_buffer_number as u64)
+            .sum();
+        let write_buffer_size = |cf_options: &ColumnFamilyOptions, weight: u64| {
+            if cf_options.write_buffer_size > 0 {
+                return cf_options.write_buffer_size as usize;
+            }
+            // Weighted share of the memtable budget per write buffer
+            let unit = memory_budget.memtable / memtable_weight_units.max(1);
+            ((unit * weight) as usize).clamp(
+                Self::MIN_DERIVED_WRITE_BUFFER_SIZE,
+                Self::MAX_DERIVED_WRITE_BUFFER_SIZE * weight as usize,
+            )
+        };
 
         // Configure options for the main database
         let mut db_opts = Options::default();
         db_opts.create_if_missing(true);
         db_opts.create_missing_column_families(true);
-
-        // Configure column family for giant vertices (optimized for read-heavy workload)
-        let mut giant_cf_opts = Options::default();
-        giant_cf_opts.set_write_buffer_size(64 * 1024 * 1024); // 64MB write buffer
-        giant_cf_opts.set_max_write_buffer_number(3);
-        giant_cf_opts.set_target_file_size_base(128 * 1024 * 1024); // 128MB SST files
-
-        // Set up block-based table with large block cache for read performance
-        let mut giant_block_opts = BlockBasedOptions::default();
-        giant_block_opts.set_block_cache(&Cache::new_lru_cache(256 * 1024 * 1024)); // 256MB
-        giant_cf_opts.set_block_based_table_factory(&giant_block_opts);
-
-        // Configure column family for deltas (optimized for write-heavy workload with merge operator)
-        let mut delta_cf_opts = Options::default();
-        delta_cf_opts.set_write_buffer_size(128 * 1024 * 1024); // 128MB write buffer
-        delta_cf_opts.set_max_write_buffer_number(5); // More write buffers for high write throughput
-        delta_cf_opts.set_level_zero_file_num_compaction_trigger(8);
-        delta_cf_opts.set_level_zero_slowdown_writes_trigger(20);
-
-        // Set merge operator for efficient delta accumulation
-        delta_cf_opts.set_merge_operator(
-            "DeltaLogMergeOperator",
-            // Full merge: merge base value (existing DeltaLog) with operands (raw operations)
-            |_key: &[u8], existing_value: Option<&[u8]>, operands: &MergeOperands| {
-                let operand_slices: Vec<&[u8]> = operands.iter().collect();
-                DeltaLog::merge_for_rocksdb(existing_value, &operand_slices)
-            },
-            // Partial merge: merge multiple operands without base value
-            |_key: &[u8], _left_operand: Option<&[u8]>, operands: &MergeOperands| {
-                let operand_slices: Vec<&[u8]> = operands.iter().collect();
-                DeltaLog::partial_merge_for_rocksdb(&operand_slices)
-            },
-        );
-
-        // Configure column family for vertex properties (balanced configuration)
-        let mut vertex_prop_cf_opts = Options::default();
-        vertex_prop_cf_opts.set_write_buffer_size(64 * 1024 * 1024); // 64MB write buffer
-        vertex_prop_cf_opts.set_max_write_buffer_number(3);
-        vertex_prop_cf_opts.set_target_file_size_base(64 * 1024 * 1024); // 64MB SST files
-        // Enable bloom filter for faster point lookups
-        let mut vertex_prop_block_opts = BlockBasedOptions::default();
-        vertex_prop_block_opts.set_bloom_filter(10.0, false); // 10 bits per key
-        vertex_prop_block_opts.set_block_cache(&Cache::new_lru_cache(128 * 1024 * 1024)); // 128MB
-        vertex_prop_cf_opts.set_block_based_table_factory(&vertex_prop_block_opts);
-
-        // Configure column family for edge properties (optimized for potential high volume)
-        let mut edge_prop_cf_opts = Options::default();
-        edge_prop_cf_opts.set_write_buffer_size(128 * 1024 * 1024); // 128MB write buffer (edges are more numerous)
-        edge_prop_cf_opts.set_max_write_buffer_number(4);
-        edge_prop_cf_opts.set_target_file_size_base(64 * 1024 * 1024); // 64MB SST files
-        edge_prop_cf_opts.set_compression_type(rocksdb::DBCompressionType::Lz4); // Fast compression for edge properties
-        // Enable bloom filter for faster point lookups
-        let mut edge_prop_block_opts = BlockBasedOptions::default();
-        edge_prop_block_opts.set_bloom_filter(10.0, false); // 10 bits per key
-        edge_prop_block_opts.set_block_cache(&Cache::new_lru_cache(128 * 1024 * 1024)); // 128MB
-        edge_prop_cf_opts.set_block_based_table_factory(&edge_prop_block_opts);
+        db_opts.set_write_buffer_manager(&write_buffer_manager);
+        if external_options.wal.max_total_wal_size > 0 {
+            db_opts.set_max_total_wal_size(external_options.wal.max_total_wal_size);
+        }
 
         // Create column family descriptors
-        let cf_descriptors = vec![
-            ColumnFamilyDescriptor::new(Self::CF_GIANT_VERTICES, giant_cf_opts),
-            ColumnFamilyDescriptor::new(Self::CF_DELTAS, delta_cf_opts),
-            ColumnFamilyDescriptor::new(Self::CF_VERTEX_PROPERTIES, vertex_prop_cf_opts),
-            ColumnFamilyDescriptor::new(Self::CF_EDGE_PROPERTIES, edge_prop_cf_opts),
-        ];
+        let cf_descriptors = column_families
+            .iter()
+            .map(|(name, cf_options, weight)| {
+                let mut cf_opts = Self::column_family_options(cf_options, &shared_cache);
+                cf_opts.set_write_buffer_size(write_buffer_size(cf_options, *weight));
+                if *name == Self::CF_DELTAS {
+                    // Set merge operator for efficient delta accumulation
+                    cf_opts.set_merge_operator(
+                        "DeltaLogMergeOperator",
+                        // Full merge: merge base value (existing DeltaLog) with operands (raw operations)
+                        |_key: &[u8], existing_value: Option<&[u8]>, operands: &MergeOperands| {
+                            let operand_slices: Vec<&[u8]> = operands.iter().collect();
+                            DeltaLog::merge_for_rocksdb(existing_value, &operand_slices)
+                        },
+                        // Partial merge: merge multiple operands without base value
+                        |_key: &[u8], _left_operand: Option<&[u8]>, operands: &MergeOperands| {
+                            let operand_slices: Vec<&[u8]> = operands.iter().collect();
+                            DeltaLog::partial_merge_for_rocksdb(&operand_slices)
+                        },
+                    );
+                    // Drop the delta logs left with nothing to hide, expired adds of base
+                    // edges stay as removes
+                    cf_opts.set_compaction_filter(
+                        "DeltaLogTtlFilter",
+                        |_level: u32, _key: &[u8], value: &[u8]| {
+                            if DeltaLog::is_expired_for_rocksdb(value) {
+                                Decision::Remove
+                            } else {
+                                Decision::Keep
+                            }
+                        },
+                    );
+                }
+                if *name == Self::CF_EDGE_HISTORY {
+                    // Every retained operation is kept, a merge appends the operands
+                    let retention_secs = options.edge_history_retention_secs;
+                    cf_opts.set_merge_operator(
+                        "EdgeHistoryMergeOperator",
+                        move |_key: &[u8],
+                              existing_value: Option<&[u8]>,
+                              operands: &MergeOperands| {
+                            let operand_slices: Vec<&[u8]> = operands.iter().collect();
+                            DeltaLog::append_for_rocksdb(
+                                existing_value,
+                                &operand_slices,
+                                retention_secs,
+                            )
+                        },
+                        |_key: &[u8], _left_operand: Option<&[u8]>, operands: &MergeOperands| {
+                            let operand_slices: Vec<&[u8]> = operands.iter().collect();
+                            DeltaLog::partial_merge_for_rocksdb(&operand_slices)
+                        },
+                    );
+                    // Drop the histories older than the retention
+                    cf_opts.set_compaction_filter(
+                        "EdgeHistoryRetentionFilter",
+                        move |_level: u32, _key: &[u8], value: &[u8]| {
+                            if DeltaLog::is_history_expired_for_rocksdb(value, retention_secs) {
+                                Decision::Remove
+                            } else {
+                                Decision::Keep
+                            }
+                        },
+                    );
+                }
+                ColumnFamilyDescriptor::new(*name, cf_opts)
+            })
+            .collect::<Vec<_>>();
 
         // Open database with column families
-        let db = DB::open_cf_descriptors(&db_opts, &db_path, cf_descriptors)?;
+        let (db, secondary_dir) = if secondary {
+            // The secondary keeps its info log apart from the files of the primary
+            let secondary_dir = tempfile::Builder::new()
+                .prefix("lsm-community-secondary-")
+                .tempdir()?;
+            db_opts.set_max_open_files(-1);
+            let db = DB::open_cf_descriptors_as_secondary(
+                &db_opts,
+                db_path.as_path(),
+                secondary_dir.path(),
+                cf_descriptors,
+            )?;
+            (db, Some(secondary_dir))
+        } else {
+            (
+                DB::open_cf_descriptors(&db_opts, &db_path, cf_descriptors)?,
+                None,
+            )
+        };
         let db = Arc::new(db);
+        let change_log = options
+            .change_log
+            .enabled
+            .then(|| ChangeLog::open(db.clone(), options.change_log.clone()))
+            .transpose()?;
 
-        // Initialize giant vertex cache with LRU eviction policy
+        // Initialize giant vertex cache weighed by the bytes of the adjacency lists
         let giant_cache = moka::sync::Cache::builder()
-            .max_capacity(options.giant_cache_capacity)
+            .weigher(|_vertex_id: &VId, neighbors: &Arc<VIdList>| {
+                let bytes =
+                    size_of::<VId>() + size_of::<VIdList>() + neighbors.len() * size_of::<VId>();
+                bytes.try_into().unwrap_or(u32::MAX)
+            })
+            .max_capacity(memory_budget.giant_cache)
             .build();
 
-        Ok(Self { db, giant_cache })
+        // Durability of every write
+        let mut write_options = WriteOptions::default();
+        write_options.disable_wal(options.external_storage.wal.disable_wal);
+        write_options.set_sync(options.external_storage.wal.sync);
+
+        Ok(Self {
+            db,
+            giant_cache,
+            write_options,
+            edge_history: options.edge_history,
+            change_log,
+            secondary_dir,
+        })
+    }
+
+    /// Build the RocksDB options of a column family, except for its write buffer size.
+    fn column_family_options(cf_options: &ColumnFamilyOptions, shared_cache: &Cache) -> Options {
+        let mut cf_opts = Options::default();
+        cf_opts.set_max_write_buffer_number(cf_options.max_write_buffer_number);
+        cf_opts.set_target_file_size_base(cf_options.target_file_size_base);
+        cf_opts
+            .set_level_zero_file_num_compaction_trigger(cf_options.level_zero_compaction_trigger);
+        cf_opts.set_level_zero_slowdown_writes_trigger(cf_options.level_zero_slowdown_trigger);
+        cf_opts.set_compression_type(match cf_options.compression {
+            CompressionType::None => DBCompressionType::None,
+            CompressionType::Snappy => DBCompressionType::Snappy,
+            CompressionType::Zlib => DBCompressionType::Zlib,
+            CompressionType::Bz2 => DBCompressionType::Bz2,
+            CompressionType::Lz4 => DBCompressionType::Lz4,
+            CompressionType::Lz4hc => DBCompressionType::Lz4hc,
+            CompressionType::Zstd => DBCompressionType::Zstd,
+        });
+
+        // Block-based table with the shared or a private block cache
+        let mut block_opts = BlockBasedOptions::default();
+        if cf_options.block_cache_size > 0 {
+            block_opts.set_block_cache(&Cache::new_lru_cache(cf_options.block_cache_size as usize));
+        } else {
+            block_opts.set_block_cache(shared_cache);
+        }
+        if cf_options.bloom_filter_bits > 0.0 {
+            block_opts.set_bloom_filter(cf_options.bloom_filter_bits, false);
+        }
+        cf_opts.set_block_based_table_factory(&block_opts);
+        cf_opts
+    }
+
+    /// Whether this is a read-only secondary instance.
+    pub fn is_secondary(&self) -> bool {
+        self.secondary_dir.is_some()
+    }
+
+    /// Replays the writes the primary committed since the open or the last catch up.
+    ///
+    /// Only valid on a secondary instance. The giant vertex cache is cleared, since the
+    /// primary may have changed any adjacency list.
+    pub fn try_catch_up_with_primary(&self) -> anyhow::Result<()> {
+        if !self.is_secondary() {
+            return Err(anyhow::anyhow!("Not a secondary instance"));
+        }
+        self.db.try_catch_up_with_primary()?;
+        self.giant_cache.invalidate_all();
+        Ok(())
+    }
+
+    /// Sequence number of the latest write to any column family.
+    ///
+    /// Tells whether the storage changed since a derived index was built: every delta,
+    /// property and change log write increases it.
+    pub fn latest_sequence_number(&self) -> u64 {
+        self.db.latest_sequence_number()
     }
 
     /// Append a single delta operation to a vertex's delta log.
@@ -167,12 +411,8 @@ impl ExternalStorage {
             .cf_handle(Self::CF_DELTAS)
             .ok_or_else(|| anyhow::anyhow!("Delta CF not found"))?;
 
-        let key = vertex_id.to_be_bytes();
-        let value = op.encode();
-
         // Use merge instead of put - this will be accumulated by the merge operator
-        self.db.merge_cf(&cf, &key, &value)?;
-        Ok(())
+        self.merge_delta(&cf, vertex_id, &[op])
     }
 
     /// Append multiple delta operations to a vertex's delta log in a single merge.
@@ -193,11 +433,161 @@ impl ExternalStorage {
             .cf_handle(Self::CF_DELTAS)
             .ok_or_else(|| anyhow::anyhow!("Delta CF not found"))?;
 
+        self.merge_delta(&cf, vertex_id, ops)
+    }
+
+    /// Append a delta operation to a vertex's delta log and put a property of its edge in
+    /// the same write, so that a crash keeps both or neither.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertex_id` - The vertex ID
+    /// * `op` - The delta operation to append
+    /// * `property_name` - Name of the property of the edge to `op.neighbor`
+    /// * `value` - The property value as raw bytes
+    pub fn append_delta_with_edge_property(
+        &self,
+        vertex_id: VId,
+        op: DeltaOperation,
+        property_name: String,
+        value: &[u8],
+    ) -> anyhow::Result<()> {
+        let cf = self
+            .db
+            .cf_handle(Self::CF_DELTAS)
+            .ok_or_else(|| anyhow::anyhow!("Delta CF not found"))?;
+        let property_cf = self
+            .db
+            .cf_handle(Self::CF_EDGE_PROPERTIES)
+            .ok_or_else(|| anyhow::anyhow!("Edge properties CF not found"))?;
+
+        let ops = [op];
+        let mut batch = self.delta_batch(&cf, vertex_id, &ops)?;
+        let key = EdgePropertyKey::new(vertex_id, op.neighbor, property_name);
+        batch.put_cf(&property_cf, key.encode(), value);
+        self.write_logged(batch, || {
+            let mut changes = Self::delta_changes(vertex_id, &ops);
+            changes.push(ChangeEvent::edge_property(
+                ChangeKind::PutEdgeProperty,
+                vertex_id,
+                op.neighbor,
+                &key.property_name,
+            ));
+            changes
+        })
+    }
+
+    /// Merge operations into the delta log of a vertex, and into the edge history in the
+    /// same write if it is retained.
+    fn merge_delta(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        vertex_id: VId,
+        ops: &[DeltaOperation],
+    ) -> anyhow::Result<()> {
+        let batch = self.delta_batch(cf, vertex_id, ops)?;
+        self.write_logged(batch, || Self::delta_changes(vertex_id, ops))
+    }
+
+    /// Batch merging operations into the delta log of a vertex, and into the edge history
+    /// if it is retained.
+    fn delta_batch(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        vertex_id: VId,
+        ops: &[DeltaOperation],
+    ) -> anyhow::Result<WriteBatch> {
         let key = vertex_id.to_be_bytes();
         let value = DeltaOperation::encode_batch(ops);
 
-        self.db.merge_cf(&cf, &key, &value)?;
-        Ok(())
+        let mut batch = WriteBatch::default();
+        batch.merge_cf(cf, key, &value);
+        if self.edge_history {
+            let history_cf = self
+                .db
+                .cf_handle(Self::CF_EDGE_HISTORY)
+                .ok_or_else(|| anyhow::anyhow!("Edge history CF not found"))?;
+            batch.merge_cf(&history_cf, key, &value);
+        }
+        Ok(batch)
+    }
+
+    /// Changes made by merging operations into the delta log of a vertex.
+    fn delta_changes(vertex_id: VId, ops: &[DeltaOperation]) -> Vec<ChangeEvent> {
+        ops.iter()
+            .filter_map(|op| {
+                let kind = match op.get_op_type()? {
+                    DeltaOpType::AddNeighbor => ChangeKind::AddEdge,
+                    DeltaOpType::RemoveNeighbor => ChangeKind::RemoveEdge,
+                };
+                Some(ChangeEvent::edge(kind, vertex_id, op.neighbor))
+            })
+            .collect()
+    }
+
+    /// Write a batch, and the changes it makes into the change log in the same write if
+    /// the log is kept.
+    fn write_logged(
+        &self,
+        batch: WriteBatch,
+        changes: impl FnOnce() -> Vec<ChangeEvent>,
+    ) -> anyhow::Result<()> {
+        match &self.change_log {
+            Some(change_log) => change_log.write(batch, changes(), &self.write_options),
+            None => {
+                self.db.write_opt(batch, &self.write_options)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Record a mutation made outside of the external storage, e.g. a vertex insertion,
+    /// in the change log. Does nothing without the `change_log` option.
+    pub fn log_change(&self, change: ChangeEvent) -> anyhow::Result<()> {
+        match &self.change_log {
+            Some(change_log) => {
+                change_log.write(WriteBatch::default(), vec![change], &self.write_options)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Subscribe to the change log from a sequence number on.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_seq` - Sequence number of the first change returned
+    ///
+    /// # Returns
+    ///
+    /// Returns the subscription, or an error if the change log is not kept or the
+    /// retention already dropped the change `from_seq`.
+    ///
+    /// Expired edges, the properties removed with them and the delta logs deleted with
+    /// [`ExternalStorage::delete_delta_log`] are not in the change log.
+    pub fn subscribe_changes(&self, from_seq: u64) -> anyhow::Result<ChangeSubscription> {
+        self.enabled_change_log()?.subscribe(from_seq)
+    }
+
+    /// Sequence number of the next change written to the change log.
+    pub fn next_change_seq(&self) -> anyhow::Result<u64> {
+        Ok(self.enabled_change_log()?.next_seq())
+    }
+
+    /// Drop the changes beyond the retention of the change log now, writes also trim it
+    /// from time to time.
+    ///
+    /// # Returns
+    ///
+    /// Returns the sequence number of the oldest change retained.
+    pub fn trim_change_log(&self) -> anyhow::Result<u64> {
+        self.enabled_change_log()?.trim(&self.write_options)
+    }
+
+    fn enabled_change_log(&self) -> anyhow::Result<&ChangeLog> {
+        self.change_log
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Change log is disabled"))
     }
 
     /// Read the delta log for a vertex.
@@ -211,7 +601,8 @@ impl ExternalStorage {
     ///
     /// # Returns
     ///
-    /// Returns Some(DeltaLog) if the vertex has delta operations, None otherwise.
+    /// Returns Some(DeltaLog) if the vertex has delta operations, None otherwise. Expired
+    /// adds are turned into removes, the removes of edges outside the base graph dropped.
     pub fn read_delta_log(&self, vertex_id: VId) -> anyhow::Result<Option<DeltaLog>> {
         let cf = self
             .db
@@ -222,16 +613,66 @@ impl ExternalStorage {
 
         match self.db.get_cf(&cf, &key)? {
             Some(bytes) => {
-                let log = DeltaLog::decode(&bytes)?;
+                let mut log = DeltaLog::decode(&bytes)?;
+                log.expire(generate_timestamp_micros());
                 Ok(Some(log))
             }
             None => Ok(None),
         }
     }
 
+    /// Read the history of the out edges of a vertex.
+    ///
+    /// With the `edge_history` option, every operation appended since the option was set
+    /// and retained by `edge_history_retention_secs`, expired ones included, and the latest
+    /// operation of each edge in the delta log. Otherwise only the latter.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertex_id` - The vertex ID
+    ///
+    /// # Returns
+    ///
+    /// Returns the operations in append order, empty if the vertex has none.
+    pub fn read_edge_history(&self, vertex_id: VId) -> anyhow::Result<Vec<DeltaOperation>> {
+        let key = vertex_id.to_be_bytes();
+        if !self.edge_history {
+            let cf = self
+                .db
+                .cf_handle(Self::CF_DELTAS)
+                .ok_or_else(|| anyhow::anyhow!("Delta CF not found"))?;
+            return match self.db.get_cf(&cf, &key)? {
+                Some(bytes) => Ok(DeltaLog::decode(&bytes)?.ops),
+                None => Ok(Vec::new()),
+            };
+        }
+
+        let cf = self
+            .db
+            .cf_handle(Self::CF_EDGE_HISTORY)
+            .ok_or_else(|| anyhow::anyhow!("Edge history CF not found"))?;
+        let mut ops = match self.db.get_cf(&cf, &key)? {
+            Some(bytes) => DeltaOperation::decode_batch(&bytes)?,
+            None => Vec::new(),
+        };
+        // Operations older than the retention are gone, the latest one of each edge is not
+        let retained: FxHashSet<(VId, u64)> =
+            ops.iter().map(|op| (op.neighbor, op.timestamp)).collect();
+        if let Some(log) = self.read_delta_log(vertex_id)? {
+            ops.extend(
+                log.ops
+                    .into_iter()
+                    .filter(|op| !retained.contains(&(op.neighbor, op.timestamp))),
+            );
+        }
+        ops.sort_by_key(|op| op.timestamp);
+        Ok(ops)
+    }
+
     /// Delete the delta log for a vertex.
     ///
-    /// This is useful after applying deltas to the base graph or during cleanup.
+    /// This is useful after applying deltas to the base graph or during cleanup. The
+    /// deletion is not recorded in the change log.
     ///
     /// # Arguments
     ///
@@ -243,7 +684,57 @@ impl ExternalStorage {
             .ok_or_else(|| anyhow::anyhow!("Delta CF not found"))?;
 
         let key = vertex_id.to_be_bytes();
-        self.db.delete_cf(&cf, &key)?;
+        self.db.delete_cf_opt(&cf, &key, &self.write_options)?;
+        Ok(())
+    }
+
+    /// Expiry times of the delta operations with a time-to-live, later than `after` in
+    /// microseconds since the Unix epoch, with the source and neighbor of their edge.
+    ///
+    /// Expired adds turned into removes keep their time-to-live and are listed too.
+    pub fn delta_expiries(&self, after: u64) -> anyhow::Result<Vec<(u64, VId, VId)>> {
+        let cf = self
+            .db
+            .cf_handle(Self::CF_DELTAS)
+            .ok_or_else(|| anyhow::anyhow!("Delta CF not found"))?;
+
+        let mut iter = self.db.raw_iterator_cf(&cf);
+        iter.seek_to_first();
+        let mut expiries = Vec::new();
+        while iter.valid() {
+            let key: [u8; 4] = iter
+                .key()
+                .unwrap()
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Invalid delta log key"))?;
+            let vertex_id = VId::from_be_bytes(key);
+            for op in DeltaLog::decode(iter.value().unwrap())?.ops {
+                if let Some(expires_at) = op.expires_at().filter(|&expires_at| expires_at > after) {
+                    expiries.push((expires_at, vertex_id, op.neighbor));
+                }
+            }
+            iter.next();
+        }
+        iter.status()?;
+        Ok(expiries)
+    }
+
+    /// Compact the delta logs of all vertices.
+    ///
+    /// Compaction runs in the background anyway, this merges the pending delta operations
+    /// now, turning the expired adds into removes, e.g. before a backup. Delta logs left
+    /// with nothing to hide are dropped.
+    pub fn compact_delta_logs(&self) -> anyhow::Result<()> {
+        let cf = self
+            .db
+            .cf_handle(Self::CF_DELTAS)
+            .ok_or_else(|| anyhow::anyhow!("Delta CF not found"))?;
+
+        // The compaction filter does not see the logs merged by the same compaction, the
+        // second one drops those the merge emptied
+        for _ in 0..2 {
+            self.db.compact_range_cf(&cf, None::<&[u8]>, None::<&[u8]>);
+        }
         Ok(())
     }
 
@@ -266,8 +757,9 @@ impl ExternalStorage {
         vertex_id: VId,
         neighbor_iter: impl Iterator<Item = VId>,
     ) -> anyhow::Result<()> {
-        // Collect neighbors into a VIdList
-        let neighbors: VIdList = neighbor_iter.collect();
+        // Collect neighbors into a sorted VIdList
+        let mut neighbors: VIdList = neighbor_iter.collect();
+        neighbors.sort_unstable();
         let neighbors_arc = Arc::new(neighbors.clone());
 
         // Serialize the neighbor list using bincode
@@ -284,7 +776,8 @@ impl ExternalStorage {
             .db
             .cf_handle(Self::CF_GIANT_VERTICES)
             .expect("Giant vertices CF should exist");
-        self.db.put_cf(&cf, &key, &compressed)?;
+        self.db
+            .put_cf_opt(&cf, &key, &compressed, &self.write_options)?;
 
         // Insert into cache (uncompressed)
         self.giant_cache.insert(vertex_id, neighbors_arc);
@@ -329,7 +822,11 @@ impl ExternalStorage {
 
         let compressed = self.db.get_cf(&cf, &key).ok()??;
         let decompressed = lz4::block::decompress(&compressed, None).ok()?;
-        let neighbors: VIdList = bincode::deserialize(&decompressed).ok()?;
+        let mut neighbors: VIdList = bincode::deserialize(&decompressed).ok()?;
+        // Lists written by older versions are unsorted, readers merge them in ascending order
+        if !neighbors.is_sorted() {
+            neighbors.sort_unstable();
+        }
 
         // Insert into cache
         let neighbors_arc = Arc::new(neighbors);
@@ -363,8 +860,61 @@ impl ExternalStorage {
         let key = VertexPropertyKey::new(vertex_id, property_name);
         let encoded_key = key.encode();
 
-        self.db.put_cf(&cf, &encoded_key, value)?;
-        Ok(())
+        let mut batch = WriteBatch::default();
+        batch.put_cf(&cf, &encoded_key, value);
+        self.write_logged(batch, || {
+            vec![ChangeEvent::vertex_property(
+                ChangeKind::PutVertexProperty,
+                vertex_id,
+                &key.property_name,
+            )]
+        })
+    }
+
+    /// Put the same property of many vertices into storage with one write batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `property_name` - Name of the property
+    /// * `values` - Pairs of vertex ID and property value as bytes
+    ///
+    /// # Returns
+    ///
+    /// Returns Ok(()) if successful, or an error if the operation fails.
+    pub fn put_vertex_properties_batch<I, V>(
+        &self,
+        property_name: &str,
+        values: I,
+    ) -> anyhow::Result<()>
+    where
+        I: IntoIterator<Item = (VId, V)>,
+        V: AsRef<[u8]>,
+    {
+        let cf = self
+            .db
+            .cf_handle(Self::CF_VERTEX_PROPERTIES)
+            .ok_or_else(|| anyhow::anyhow!("Vertex properties CF not found"))?;
+
+        let mut batch = WriteBatch::default();
+        let mut vertex_ids = Vec::new();
+        for (vertex_id, value) in values {
+            let key = VertexPropertyKey::new(vertex_id, property_name.to_owned());
+            batch.put_cf(&cf, key.encode(), value);
+            vertex_ids.push(vertex_id);
+        }
+
+        self.write_logged(batch, || {
+            vertex_ids
+                .into_iter()
+                .map(|vertex_id| {
+                    ChangeEvent::vertex_property(
+                        ChangeKind::PutVertexProperty,
+                        vertex_id,
+                        property_name,
+                    )
+                })
+                .collect()
+        })
     }
 
     /// Remove a vertex property from storage.
@@ -390,8 +940,15 @@ impl ExternalStorage {
         let key = VertexPropertyKey::new(vertex_id, property_name);
         let encoded_key = key.encode();
 
-        self.db.delete_cf(&cf, &encoded_key)?;
-        Ok(())
+        let mut batch = WriteBatch::default();
+        batch.delete_cf(&cf, &encoded_key);
+        self.write_logged(batch, || {
+            vec![ChangeEvent::vertex_property(
+                ChangeKind::RemoveVertexProperty,
+                vertex_id,
+                &key.property_name,
+            )]
+        })
     }
 
     /// Get a vertex property from storage.
@@ -495,8 +1052,16 @@ impl ExternalStorage {
         let key = EdgePropertyKey::new(source_id, destination_id, property_name);
         let encoded_key = key.encode();
 
-        self.db.put_cf(&cf, &encoded_key, value)?;
-        Ok(())
+        let mut batch = WriteBatch::default();
+        batch.put_cf(&cf, &encoded_key, value);
+        self.write_logged(batch, || {
+            vec![ChangeEvent::edge_property(
+                ChangeKind::PutEdgeProperty,
+                source_id,
+                destination_id,
+                &key.property_name,
+            )]
+        })
     }
 
     /// Remove an edge property from storage.
@@ -524,7 +1089,42 @@ impl ExternalStorage {
         let key = EdgePropertyKey::new(source_id, destination_id, property_name);
         let encoded_key = key.encode();
 
-        self.db.delete_cf(&cf, &encoded_key)?;
+        let mut batch = WriteBatch::default();
+        batch.delete_cf(&cf, &encoded_key);
+        self.write_logged(batch, || {
+            vec![ChangeEvent::edge_property(
+                ChangeKind::RemoveEdgeProperty,
+                source_id,
+                destination_id,
+                &key.property_name,
+            )]
+        })
+    }
+
+    /// Remove a property of an expired edge from storage.
+    ///
+    /// Unlike [`ExternalStorage::remove_edge_property`], the removal is not recorded in the
+    /// change log, the expiry of the edge is not either.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_id` - The source vertex ID
+    /// * `destination_id` - The destination vertex ID
+    /// * `property_name` - Name of the property to remove
+    pub fn remove_expired_edge_property(
+        &self,
+        source_id: VId,
+        destination_id: VId,
+        property_name: String,
+    ) -> anyhow::Result<()> {
+        let cf = self
+            .db
+            .cf_handle(Self::CF_EDGE_PROPERTIES)
+            .ok_or_else(|| anyhow::anyhow!("Edge properties CF not found"))?;
+
+        let key = EdgePropertyKey::new(source_id, destination_id, property_name);
+        self.db
+            .delete_cf_opt(&cf, key.encode(), &self.write_options)?;
         Ok(())
     }
 
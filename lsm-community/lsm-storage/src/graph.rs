@@ -1,6 +1,6 @@
 use crate::types::{CommId, VId, VIdList};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::Path;
 use std::vec;
 
@@ -260,6 +260,70 @@ impl CsrGraph {
         )
     }
 
+    /// Builds a CSR graph from adjacency lists and a community assignment.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_lists` - Out neighbors of each vertex
+    /// * `community_map` - Community ID of each vertex, one entry per adjacency list
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CsrGraph)` - Constructed CSR graph
+    /// * `Err(std::io::Error)` - The community map does not cover every vertex
+    pub fn from_edge_lists(
+        edge_lists: Vec<Vec<VId>>,
+        community_map: Vec<CommId>,
+    ) -> std::io::Result<Self> {
+        if edge_lists.len() != community_map.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Community map has {} entries for {} vertices",
+                    community_map.len(),
+                    edge_lists.len()
+                ),
+            ));
+        }
+        let num_vertices = edge_lists.len();
+        let num_edges = edge_lists.iter().map(Vec::len).sum();
+        let max_comm_id = community_map.iter().copied().max().unwrap_or(0);
+        Self::build_csr(
+            num_vertices,
+            num_edges,
+            edge_lists,
+            community_map,
+            max_comm_id,
+        )
+    }
+
+    /// Writes the graph in the text format read by [`CsrGraph::from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the graph file, overwritten if it exists
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Successfully written
+    /// * `Err(std::io::Error)` - File writing error
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        const BUFFER_SIZE: usize = 8 * 1024 * 1024;
+        let file = File::create(path.as_ref())?;
+        let mut writer = BufWriter::with_capacity(BUFFER_SIZE, file);
+
+        writeln!(writer, "t {} {}", self.num_vertices, self.num_edges)?;
+        for (vertex_id, comm_id) in self.community_map.iter().enumerate() {
+            writeln!(writer, "v {} 0 {}", vertex_id, comm_id)?;
+        }
+        for vertex_id in 0..self.num_vertices {
+            for neighbor in self.get_neighbor_iter(vertex_id as VId) {
+                writeln!(writer, "e {} {}", vertex_id, neighbor)?;
+            }
+        }
+        writer.flush()
+    }
+
     /// Parses the metadata line to extract vertex and edge counts.
     ///
     /// # Arguments
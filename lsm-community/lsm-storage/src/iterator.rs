@@ -1,20 +1,29 @@
-use std::{sync::Arc, vec::IntoIter};
+use std::sync::Arc;
 
 use crate::{
     block::Block,
+    delta::{DeltaLog, DeltaOpType, DeltaOperation},
+    mem_graph::SharedNeighborList,
     types::{VId, VIdList},
 };
 
+/// Neighbor iterator of a normal vertex, merging its mem graph list and block list.
+///
+/// Both lists are sorted, the iterator yields their sorted union. The mem graph list is
+/// held as a shared snapshot taken on creation, so concurrent edge inserts neither block
+/// nor shift the iteration, and the block list is read in place: iterating allocates
+/// nothing. A block list written unsorted by an older version is sorted into a copy of
+/// the mem graph list instead.
 pub struct GlobalNeighborIterator {
-    mem_iter: IntoIter<VId>,
+    mem_neighbors: Option<Arc<VIdList>>,
+    mem_pos: usize,
     block_arc: Option<Arc<Block>>,
     block_iter_state: Option<(usize, usize, usize)>,
-    from_mem: bool,
 }
 
 impl GlobalNeighborIterator {
     pub fn new(
-        mem_neighbors: Vec<VId>,
+        mem_neighbors: Option<SharedNeighborList>,
         block_arc: Option<Arc<Block>>,
         vertex_offset: Option<usize>,
     ) -> Self {
@@ -48,11 +57,69 @@ impl GlobalNeighborIterator {
             None
         };
 
-        Self {
-            mem_iter: mem_neighbors.into_iter(),
+        // Snapshot the mem graph list under a single lock, without copying it
+        let mem_neighbors = mem_neighbors.map(|neighbors| Arc::clone(&neighbors.read().unwrap()));
+        let mut iter = Self {
+            mem_neighbors,
+            mem_pos: 0,
             block_arc,
             block_iter_state,
-            from_mem: true,
+        };
+        if !iter.block_neighbors().is_sorted() {
+            let mut neighbors: VIdList = iter.mem_neighbors.as_deref().cloned().unwrap_or_default();
+            neighbors.extend(iter.block_neighbors());
+            neighbors.sort_unstable();
+            iter.mem_neighbors = Some(Arc::new(neighbors));
+            iter.block_arc = None;
+            iter.block_iter_state = None;
+        }
+        iter
+    }
+
+    /// The remaining neighbors of the block list.
+    fn block_neighbors(&self) -> impl Iterator<Item = VId> + '_ {
+        let (current, end, edge_offset) = self.block_iter_state.unwrap_or_default();
+        self.block_arc.iter().flat_map(move |block| {
+            (current..end).map(move |index| {
+                let offset = edge_offset + index * 4;
+                u32::from_be_bytes([
+                    block.data[offset],
+                    block.data[offset + 1],
+                    block.data[offset + 2],
+                    block.data[offset + 3],
+                ])
+            })
+        })
+    }
+
+    /// Peek the next neighbor of the mem graph list.
+    #[inline]
+    fn peek_mem(&self) -> Option<VId> {
+        self.mem_neighbors.as_ref()?.get(self.mem_pos).copied()
+    }
+
+    /// Peek the next neighbor of the block list.
+    #[inline]
+    fn peek_block(&self) -> Option<VId> {
+        let block = self.block_arc.as_ref()?;
+        let (current, end, edge_offset) = self.block_iter_state?;
+        if current < end {
+            let offset = edge_offset + current * 4;
+            Some(u32::from_be_bytes([
+                block.data[offset],
+                block.data[offset + 1],
+                block.data[offset + 2],
+                block.data[offset + 3],
+            ]))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn advance_block(&mut self) {
+        if let Some((current, _, _)) = &mut self.block_iter_state {
+            *current += 1;
         }
     }
 }
@@ -61,33 +128,122 @@ impl Iterator for GlobalNeighborIterator {
     type Item = VId;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.from_mem {
-            if let Some(vid) = self.mem_iter.next() {
-                return Some(vid);
+        match (self.peek_mem(), self.peek_block()) {
+            (Some(mem_vid), Some(block_vid)) if mem_vid <= block_vid => {
+                self.mem_pos += 1;
+                Some(mem_vid)
+            }
+            (_, Some(block_vid)) => {
+                self.advance_block();
+                Some(block_vid)
+            }
+            (Some(mem_vid), None) => {
+                self.mem_pos += 1;
+                Some(mem_vid)
             }
-            self.from_mem = false;
+            (None, None) => None,
         }
+    }
+}
 
-        let Some(block) = &self.block_arc else {
-            return None;
-        };
+/// Streaming merge of a sorted base neighbor list with a delta log.
+///
+/// The delta operations are ordered by (neighbor, timestamp) once on construction, after
+/// that base neighbors and operations are walked in lockstep: a neighbor whose latest
+/// operation is a removal is skipped, an added neighbor is yielded once and duplicate
+/// base neighbors are collapsed. Iterating never allocates.
+pub struct MergedNeighborIterator<B: Iterator<Item = VId>> {
+    base: B,
+    base_head: Option<VId>,
+    deltas: Vec<DeltaOperation>,
+    delta_pos: usize,
+    last_yielded: Option<VId>,
+}
 
-        let Some((current, end, edge_offset)) = &mut self.block_iter_state else {
-            return None;
-        };
+impl<B: Iterator<Item = VId>> MergedNeighborIterator<B> {
+    /// Create a merged iterator.
+    ///
+    /// # Arguments
+    /// * `base` - Base neighbors in ascending order
+    /// * `delta` - Delta log of the vertex, if any
+    pub fn new(mut base: B, delta: Option<DeltaLog>) -> Self {
+        let mut deltas = delta.map(|log| log.ops).unwrap_or_default();
+        deltas.sort_unstable_by_key(|op| (op.neighbor, op.timestamp));
+        let base_head = base.next();
+        Self {
+            base,
+            base_head,
+            deltas,
+            delta_pos: 0,
+            last_yielded: None,
+        }
+    }
 
-        if *current < *end {
-            let offset = *edge_offset + *current * 4;
-            let vid = u32::from_be_bytes([
-                block.data[offset],
-                block.data[offset + 1],
-                block.data[offset + 2],
-                block.data[offset + 3],
-            ]);
-            *current += 1;
-            Some(vid)
-        } else {
-            None
+    /// Consume the delta operations of the next neighbor, returning it with its final state.
+    #[inline]
+    fn next_delta(&mut self) -> Option<(VId, Option<bool>)> {
+        let neighbor = self.deltas.get(self.delta_pos)?.neighbor;
+        let mut present = None;
+        while let Some(op) = self.deltas.get(self.delta_pos) {
+            if op.neighbor != neighbor {
+                break;
+            }
+            match op.get_op_type() {
+                Some(DeltaOpType::AddNeighbor) => present = Some(true),
+                Some(DeltaOpType::RemoveNeighbor) => present = Some(false),
+                // Reserved for future use, ignore unknown operations
+                None => {}
+            }
+            self.delta_pos += 1;
+        }
+        Some((neighbor, present))
+    }
+
+    #[inline]
+    fn advance_base(&mut self) {
+        self.base_head = self.base.next();
+    }
+}
+
+impl<B: Iterator<Item = VId>> Iterator for MergedNeighborIterator<B> {
+    type Item = VId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let delta_head = self.deltas.get(self.delta_pos).map(|op| op.neighbor);
+            let candidate = match (self.base_head, delta_head) {
+                (Some(base_vid), Some(delta_vid)) if base_vid < delta_vid => {
+                    self.advance_base();
+                    Some(base_vid)
+                }
+                (base_vid, Some(delta_vid)) => {
+                    let in_base = base_vid == Some(delta_vid);
+                    if in_base {
+                        self.advance_base();
+                    }
+                    let (_, present) = self.next_delta().unwrap();
+                    present.unwrap_or(in_base).then_some(delta_vid)
+                }
+                (Some(base_vid), None) => {
+                    self.advance_base();
+                    Some(base_vid)
+                }
+                (None, None) => return None,
+            };
+
+            if let Some(vid) = candidate {
+                // Collapse duplicate base neighbors
+                if self.last_yielded == Some(vid) {
+                    continue;
+                }
+                self.last_yielded = Some(vid);
+                return Some(vid);
+            } else if let Some(removed) = delta_head {
+                // A removed neighbor also hides its duplicates in the base list
+                while self.base_head == Some(removed) {
+                    self.advance_base();
+                }
+            }
         }
     }
 }
@@ -103,6 +259,8 @@ pub enum UnifiedNeighborIterator {
         neighbors: Arc<VIdList>,
         index: usize,
     },
+    /// Iterator for vertices without stored neighbors
+    Empty,
 }
 
 impl UnifiedNeighborIterator {
@@ -118,6 +276,11 @@ impl UnifiedNeighborIterator {
             index: 0,
         }
     }
+
+    /// Create an iterator without neighbors
+    pub fn empty() -> Self {
+        Self::Empty
+    }
 }
 
 impl Iterator for UnifiedNeighborIterator {
@@ -135,6 +298,7 @@ impl Iterator for UnifiedNeighborIterator {
                     None
                 }
             }
+            Self::Empty => None,
         }
     }
 
@@ -145,6 +309,7 @@ impl Iterator for UnifiedNeighborIterator {
                 let remaining = neighbors.len().saturating_sub(*index);
                 (remaining, Some(remaining))
             }
+            Self::Empty => (0, Some(0)),
         }
     }
 }
@@ -158,6 +323,7 @@ impl ExactSizeIterator for UnifiedNeighborIterator {
                 0
             }
             Self::External { neighbors, index } => neighbors.len().saturating_sub(*index),
+            Self::Empty => 0,
         }
     }
 }
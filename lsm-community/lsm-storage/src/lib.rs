@@ -2,6 +2,8 @@ pub mod algorithms;
 pub mod block;
 pub mod bucket;
 pub mod cache;
+pub mod catalog;
+pub mod change_log;
 pub mod comm_io;
 pub mod config;
 pub mod delta;
@@ -9,11 +11,13 @@ pub mod external;
 pub mod graph;
 pub mod iterator;
 pub mod mem_graph;
+pub mod memory;
 pub mod property;
 pub mod tests;
 pub mod types;
 pub mod utils;
 pub mod vertex_index;
 
-pub use comm_io::LsmCommunity;
+pub use catalog::{GraphCatalog, OpenMode};
+pub use comm_io::{LsmCommunity, ReadOnlyError};
 pub use config::{ConfigManager, LsmCommunityStorageOptions};
@@ -1,3 +1,4 @@
+use crate::iterator::GlobalNeighborIterator;
 use crate::types::{EdgeList, VId, VIdList, VirtualCommId};
 use anyhow::{Ok, Result};
 use crossbeam_skiplist::SkipMap;
@@ -6,6 +7,12 @@ use std::sync::{
     atomic::{AtomicUsize, Ordering},
 };
 
+/// Sorted neighbor list of a vertex in the mem graph.
+///
+/// Readers take a snapshot by cloning the inner `Arc` under the read lock, writers update
+/// the list in place with [`Arc::make_mut`], which copies it only while a snapshot is held.
+pub type SharedNeighborList = Arc<RwLock<Arc<VIdList>>>;
+
 /// In-memory graph structure using skip list for concurrent access.
 ///
 /// MemGraph stores graph vertices and their edges in memory, similar to a MemTable
@@ -13,7 +20,7 @@ use std::sync::{
 /// values wrapped in Arc<RwLock<>> to allow modification of existing entries.
 pub struct MemGraph {
     /// Skip list mapping vertex IDs to their neighbors
-    pub map: Arc<SkipMap<VId, Arc<RwLock<VIdList>>>>,
+    pub map: Arc<SkipMap<VId, SharedNeighborList>>,
     /// Approximate size of the graph in bytes
     approximate_size: Arc<AtomicUsize>,
     /// Virtual community ID this MemGraph belongs to
@@ -39,16 +46,15 @@ impl MemGraph {
         self.virtual_id
     }
 
-    // Get neighbor for MemGraph.
+    // Get neighbor for MemGraph, in ascending order and without cloning the list.
     pub fn get_neighbor_iter(&self, vertex_id: VId) -> impl Iterator<Item = VId> {
-        self.map
-            .get(&vertex_id)
-            .map(|entry| {
-                let neighbors = entry.value().read().unwrap();
-                neighbors.clone()
-            })
-            .unwrap_or_else(Vec::new)
-            .into_iter()
+        let neighbors = self.get_neighbor_list(vertex_id);
+        GlobalNeighborIterator::new(neighbors, None, None)
+    }
+
+    // Get the shared, sorted neighbor list of a vertex.
+    pub fn get_neighbor_list(&self, vertex_id: VId) -> Option<SharedNeighborList> {
+        self.map.get(&vertex_id).map(|entry| entry.value().clone())
     }
 
     /// Returns the approximate size of the MemGraph in bytes.
@@ -72,7 +78,8 @@ impl MemGraph {
 
         // Create new vertex with empty edge list
         let edge_list = Vec::new();
-        self.map.insert(vertex_id, Arc::new(RwLock::new(edge_list)));
+        self.map
+            .insert(vertex_id, Arc::new(RwLock::new(Arc::new(edge_list))));
 
         // Update approximate size
         // Size includes: VId + Arc + RwLock + Vec overhead
@@ -87,8 +94,8 @@ impl MemGraph {
     /// Put an edge into this MemGraph.
     ///
     /// Adds a directed edge from src_id to dst_id. If src_id doesn't exist,
-    /// it will be created automatically. The edge is appended to the source
-    /// vertex's edge list (duplicates are not checked).
+    /// it will be created automatically. The edge is inserted into the source
+    /// vertex's sorted edge list (duplicates are not checked).
     ///
     /// # Arguments
     ///
@@ -96,9 +103,11 @@ impl MemGraph {
     /// * `dst_id` - Destination vertex ID
     pub fn put_edge(&self, src_id: VId, dst_id: VId) -> Result<()> {
         if let Some(entry) = self.map.get(&src_id) {
-            // Source vertex exists, append the edge
+            // Source vertex exists, insert the edge at its sorted position
             let mut edge_list = entry.value().write().unwrap();
-            edge_list.push(dst_id);
+            let edge_list = Arc::make_mut(&mut edge_list);
+            let position = edge_list.partition_point(|&vid| vid <= dst_id);
+            edge_list.insert(position, dst_id);
 
             // Update approximate size (one VId added)
             self.approximate_size
@@ -106,7 +115,8 @@ impl MemGraph {
         } else {
             // Source vertex doesn't exist, create it with this edge
             let edge_list = vec![dst_id];
-            self.map.insert(src_id, Arc::new(RwLock::new(edge_list)));
+            self.map
+                .insert(src_id, Arc::new(RwLock::new(Arc::new(edge_list))));
 
             // Update approximate size (entry overhead + one VId)
             let entry_overhead = std::mem::size_of::<VId>() + std::mem::size_of::<VId>();
@@ -145,20 +155,24 @@ impl MemGraph {
         }
 
         // Insert edges for each source vertex
-        for (src_id, destinations) in edges_by_src {
+        for (src_id, mut destinations) in edges_by_src {
             let num_edges = destinations.len();
+            destinations.sort_unstable();
 
             if let Some(entry) = self.map.get(&src_id) {
-                // Source vertex exists, extend its neighbor list
+                // Source vertex exists, extend its neighbor list and keep it sorted
                 let mut neighbor_list = entry.value().write().unwrap();
+                let neighbor_list = Arc::make_mut(&mut neighbor_list);
                 neighbor_list.extend(destinations);
+                neighbor_list.sort_unstable();
 
                 // Update approximate size (num_edges * VId)
                 self.approximate_size
                     .fetch_add(num_edges * std::mem::size_of::<VId>(), Ordering::Relaxed);
             } else {
                 // Source vertex doesn't exist, create it with these neighbors
-                self.map.insert(src_id, Arc::new(RwLock::new(destinations)));
+                self.map
+                    .insert(src_id, Arc::new(RwLock::new(Arc::new(destinations))));
 
                 // Update approximate size (entry overhead + edges)
                 let entry_overhead =
@@ -0,0 +1,114 @@
+use crate::config::LsmCommunityStorageOptions;
+
+/// Share of the memory budget given to the block cache (in percent).
+const BLOCK_CACHE_RATIO: u64 = 50;
+/// Share of the memory budget given to the giant-vertex cache (in percent).
+const GIANT_CACHE_RATIO: u64 = 10;
+/// Share of the memory budget given to the shared RocksDB block cache (in percent).
+const ROCKSDB_CACHE_RATIO: u64 = 20;
+/// Share of the memory budget given to the RocksDB memtables (in percent).
+const MEMTABLE_RATIO: u64 = 20;
+
+/// Lower bound of the memtable share, RocksDB needs a few write buffers to make progress.
+pub(crate) const MIN_MEMTABLE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Partition of the global `memory_budget` across the memory consumers of the storage engine.
+///
+/// All values are in bytes:
+/// * `block_cache` - Byte-weighted cache of decoded bucket blocks.
+/// * `giant_cache` - Byte-weighted cache of giant-vertex adjacency lists.
+/// * `rocksdb_cache` - LRU cache shared by all column families of the external DB.
+/// * `memtable` - Total size of the external DB memtables, enforced by a write buffer manager.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryBudget {
+    pub block_cache: u64,
+    pub giant_cache: u64,
+    pub rocksdb_cache: u64,
+    pub memtable: u64,
+}
+
+impl MemoryBudget {
+    /// Partition the memory budget of the options.
+    ///
+    /// A non-zero `block_cache_capacity` or `giant_cache_capacity` pins that cache to the given
+    /// number of bytes, the rest of the budget is split by the default ratios of the others.
+    /// The memtable floor is taken out of the unpinned caches, so the total never exceeds the
+    /// budget.
+    ///
+    /// # Arguments
+    /// * `options` - Storage options holding the budget and cache overrides
+    ///
+    /// # Returns
+    /// * `MemoryBudget` - The byte size of every memory consumer
+    pub fn from_options(options: &LsmCommunityStorageOptions) -> Self {
+        let pinned_block =
+            (options.block_cache_capacity > 0).then_some(options.block_cache_capacity);
+        let pinned_giant =
+            (options.giant_cache_capacity > 0).then_some(options.giant_cache_capacity);
+
+        let remaining = options
+            .memory_budget
+            .saturating_sub(pinned_block.unwrap_or(0))
+            .saturating_sub(pinned_giant.unwrap_or(0));
+        let free_ratio = ROCKSDB_CACHE_RATIO
+            + MEMTABLE_RATIO
+            + pinned_block.map_or(BLOCK_CACHE_RATIO, |_| 0)
+            + pinned_giant.map_or(GIANT_CACHE_RATIO, |_| 0);
+        let memtable = ((remaining as u128 * MEMTABLE_RATIO as u128 / free_ratio as u128) as u64)
+            .max(MIN_MEMTABLE_BYTES)
+            .min(remaining);
+
+        let cache_bytes = remaining - memtable;
+        let cache_ratio = free_ratio - MEMTABLE_RATIO;
+        let share = |ratio: u64| (cache_bytes as u128 * ratio as u128 / cache_ratio as u128) as u64;
+
+        Self {
+            block_cache: pinned_block.unwrap_or_else(|| share(BLOCK_CACHE_RATIO)),
+            giant_cache: pinned_giant.unwrap_or_else(|| share(GIANT_CACHE_RATIO)),
+            rocksdb_cache: share(ROCKSDB_CACHE_RATIO),
+            memtable,
+        }
+    }
+
+    /// Rebalance the budget once the on-disk footprint of the buckets is known.
+    ///
+    /// The block cache never needs more bytes than the block segments of all buckets, the
+    /// surplus is handed to the giant cache and the RocksDB cache in proportion to their shares.
+    /// Caches pinned by `block_cache_capacity` or `giant_cache_capacity` keep their size.
+    ///
+    /// # Arguments
+    /// * `options` - Storage options holding the cache overrides
+    /// * `block_working_set` - Total size of the block segments of all buckets in bytes
+    ///
+    /// # Returns
+    /// * `MemoryBudget` - The rebalanced budget, the total stays unchanged
+    pub fn rebalance(&self, options: &LsmCommunityStorageOptions, block_working_set: u64) -> Self {
+        if options.block_cache_capacity > 0 {
+            return *self;
+        }
+        let surplus = self.block_cache.saturating_sub(block_working_set);
+        if surplus == 0 {
+            return *self;
+        }
+        let receivers = self.giant_cache + self.rocksdb_cache;
+        let giant_gain = if options.giant_cache_capacity > 0 {
+            0
+        } else if receivers == 0 {
+            surplus / 2
+        } else {
+            (surplus as u128 * self.giant_cache as u128 / receivers as u128) as u64
+        };
+
+        Self {
+            block_cache: self.block_cache - surplus,
+            giant_cache: self.giant_cache + giant_gain,
+            rocksdb_cache: self.rocksdb_cache + (surplus - giant_gain),
+            memtable: self.memtable,
+        }
+    }
+
+    /// Total number of bytes covered by this budget.
+    pub fn total(&self) -> u64 {
+        self.block_cache + self.giant_cache + self.rocksdb_cache + self.memtable
+    }
+}
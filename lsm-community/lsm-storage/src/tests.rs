@@ -2,12 +2,17 @@ mod test_algorithm;
 mod test_block;
 mod test_bucket;
 mod test_cache;
+mod test_catalog;
+mod test_change_log;
 mod test_comm_io;
 mod test_config;
 mod test_delta;
 mod test_external;
+mod test_fault;
 mod test_graph;
+mod test_iterator;
 mod test_mem_graph;
+mod test_memory;
 mod test_property;
 mod test_utils;
 mod test_vertex_index;
@@ -64,6 +64,1329 @@ mod test_algorithm_bfs {
     }
 }
 
+#[cfg(test)]
+mod test_algorithm_parallel_bfs {
+    use crate::{
+        algorithms::bfs::BfsOptions, comm_io::LsmCommunity, config::LsmCommunityStorageOptions,
+        types::VId,
+    };
+    use serial_test::serial;
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_bfs_bottom_up_matches_top_down() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        // Force every level after the first to run bottom-up.
+        let bottom_up_options = BfsOptions {
+            alpha: u64::MAX,
+            beta: u64::MAX,
+            num_threads: Some(2),
+            ..Default::default()
+        };
+        for start_vertex in lsm_community.get_all_vertex_id() {
+            let top_down = lsm_community.bfs(start_vertex);
+            let bottom_up = lsm_community.bfs_with_options(start_vertex, &bottom_up_options)?;
+            assert_eq!(top_down, bottom_up, "Mismatch from vertex {}", start_vertex);
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_bfs_max_depth() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        let bfs_options = BfsOptions {
+            max_depth: Some(3),
+            ..Default::default()
+        };
+        let limited = lsm_community.bfs_with_options(0, &bfs_options)?;
+        let full = lsm_community.bfs(0);
+
+        let expected: Vec<(VId, u32)> = full.into_iter().filter(|(_, d)| *d <= 3).collect();
+        assert_eq!(limited, expected);
+        assert!(lsm_community.bfs(VId::MAX).is_empty());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_algorithm_centrality {
+    use crate::{
+        algorithms::centrality::CentralityOptions, comm_io::LsmCommunity,
+        config::LsmCommunityStorageOptions,
+    };
+    use serial_test::serial;
+
+    /// Hop distances and shortest path counts from every vertex, by BFS over the edge list.
+    fn all_pairs(lsm_community: &LsmCommunity) -> anyhow::Result<(Vec<Vec<u32>>, Vec<Vec<f64>>)> {
+        let vertex_count = lsm_community.vertex_count();
+        let mut neighbors = vec![Vec::new(); vertex_count];
+        let mut edges = lsm_community.read_all_edges()?;
+        edges.sort_unstable();
+        edges.dedup();
+        for (src, dst) in edges {
+            neighbors[src as usize].push(dst as usize);
+        }
+        let mut dist = vec![vec![u32::MAX; vertex_count]; vertex_count];
+        let mut sigma = vec![vec![0.0; vertex_count]; vertex_count];
+        for s in 0..vertex_count {
+            dist[s][s] = 0;
+            sigma[s][s] = 1.0;
+            let mut queue = std::collections::VecDeque::from([s]);
+            while let Some(v) = queue.pop_front() {
+                for &w in &neighbors[v] {
+                    if dist[s][w] == u32::MAX {
+                        dist[s][w] = dist[s][v] + 1;
+                        queue.push_back(w);
+                    }
+                    if dist[s][w] == dist[s][v] + 1 {
+                        sigma[s][w] += sigma[s][v];
+                    }
+                }
+            }
+        }
+        Ok((dist, sigma))
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_exact_centrality() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+        let vertex_count = lsm_community.vertex_count();
+        let (dist, sigma) = all_pairs(&lsm_community)?;
+
+        let betweenness = lsm_community.betweenness_centrality(&CentralityOptions::default())?;
+        let closeness = lsm_community.harmonic_closeness(&CentralityOptions::default())?;
+        for v in 0..vertex_count {
+            let mut expected_betweenness = 0.0;
+            let mut expected_closeness = 0.0;
+            for s in 0..vertex_count {
+                if s != v && dist[v][s] != u32::MAX {
+                    expected_closeness += 1.0 / dist[v][s] as f64;
+                }
+                for t in 0..vertex_count {
+                    if s == v || t == v || s == t || dist[s][t] == u32::MAX {
+                        continue;
+                    }
+                    if dist[s][v] != u32::MAX
+                        && dist[v][t] != u32::MAX
+                        && dist[s][v] + dist[v][t] == dist[s][t]
+                    {
+                        expected_betweenness += sigma[s][v] * sigma[v][t] / sigma[s][t];
+                    }
+                }
+            }
+            assert!(
+                (betweenness[v] - expected_betweenness).abs() < 1e-9,
+                "vertex {}",
+                v
+            );
+            assert!(
+                (closeness[v] - expected_closeness).abs() < 1e-9,
+                "vertex {}",
+                v
+            );
+        }
+        assert!(betweenness.iter().any(|&score| score > 0.0));
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_sampled_centrality() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+        let vertex_count = lsm_community.vertex_count();
+
+        // Sampling every vertex is exact
+        let exact = CentralityOptions::default();
+        let all_pivots = CentralityOptions {
+            samples: Some(vertex_count),
+            ..Default::default()
+        };
+        assert_eq!(
+            lsm_community.betweenness_centrality(&all_pivots)?,
+            lsm_community.betweenness_centrality(&exact)?
+        );
+        assert_eq!(
+            lsm_community.harmonic_closeness(&all_pivots)?,
+            lsm_community.harmonic_closeness(&exact)?
+        );
+
+        // Equal seeds give equal estimates, whatever the number of threads
+        let sampled = CentralityOptions {
+            samples: Some(vertex_count / 2),
+            seed: 11,
+            num_threads: None,
+        };
+        let threaded = CentralityOptions {
+            num_threads: Some(3),
+            ..sampled.clone()
+        };
+        let betweenness = lsm_community.betweenness_centrality(&sampled)?;
+        let closeness = lsm_community.harmonic_closeness(&sampled)?;
+        assert_eq!(betweenness.len(), vertex_count);
+        assert_eq!(
+            lsm_community.betweenness_centrality(&threaded)?,
+            betweenness
+        );
+        assert_eq!(lsm_community.harmonic_closeness(&threaded)?, closeness);
+        assert!(
+            betweenness
+                .iter()
+                .chain(&closeness)
+                .all(|&score| score >= 0.0)
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_algorithm_khop {
+    use crate::{
+        comm_io::LsmCommunity,
+        config::LsmCommunityStorageOptions,
+        graph::CsrGraph,
+        types::{Direction, VId},
+    };
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_k_hop_matches_bfs() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        for start_vertex in lsm_community.get_all_vertex_id() {
+            let k_hop = lsm_community.k_hop(start_vertex, 2, Direction::Out, None);
+            let expected: Vec<(VId, u32)> = lsm_community
+                .bfs(start_vertex)
+                .into_iter()
+                .filter(|&(_, d)| (1..=2).contains(&d))
+                .collect();
+            assert_eq!(k_hop, expected, "Mismatch from vertex {}", start_vertex);
+
+            let mut in_neighbors: Vec<(VId, u32)> = lsm_community
+                .read_in_neighbor_clone(start_vertex)?
+                .into_iter()
+                .filter(|&vid| vid != start_vertex)
+                .map(|vid| (vid, 1))
+                .collect();
+            in_neighbors.sort_unstable();
+            in_neighbors.dedup();
+            assert_eq!(
+                lsm_community.k_hop(start_vertex, 1, Direction::In, None),
+                in_neighbors
+            );
+        }
+
+        // Limit and invalid start
+        let limited = lsm_community.k_hop(0, 3, Direction::Both, Some(2));
+        assert_eq!(
+            limited.len(),
+            2.min(lsm_community.k_hop(0, 3, Direction::Both, None).len())
+        );
+        assert!(
+            lsm_community
+                .k_hop(VId::MAX, 2, Direction::Out, None)
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_ego_network() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        let start_vertex = 0;
+        let mut members: Vec<VId> = lsm_community
+            .k_hop(start_vertex, 1, Direction::Both, None)
+            .into_iter()
+            .map(|(vid, _)| vid)
+            .collect();
+        members.push(start_vertex);
+
+        let mut expected: Vec<(VId, VId)> = lsm_community
+            .read_all_edges()?
+            .into_iter()
+            .filter(|(src, dst)| members.contains(src) && members.contains(dst))
+            .collect();
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(lsm_community.ego_network(start_vertex, 1), expected);
+        assert!(lsm_community.ego_network(VId::MAX, 1).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_k_hop_in_follows_edge_updates() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut options = LsmCommunityStorageOptions::default();
+        options.work_space_dir = temp_dir.path().to_string_lossy().into_owned();
+        options.graph_name = "khop_updates".to_owned();
+        let lsm_community = LsmCommunity::create(options, CsrGraph::new())?;
+        for _ in 0..3 {
+            lsm_community.insert_vertex()?;
+        }
+        lsm_community.insert_edge(1, 0)?;
+        assert_eq!(lsm_community.k_hop(0, 2, Direction::In, None), vec![(1, 1)]);
+
+        // The cached in-neighbor lists follow inserts, removals and new vertices
+        lsm_community.insert_edge(2, 1)?;
+        assert_eq!(
+            lsm_community.k_hop(0, 2, Direction::In, None),
+            vec![(1, 1), (2, 2)]
+        );
+        lsm_community.remove_edge(1, 0)?;
+        assert!(lsm_community.k_hop(0, 2, Direction::In, None).is_empty());
+        let vertex_id = lsm_community.insert_vertex()?;
+        assert!(
+            lsm_community
+                .k_hop(vertex_id, 1, Direction::In, None)
+                .is_empty()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_algorithm_shortest_path {
+    use crate::{
+        algorithms::path::{EdgeWeight, ShortestPathAlgorithm},
+        comm_io::LsmCommunity,
+        config::LsmCommunityStorageOptions,
+        types::VId,
+    };
+    use serial_test::serial;
+    use std::sync::Arc;
+
+    fn test_weight(src: VId, dst: VId) -> f64 {
+        ((src * 7 + dst * 3) % 5 + 1) as f64
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_unit_weight_matches_bfs() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        let src_vertex = 0;
+        let bfs_result = lsm_community.bfs(src_vertex);
+        for &(dst_vertex, distance) in &bfs_result {
+            let shortest_path = lsm_community
+                .shortest_path(src_vertex, dst_vertex, &EdgeWeight::Unit)?
+                .expect("Reachable vertex must have a path");
+            assert_eq!(shortest_path.cost, distance as f64);
+            assert_eq!(shortest_path.path.len(), distance as usize + 1);
+            assert_eq!(shortest_path.path.first(), Some(&src_vertex));
+            assert_eq!(shortest_path.path.last(), Some(&dst_vertex));
+        }
+
+        // Unreachable target and invalid vertex
+        if let Some(unreachable) = lsm_community
+            .get_all_vertex_id()
+            .into_iter()
+            .find(|vid| bfs_result.iter().all(|(reached, _)| reached != vid))
+        {
+            assert!(
+                lsm_community
+                    .shortest_path(src_vertex, unreachable, &EdgeWeight::Unit)?
+                    .is_none()
+            );
+        }
+        assert!(
+            lsm_community
+                .shortest_path(src_vertex, VId::MAX, &EdgeWeight::Unit)
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_weighted_dijkstra_matches_bidirectional() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        let weight = EdgeWeight::Native(Arc::new(|src, dst| Some(test_weight(src, dst))));
+        let vertices = lsm_community.get_all_vertex_id();
+        for &src_vertex in vertices.iter().take(5) {
+            for &dst_vertex in &vertices {
+                let dijkstra = lsm_community.shortest_path_with_algorithm(
+                    src_vertex,
+                    dst_vertex,
+                    &weight,
+                    ShortestPathAlgorithm::Dijkstra,
+                )?;
+                let bidirectional = lsm_community.shortest_path_with_algorithm(
+                    src_vertex,
+                    dst_vertex,
+                    &weight,
+                    ShortestPathAlgorithm::Bidirectional,
+                )?;
+                assert_eq!(
+                    dijkstra.as_ref().map(|p| p.cost),
+                    bidirectional.as_ref().map(|p| p.cost),
+                    "Mismatch from {} to {}",
+                    src_vertex,
+                    dst_vertex
+                );
+
+                // The reported cost is the cost of the returned path
+                if let Some(shortest_path) = bidirectional {
+                    let path_cost: f64 = shortest_path
+                        .path
+                        .windows(2)
+                        .map(|edge| test_weight(edge[0], edge[1]))
+                        .sum();
+                    assert_eq!(path_cost, shortest_path.cost);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_weight_from_edge_property() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        let property_name = "test_shortest_path_weight";
+        for (src, dst) in lsm_community.read_all_edges()? {
+            lsm_community.put_edge_weight(src, dst, property_name, test_weight(src, dst))?;
+        }
+
+        let native = EdgeWeight::Native(Arc::new(|src, dst| Some(test_weight(src, dst))));
+        let property = EdgeWeight::Property(property_name.to_owned());
+        for (dst_vertex, _) in lsm_community.bfs(0) {
+            assert_eq!(
+                lsm_community.shortest_path(0, dst_vertex, &native)?,
+                lsm_community.shortest_path(0, dst_vertex, &property)?
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_algorithm_pagerank {
+    use crate::{
+        algorithms::pagerank::PageRankOptions, comm_io::LsmCommunity,
+        config::LsmCommunityStorageOptions, types::VId,
+    };
+    use serial_test::serial;
+
+    /// Sequential power iteration over the edge list.
+    fn reference_pagerank(
+        vertex_count: usize,
+        edges: &[(VId, VId)],
+        teleport: &[f64],
+        damping: f64,
+    ) -> Vec<f64> {
+        let mut out_degree = vec![0usize; vertex_count];
+        for &(src, _) in edges {
+            out_degree[src as usize] += 1;
+        }
+        let mut rank = teleport.to_vec();
+        for _ in 0..200 {
+            let dangling: f64 = (0..vertex_count)
+                .filter(|&v| out_degree[v] == 0)
+                .map(|v| rank[v])
+                .sum();
+            let mut next: Vec<f64> = teleport
+                .iter()
+                .map(|&t| (1.0 - damping + damping * dangling) * t)
+                .collect();
+            for &(src, dst) in edges {
+                next[dst as usize] +=
+                    damping * rank[src as usize] / out_degree[src as usize] as f64;
+            }
+            rank = next;
+        }
+        rank
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_pagerank_matches_reference() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        let vertex_count = lsm_community.vertex_count();
+        let mut edges = lsm_community.read_all_edges()?;
+        edges.sort_unstable();
+        edges.dedup();
+
+        let pagerank_options = PageRankOptions {
+            tolerance: 1e-12,
+            max_iterations: 200,
+            num_threads: Some(2),
+            ..Default::default()
+        };
+        let scores = lsm_community.pagerank_with_options(&pagerank_options)?;
+        let expected = reference_pagerank(
+            vertex_count,
+            &edges,
+            &vec![1.0 / vertex_count as f64; vertex_count],
+            pagerank_options.damping,
+        );
+        assert_eq!(scores.len(), vertex_count);
+        assert!((scores.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        for (vid, (score, expected)) in scores.iter().zip(&expected).enumerate() {
+            assert!(
+                (score - expected).abs() < 1e-9,
+                "Mismatch at vertex {}",
+                vid
+            );
+        }
+
+        // Personalized PageRank from a single seed
+        let seed: VId = 0;
+        let mut teleport = vec![0.0; vertex_count];
+        teleport[seed as usize] = 1.0;
+        let personalized = lsm_community.personalized_pagerank(&[seed], &pagerank_options)?;
+        let expected =
+            reference_pagerank(vertex_count, &edges, &teleport, pagerank_options.damping);
+        for (vid, (score, expected)) in personalized.iter().zip(&expected).enumerate() {
+            assert!(
+                (score - expected).abs() < 1e-9,
+                "Mismatch at vertex {}",
+                vid
+            );
+        }
+        assert!(
+            lsm_community
+                .personalized_pagerank(&[], &pagerank_options)
+                .is_err()
+        );
+        assert!(
+            lsm_community
+                .personalized_pagerank(&[VId::MAX], &pagerank_options)
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_pagerank_write_back() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        let property_name = "test_pagerank";
+        let pagerank_options = PageRankOptions {
+            write_back: Some(property_name.to_owned()),
+            ..Default::default()
+        };
+        let scores = lsm_community.pagerank_with_options(&pagerank_options)?;
+        for (vid, score) in scores.iter().enumerate() {
+            let stored = lsm_community
+                .external_db
+                .get_vertex_property(vid as VId, property_name.to_owned())?
+                .expect("Score must be written back");
+            assert_eq!(f64::from_le_bytes(stored.as_slice().try_into()?), *score);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_algorithm_triangle {
+    use crate::{comm_io::LsmCommunity, config::LsmCommunityStorageOptions, types::VId};
+    use rustc_hash::FxHashSet;
+    use serial_test::serial;
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_triangle_count_matches_brute_force() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        // Undirected simple graph from the edge list
+        let vertex_count = lsm_community.vertex_count();
+        let mut neighbors: Vec<FxHashSet<VId>> = vec![FxHashSet::default(); vertex_count];
+        for (src, dst) in lsm_community.read_all_edges()? {
+            if src != dst {
+                neighbors[src as usize].insert(dst);
+                neighbors[dst as usize].insert(src);
+            }
+        }
+        let mut expected = vec![0u64; vertex_count];
+        for u in 0..vertex_count {
+            for &v in &neighbors[u] {
+                for &w in &neighbors[u] {
+                    if v < w && neighbors[v as usize].contains(&w) {
+                        expected[u] += 1;
+                    }
+                }
+            }
+        }
+
+        let triangle_count = lsm_community.triangle_count();
+        assert_eq!(triangle_count.per_vertex, expected);
+        assert_eq!(triangle_count.total, expected.iter().sum::<u64>() / 3);
+
+        let clustering_coefficient = lsm_community.clustering_coefficient();
+        for (vid, coefficient) in clustering_coefficient.iter().enumerate() {
+            let degree = neighbors[vid].len() as f64;
+            let expected = if degree < 2.0 {
+                0.0
+            } else {
+                expected[vid] as f64 / (degree * (degree - 1.0) / 2.0)
+            };
+            assert!(
+                (coefficient - expected).abs() < 1e-12,
+                "Mismatch at vertex {}",
+                vid
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_algorithm_kcore {
+    use crate::{comm_io::LsmCommunity, config::LsmCommunityStorageOptions, types::VId};
+    use rustc_hash::FxHashSet;
+    use serial_test::serial;
+
+    /// Undirected simple edges `(u, v)` with `u < v`.
+    fn undirected_edges(lsm_community: &LsmCommunity) -> anyhow::Result<FxHashSet<(VId, VId)>> {
+        Ok(lsm_community
+            .read_all_edges()?
+            .into_iter()
+            .filter(|(src, dst)| src != dst)
+            .map(|(src, dst)| (src.min(dst), src.max(dst)))
+            .collect())
+    }
+
+    /// Vertices reachable from `start` through `edges`, sorted.
+    fn component(edges: &FxHashSet<(VId, VId)>, start: VId) -> Vec<VId> {
+        let mut members = vec![start];
+        let mut frontier = vec![start];
+        while let Some(vid) = frontier.pop() {
+            for &(u, v) in edges {
+                for (from, to) in [(u, v), (v, u)] {
+                    if from == vid && !members.contains(&to) {
+                        members.push(to);
+                        frontier.push(to);
+                    }
+                }
+            }
+        }
+        members.sort_unstable();
+        members
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_core_decomposition_and_k_core_search() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        let edges = undirected_edges(&lsm_community)?;
+        let core_number = lsm_community.core_decomposition();
+        let max_core = core_number.iter().copied().max().unwrap_or(0);
+        assert!(max_core > 0);
+
+        for k in 0..=max_core + 1 {
+            // Naive peeling of the vertices with fewer than k neighbors
+            let mut k_core_edges = edges.clone();
+            loop {
+                let mut degree = vec![0u32; lsm_community.vertex_count()];
+                for &(u, v) in &k_core_edges {
+                    degree[u as usize] += 1;
+                    degree[v as usize] += 1;
+                }
+                let before = k_core_edges.len();
+                k_core_edges.retain(|&(u, v)| degree[u as usize] >= k && degree[v as usize] >= k);
+                if k_core_edges.len() == before {
+                    break;
+                }
+            }
+            let in_core =
+                |vid: VId| k == 0 || k_core_edges.iter().any(|&(u, v)| u == vid || v == vid);
+
+            for vid in lsm_community.get_all_vertex_id() {
+                assert_eq!(core_number[vid as usize] >= k, in_core(vid), "k = {}", k);
+                let expected = in_core(vid).then(|| component(&k_core_edges, vid));
+                assert_eq!(lsm_community.k_core_search(vid, k), expected, "k = {}", k);
+            }
+        }
+        assert!(lsm_community.k_core_search(VId::MAX, 1).is_none());
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_k_truss_search() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        let edges = undirected_edges(&lsm_community)?;
+        for k in 2..=5 {
+            // Naive peeling of the edges closing fewer than k - 2 triangles
+            let mut truss_edges = edges.clone();
+            loop {
+                let adjacent = |a: VId, b: VId| truss_edges.contains(&(a.min(b), a.max(b)));
+                let weak: Vec<(VId, VId)> = truss_edges
+                    .iter()
+                    .copied()
+                    .filter(|&(u, v)| {
+                        let support = lsm_community
+                            .get_all_vertex_id()
+                            .into_iter()
+                            .filter(|&w| adjacent(u, w) && adjacent(v, w))
+                            .count();
+                        support < k as usize - 2
+                    })
+                    .collect();
+                if weak.is_empty() {
+                    break;
+                }
+                for edge in weak {
+                    truss_edges.remove(&edge);
+                }
+            }
+
+            for vid in lsm_community.get_all_vertex_id() {
+                let has_edge = truss_edges.iter().any(|&(u, v)| u == vid || v == vid);
+                let expected = has_edge.then(|| component(&truss_edges, vid));
+                assert_eq!(lsm_community.k_truss_search(vid, k), expected, "k = {}", k);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_algorithm_louvain {
+    use crate::{
+        algorithms::louvain::CommunityDetectionOptions,
+        comm_io::LsmCommunity,
+        config::LsmCommunityStorageOptions,
+        graph::CsrGraph,
+        types::{CommId, VId},
+    };
+    use rustc_hash::FxHashSet;
+    use serial_test::serial;
+
+    /// Modularity recomputed from the simple undirected edges.
+    fn reference_modularity(
+        lsm_community: &LsmCommunity,
+        community_map: &[CommId],
+    ) -> anyhow::Result<f64> {
+        let edges: FxHashSet<(VId, VId)> = lsm_community
+            .read_all_edges()?
+            .into_iter()
+            .filter(|(src, dst)| src != dst)
+            .map(|(src, dst)| (src.min(dst), src.max(dst)))
+            .collect();
+        let edge_count = edges.len() as f64;
+        let mut degree = vec![0.0; community_map.len()];
+        for &(u, v) in &edges {
+            degree[u as usize] += 1.0;
+            degree[v as usize] += 1.0;
+        }
+        // Q = 1 / 2m * sum over same-community pairs of (A_uv - k_u * k_v / 2m)
+        let mut sum = 0.0;
+        for u in 0..community_map.len() {
+            for v in 0..community_map.len() {
+                if community_map[u] == community_map[v] {
+                    let adjacent =
+                        edges.contains(&((u as VId).min(v as VId), (u as VId).max(v as VId)));
+                    sum += adjacent as u8 as f64 - degree[u] * degree[v] / (2.0 * edge_count);
+                }
+            }
+        }
+        Ok(sum / (2.0 * edge_count))
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_label_propagation_and_louvain() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+        let vertex_count = lsm_community.vertex_count();
+
+        let detection_options = CommunityDetectionOptions::default();
+        let label_propagation = lsm_community.label_propagation(&detection_options);
+        let louvain = lsm_community.louvain(&detection_options);
+        for assignment in [&label_propagation, &louvain] {
+            assert_eq!(assignment.community_map.len(), vertex_count);
+            let members: usize = assignment.community_list().iter().map(Vec::len).sum();
+            assert_eq!(members, vertex_count);
+            assert!(assignment.community_list().iter().all(|c| !c.is_empty()));
+            let expected = reference_modularity(&lsm_community, &assignment.community_map)?;
+            assert!((assignment.modularity - expected).abs() < 1e-9);
+        }
+        assert!(louvain.modularity > 0.0);
+        assert!(louvain.modularity >= label_propagation.modularity - 1e-9);
+
+        // Equal seeds give equal results
+        assert_eq!(lsm_community.louvain(&detection_options), louvain);
+        assert_eq!(
+            lsm_community.label_propagation(&detection_options),
+            label_propagation
+        );
+
+        // Modularity of the loaded communities and of trivial partitions
+        let loaded = lsm_community.vertex_index.read().community_map.clone();
+        let expected = reference_modularity(&lsm_community, &loaded)?;
+        assert!((lsm_community.modularity(&loaded)? - expected).abs() < 1e-9);
+        assert!(lsm_community.modularity(&vec![0; vertex_count])?.abs() < 1e-9);
+        assert!(lsm_community.modularity(&[0]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_apply_and_export_communities() -> anyhow::Result<()> {
+        let graph_name = "dyn";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+
+        let db_path = format!("./{}/{}", options.work_space_dir, options.graph_name);
+        let _ = std::fs::remove_dir_all(&db_path);
+
+        let lsm_community = LsmCommunity::open(options)?;
+        let vertex_count = lsm_community.vertex_count() as VId;
+        lsm_community.insert_edge(0, vertex_count - 1)?;
+        lsm_community.insert_edge(vertex_count - 1, 0)?;
+
+        // The detection sees the inserted edges
+        let louvain = lsm_community.louvain(&CommunityDetectionOptions::default());
+        let expected = reference_modularity(&lsm_community, &louvain.community_map)?;
+        assert!((louvain.modularity - expected).abs() < 1e-9);
+
+        lsm_community.apply_communities(&louvain)?;
+        assert_eq!(
+            lsm_community.community_detection(),
+            louvain.community_list()
+        );
+        for vid in lsm_community.get_all_vertex_id() {
+            let community = lsm_community.community_search(vid).unwrap();
+            assert!(community.contains(&vid));
+            assert!(community.iter().all(|&member| {
+                louvain.community_map[member as usize] == louvain.community_map[vid as usize]
+            }));
+        }
+
+        // The export carries the live topology and round-trips through the graph file
+        let mut edges = lsm_community.read_all_edges()?;
+        edges.sort_unstable();
+        let graph = lsm_community.export_graph(&louvain.community_map)?;
+        assert_eq!(graph.num_vertices(), vertex_count as usize);
+        assert_eq!(graph.num_edges(), edges.len());
+        assert_eq!(graph.community_map(), louvain.community_map.as_slice());
+
+        let graph_path = format!("{}/relayout.graph", db_path);
+        graph.write_to_file(&graph_path)?;
+        let reloaded = CsrGraph::from_file(&graph_path)?;
+        assert_eq!(reloaded.community_map(), louvain.community_map.as_slice());
+        let mut reloaded_edges: Vec<(VId, VId)> = (0..vertex_count)
+            .flat_map(|vid| reloaded.get_neighbor_iter(vid).map(move |dst| (vid, dst)))
+            .collect();
+        reloaded_edges.sort_unstable();
+        assert_eq!(reloaded_edges, edges);
+
+        // A stale assignment is rejected
+        lsm_community.insert_vertex()?;
+        assert!(lsm_community.apply_communities(&louvain).is_err());
+        assert!(lsm_community.export_graph(&louvain.community_map).is_err());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_algorithm_sampling {
+    use crate::{
+        algorithms::{
+            path::EdgeWeight,
+            sampling::{NeighborSamplingOptions, RandomWalkOptions},
+        },
+        comm_io::LsmCommunity,
+        config::LsmCommunityStorageOptions,
+        types::VId,
+    };
+    use serial_test::serial;
+    use std::sync::Arc;
+
+    /// Out-neighbor list of every vertex.
+    fn out_neighbors(lsm_community: &LsmCommunity) -> anyhow::Result<Vec<Vec<VId>>> {
+        let mut neighbors = vec![Vec::new(); lsm_community.vertex_count()];
+        for (src, dst) in lsm_community.read_all_edges()? {
+            neighbors[src as usize].push(dst);
+        }
+        Ok(neighbors)
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_random_walks() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+        let neighbors = out_neighbors(&lsm_community)?;
+        let seeds = lsm_community.get_all_vertex_id();
+
+        let walk_options = RandomWalkOptions {
+            walk_length: 10,
+            walks_per_vertex: 3,
+            seed: 7,
+            ..Default::default()
+        };
+        let walks = lsm_community.random_walks(&seeds, &walk_options)?;
+        assert_eq!(walks.len(), seeds.len() * 3);
+        for (i, walk) in walks.iter().enumerate() {
+            assert_eq!(walk[0], seeds[i % seeds.len()]);
+            assert!(walk.len() <= 10);
+            for step in walk.windows(2) {
+                assert!(neighbors[step[0] as usize].contains(&step[1]));
+            }
+            // Only dead ends stop a walk early
+            if walk.len() < 10 {
+                assert!(neighbors[*walk.last().unwrap() as usize].is_empty());
+            }
+        }
+
+        // Equal seeds give equal walks, whatever the number of threads
+        let threaded = RandomWalkOptions {
+            num_threads: Some(2),
+            ..walk_options.clone()
+        };
+        assert_eq!(lsm_community.random_walks(&seeds, &threaded)?, walks);
+
+        // A tiny return parameter sends every walk back where it came from
+        let returning = RandomWalkOptions {
+            p: 1e-12,
+            ..walk_options.clone()
+        };
+        for walk in lsm_community.random_walks(&seeds, &returning)?.iter() {
+            for step in walk.windows(3) {
+                if neighbors[step[1] as usize].contains(&step[0]) {
+                    assert_eq!(step[2], step[0]);
+                }
+            }
+        }
+
+        // Edges without a weight or with a zero weight are never followed
+        let weighted = RandomWalkOptions {
+            weight: EdgeWeight::Native(Arc::new(|_, dst| match dst % 3 {
+                0 => None,
+                1 => Some(0.0),
+                _ => Some(2.0),
+            })),
+            ..walk_options.clone()
+        };
+        for walk in lsm_community.random_walks(&seeds, &weighted)?.iter() {
+            assert!(walk[1..].iter().all(|&vid| vid % 3 == 2));
+        }
+
+        assert!(
+            lsm_community
+                .random_walks(&[VId::MAX], &walk_options)
+                .is_err()
+        );
+        let invalid = RandomWalkOptions {
+            q: 0.0,
+            ..walk_options
+        };
+        assert!(lsm_community.random_walks(&seeds, &invalid).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_sample_neighbors() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+        let neighbors = out_neighbors(&lsm_community)?;
+
+        let seeds = [0, 5, 0];
+        let sampling_options = NeighborSamplingOptions {
+            fanouts: vec![2, usize::MAX],
+            seed: 3,
+            ..Default::default()
+        };
+        let sample = lsm_community.sample_neighbors(&seeds, &sampling_options)?;
+        assert_eq!(&sample.nodes[..2], &[0, 5]);
+        assert_eq!(sample.hop_offsets.len(), 4);
+        assert_eq!(sample.layers.len(), 2);
+        let mut unique = sample.nodes.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), sample.nodes.len());
+
+        for (hop, layer) in sample.layers.iter().enumerate() {
+            let frontier = sample.hop_offsets[hop]..sample.hop_offsets[hop + 1];
+            assert_eq!(layer.targets.len(), layer.neighbors.len());
+            for (&target, &neighbor) in layer.targets.iter().zip(&layer.neighbors) {
+                assert!(frontier.contains(&(target as usize)));
+                assert!((neighbor as usize) < sample.hop_offsets[hop + 2]);
+                let (target, neighbor) = (
+                    sample.nodes[target as usize],
+                    sample.nodes[neighbor as usize],
+                );
+                assert!(neighbors[target as usize].contains(&neighbor));
+            }
+            // Every frontier vertex gets min(fanout, degree) neighbors
+            for local in frontier {
+                let degree = neighbors[sample.nodes[local] as usize].len();
+                let drawn = layer
+                    .targets
+                    .iter()
+                    .filter(|&&t| t as usize == local)
+                    .count();
+                assert_eq!(drawn, degree.min(sampling_options.fanouts[hop]));
+            }
+        }
+
+        // Equal seeds give equal samples, whatever the number of threads
+        let threaded = NeighborSamplingOptions {
+            num_threads: Some(2),
+            ..sampling_options.clone()
+        };
+        assert_eq!(lsm_community.sample_neighbors(&seeds, &threaded)?, sample);
+
+        // With replacement every vertex with out edges gets exactly the fan-out
+        let replacing = NeighborSamplingOptions {
+            fanouts: vec![5],
+            replace: true,
+            ..sampling_options.clone()
+        };
+        let sample = lsm_community.sample_neighbors(&[0, 5], &replacing)?;
+        for local in 0..2 {
+            let degree = neighbors[sample.nodes[local] as usize].len();
+            let drawn = sample.layers[0]
+                .targets
+                .iter()
+                .filter(|&&t| t as usize == local)
+                .count();
+            assert_eq!(drawn, if degree == 0 { 0 } else { 5 });
+        }
+
+        assert!(
+            lsm_community
+                .sample_neighbors(&[VId::MAX], &sampling_options)
+                .is_err()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_algorithm_reachability {
+    use crate::{comm_io::LsmCommunity, config::LsmCommunityStorageOptions, types::VId};
+    use serial_test::serial;
+    use std::time::Duration;
+
+    /// `result[u][v]` tells whether `v` is reachable from `u`, by BFS over the edge list.
+    fn reference_closure(lsm_community: &LsmCommunity) -> anyhow::Result<Vec<Vec<bool>>> {
+        let vertex_count = lsm_community.vertex_count();
+        let mut neighbors = vec![Vec::new(); vertex_count];
+        for (src, dst) in lsm_community.read_all_edges()? {
+            neighbors[src as usize].push(dst);
+        }
+        Ok((0..vertex_count)
+            .map(|start| {
+                let mut reached = vec![false; vertex_count];
+                let mut frontier = vec![start as VId];
+                reached[start] = true;
+                while let Some(vid) = frontier.pop() {
+                    for &neighbor in &neighbors[vid as usize] {
+                        if !reached[neighbor as usize] {
+                            reached[neighbor as usize] = true;
+                            frontier.push(neighbor);
+                        }
+                    }
+                }
+                reached
+            })
+            .collect())
+    }
+
+    fn assert_reachability(lsm_community: &LsmCommunity) -> anyhow::Result<()> {
+        let expected = reference_closure(lsm_community)?;
+        for src in lsm_community.get_all_vertex_id() {
+            for dst in lsm_community.get_all_vertex_id() {
+                assert_eq!(
+                    lsm_community.is_reachable(src, dst)?,
+                    expected[src as usize][dst as usize],
+                    "{} -> {}",
+                    src,
+                    dst
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_is_reachable() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        assert_reachability(&lsm_community)?;
+        assert!(lsm_community.is_reachable(0, VId::MAX).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_reachability_index_on_edge_updates() -> anyhow::Result<()> {
+        let graph_name = "dyn";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+
+        let db_path = format!("./{}/{}", options.work_space_dir, options.graph_name);
+        let _ = std::fs::remove_dir_all(&db_path);
+
+        // The index is maintained only with the option
+        let lsm_community = LsmCommunity::open(options.clone())?;
+        assert!(lsm_community.rebuild_reachability_index().is_err());
+        assert_reachability(&lsm_community)?;
+        assert!(lsm_community.reachability_index.lock().is_none());
+        drop(lsm_community);
+
+        options.reachability_index = true;
+        let lsm_community = LsmCommunity::open(options.clone())?;
+        lsm_community.rebuild_reachability_index()?;
+        let index_path = format!("{}/reachability_index.bin", db_path);
+        assert!(std::path::Path::new(&index_path).exists());
+        assert!(!std::path::Path::new(&format!("{}.tmp", index_path)).exists());
+
+        // Edges that cannot change reachability keep the index
+        let vertex_count = lsm_community.vertex_count() as VId;
+        let expected = reference_closure(&lsm_community)?;
+        let (src, dst) = (0..vertex_count)
+            .flat_map(|src| (0..vertex_count).map(move |dst| (src, dst)))
+            .find(|&(src, dst)| src != dst && expected[src as usize][dst as usize])
+            .unwrap();
+        lsm_community.insert_edge(src, dst)?;
+        lsm_community.insert_edge(src, src)?;
+        assert!(lsm_community.reachability_index.lock().is_some());
+        assert_reachability(&lsm_community)?;
+
+        // Other insertions and removals drop it, the next query rebuilds it
+        for (src, dst) in [(vertex_count - 1, 0), (0, vertex_count / 2)] {
+            lsm_community.insert_edge(src, dst)?;
+            assert_reachability(&lsm_community)?;
+        }
+        let edges = lsm_community.read_all_edges()?;
+        for &(src, dst) in edges.iter().step_by(edges.len() / 6 + 1) {
+            lsm_community.remove_edge(src, dst)?;
+            assert_reachability(&lsm_community)?;
+        }
+        let new_vertex = lsm_community.insert_vertex()?;
+        assert_reachability(&lsm_community)?;
+        lsm_community.insert_edge(new_vertex, 0)?;
+        assert_reachability(&lsm_community)?;
+
+        // The saved index is loaded with the graph
+        drop(lsm_community);
+        assert!(std::path::Path::new(&index_path).exists());
+        let lsm_community = LsmCommunity::open(options.clone())?;
+        assert!(lsm_community.reachability_index.lock().is_some());
+        assert_reachability(&lsm_community)?;
+
+        // Once the graph moves on the saved index no longer matches and is not loaded
+        lsm_community.insert_edge(0, new_vertex)?;
+        drop(lsm_community);
+        assert!(std::path::Path::new(&index_path).exists());
+        let lsm_community = LsmCommunity::open(options.clone())?;
+        assert!(lsm_community.reachability_index.lock().is_none());
+        assert_reachability(&lsm_community)?;
+
+        // An edge expiring while the graph is closed is dropped from the loaded index
+        let isolated_vertex = lsm_community.insert_vertex()?;
+        lsm_community.insert_edge_with_ttl(0, isolated_vertex, Duration::from_secs(1))?;
+        assert!(lsm_community.is_reachable(0, isolated_vertex)?);
+        drop(lsm_community);
+        std::thread::sleep(Duration::from_millis(1100));
+        let lsm_community = LsmCommunity::open(options)?;
+        assert!(lsm_community.reachability_index.lock().is_some());
+        assert!(!lsm_community.is_reachable(0, isolated_vertex)?);
+        assert_reachability(&lsm_community)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_algorithm_subgraph {
+    use crate::{comm_io::LsmCommunity, config::LsmCommunityStorageOptions, types::VId};
+    use serial_test::serial;
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_induced_subgraph() -> anyhow::Result<()> {
+        let graph_name = "dyn";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+
+        let db_path = format!("./{}/{}", options.work_space_dir, options.graph_name);
+        let _ = std::fs::remove_dir_all(&db_path);
+
+        let lsm_community = LsmCommunity::open(options)?;
+        let vertex_count = lsm_community.vertex_count() as VId;
+        lsm_community.insert_edge(vertex_count - 1, 1)?;
+        let (removed_src, removed_dst) = lsm_community.read_all_edges()?[0];
+        lsm_community.remove_edge(removed_src, removed_dst)?;
+
+        let selected = [vertex_count - 1, 1, 3, 1, removed_src, removed_dst, 0];
+        let subgraph = lsm_community.induced_subgraph(&selected)?;
+        let mut expected_ids = selected.to_vec();
+        expected_ids.sort_unstable();
+        expected_ids.dedup();
+        assert_eq!(subgraph.vertex_ids, expected_ids);
+        assert_eq!(subgraph.graph.num_vertices(), expected_ids.len());
+
+        // The edges between the selected vertices, delta logs applied
+        let mut expected_edges: Vec<(VId, VId)> = lsm_community
+            .read_all_edges()?
+            .into_iter()
+            .filter(|(src, dst)| expected_ids.contains(src) && expected_ids.contains(dst))
+            .collect();
+        expected_edges.sort_unstable();
+        expected_edges.dedup();
+        let mut edges: Vec<(VId, VId)> = (0..subgraph.graph.num_vertices() as VId)
+            .flat_map(|local| {
+                let subgraph = &subgraph;
+                subgraph
+                    .graph
+                    .get_neighbor_iter(local)
+                    .map(move |neighbor| {
+                        (
+                            subgraph.global_id(local).unwrap(),
+                            subgraph.global_id(neighbor).unwrap(),
+                        )
+                    })
+            })
+            .collect();
+        edges.sort_unstable();
+        assert_eq!(edges, expected_edges);
+        assert!(edges.contains(&(vertex_count - 1, 1)));
+        assert!(!edges.contains(&(removed_src, removed_dst)));
+
+        for (local, &vid) in subgraph.vertex_ids.iter().enumerate() {
+            assert_eq!(subgraph.local_id(vid), Some(local as VId));
+            let community = lsm_community.community_search(vid).unwrap();
+            let comm_id = subgraph.graph.community_map()[local];
+            assert_eq!(
+                lsm_community.community_detection()[comm_id as usize],
+                community
+            );
+        }
+        let unselected = (0..vertex_count)
+            .find(|vid| !expected_ids.contains(vid))
+            .unwrap();
+        assert_eq!(subgraph.local_id(unselected), None);
+        assert!(lsm_community.induced_subgraph(&[0, VId::MAX]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_community_subgraph() -> anyhow::Result<()> {
+        let graph_name = "example";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        let communities = lsm_community.community_detection();
+        for (comm_id, members) in communities.iter().enumerate() {
+            let subgraph = lsm_community.community_subgraph(comm_id as u32)?;
+            assert_eq!(&subgraph.vertex_ids, members);
+            assert!(
+                subgraph
+                    .graph
+                    .community_map()
+                    .iter()
+                    .all(|&c| c as usize == comm_id)
+            );
+            let internal_edges = lsm_community
+                .read_all_edges()?
+                .into_iter()
+                .filter(|(src, dst)| members.contains(src) && members.contains(dst))
+                .count();
+            assert_eq!(subgraph.graph.num_edges(), internal_edges);
+        }
+        assert!(
+            lsm_community
+                .community_subgraph(communities.len() as u32)
+                .is_err()
+        );
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test_algorithm_wcc {
     use crate::{comm_io::LsmCommunity, config::LsmCommunityStorageOptions};
@@ -90,6 +1413,97 @@ mod test_algorithm_wcc {
     }
 }
 
+#[cfg(test)]
+mod test_algorithm_incremental_wcc {
+    use crate::{comm_io::LsmCommunity, config::LsmCommunityStorageOptions, types::VId};
+    use serial_test::serial;
+
+    /// Label every vertex with the smallest vertex of its component.
+    fn canonical_components(labels: &[VId]) -> Vec<VId> {
+        let mut smallest = std::collections::HashMap::new();
+        for (vid, &label) in labels.iter().enumerate() {
+            smallest.entry(label).or_insert(vid as VId);
+        }
+        labels.iter().map(|label| smallest[label]).collect()
+    }
+
+    /// Components recomputed from the edge list.
+    fn reference_components(lsm_community: &LsmCommunity) -> anyhow::Result<Vec<VId>> {
+        let mut parent: Vec<VId> = (0..lsm_community.vertex_count() as VId).collect();
+        fn find(parent: &mut [VId], mut x: VId) -> VId {
+            while parent[x as usize] != x {
+                x = parent[x as usize];
+            }
+            x
+        }
+        for (src, dst) in lsm_community.read_all_edges()? {
+            let (root_src, root_dst) = (find(&mut parent, src), find(&mut parent, dst));
+            parent[root_src.max(root_dst) as usize] = root_src.min(root_dst);
+        }
+        let labels: Vec<VId> = (0..parent.len() as VId)
+            .map(|vid| find(&mut parent, vid))
+            .collect();
+        Ok(canonical_components(&labels))
+    }
+
+    fn assert_components(lsm_community: &LsmCommunity) -> anyhow::Result<()> {
+        let expected = reference_components(lsm_community)?;
+        let labels: Vec<VId> = lsm_community
+            .get_all_vertex_id()
+            .into_iter()
+            .map(|vid| lsm_community.component_of(vid).unwrap())
+            .collect();
+        assert_eq!(canonical_components(&labels), expected);
+        assert_eq!(canonical_components(&lsm_community.wcc()), expected);
+
+        let mut roots = expected.clone();
+        roots.sort_unstable();
+        roots.dedup();
+        assert_eq!(lsm_community.component_count(), roots.len());
+        Ok(())
+    }
+
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_incremental_wcc_on_edge_updates() -> anyhow::Result<()> {
+        let graph_name = "dyn";
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        options.incremental_wcc = true;
+
+        let db_path = format!("./{}/{}", options.work_space_dir, options.graph_name);
+        let _ = std::fs::remove_dir_all(&db_path);
+
+        let lsm_community = LsmCommunity::open(options)?;
+        assert_components(&lsm_community)?;
+
+        // Insertions merge components
+        let vertex_count = lsm_community.vertex_count() as VId;
+        for (src, dst) in [(0, vertex_count - 1), (1, vertex_count / 2), (2, 2)] {
+            lsm_community.insert_edge(src, dst)?;
+            assert_components(&lsm_community)?;
+        }
+
+        // Removals split components lazily
+        let edges = lsm_community.read_all_edges()?;
+        for &(src, dst) in edges.iter().step_by(edges.len() / 8 + 1) {
+            lsm_community.remove_edge(src, dst)?;
+            assert_components(&lsm_community)?;
+        }
+        lsm_community.remove_edge(0, vertex_count - 1)?;
+        assert_components(&lsm_community)?;
+
+        // A new vertex is a component of its own
+        let before = lsm_community.component_count();
+        let new_vertex = lsm_community.insert_vertex()?;
+        assert_eq!(lsm_community.component_count(), before + 1);
+        assert_eq!(lsm_community.component_of(new_vertex), Some(new_vertex));
+        assert!(lsm_community.component_of(new_vertex + 1).is_none());
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test_algorithm_community {
     use crate::{comm_io::LsmCommunity, config::LsmCommunityStorageOptions};
@@ -129,3 +1543,172 @@ mod test_algorithm_community {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test_algorithm_temporal {
+    use crate::{
+        comm_io::LsmCommunity,
+        config::LsmCommunityStorageOptions,
+        delta::EdgeInterval,
+        graph::CsrGraph,
+        types::{Direction, VId},
+    };
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// Insert the edges one after another, with distinct timestamps.
+    fn insert_edges(lsm_community: &LsmCommunity, edges: &[(VId, VId)]) -> anyhow::Result<()> {
+        for &(src, dst) in edges {
+            std::thread::sleep(Duration::from_millis(2));
+            lsm_community.insert_edge(src, dst)?;
+        }
+        Ok(())
+    }
+
+    /// The latest validity interval of the edge `(src, dst)`.
+    fn latest_interval(
+        lsm_community: &LsmCommunity,
+        src: VId,
+        dst: VId,
+    ) -> anyhow::Result<EdgeInterval> {
+        Ok(lsm_community
+            .read_out_edge_intervals(src)?
+            .into_iter()
+            .filter(|interval| interval.neighbor == dst)
+            .last()
+            .unwrap())
+    }
+
+    #[test]
+    fn test_edge_intervals_and_windows() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = "temporal".to_owned();
+        options.work_space_dir = temp_dir.path().to_string_lossy().into_owned();
+        options.edge_history = true;
+        let lsm_community = LsmCommunity::create(options.clone(), CsrGraph::new())?;
+        for _ in 0..3 {
+            lsm_community.insert_vertex()?;
+        }
+
+        // Added, removed, then added again
+        insert_edges(&lsm_community, &[(0, 1), (0, 2)])?;
+        std::thread::sleep(Duration::from_millis(2));
+        lsm_community.remove_edge(0, 1)?;
+        insert_edges(&lsm_community, &[(0, 1)])?;
+        let intervals = lsm_community.read_out_edge_intervals(0)?;
+        assert_eq!(intervals.len(), 3);
+        let (first, second, edge_2) = (intervals[0], intervals[1], intervals[2]);
+        assert_eq!(
+            (first.neighbor, second.neighbor, edge_2.neighbor),
+            (1, 1, 2)
+        );
+        assert!(first.valid_from < edge_2.valid_from);
+        assert!(edge_2.valid_from < first.valid_to);
+        assert!(first.valid_to < second.valid_from);
+        assert_eq!(second.valid_to, EdgeInterval::OPEN_END);
+
+        // Windows before, across and after the removal
+        let read = |from, to| lsm_community.read_out_neighbor_in_window(0, from, to);
+        assert_eq!(read(0, first.valid_from)?, Vec::<VId>::new());
+        assert_eq!(read(first.valid_from, edge_2.valid_from)?, vec![1]);
+        assert_eq!(read(first.valid_to, second.valid_from)?, vec![2]);
+        assert_eq!(read(0, u64::MAX)?, vec![1, 2]);
+
+        // Without the history only the latest interval is known, windows are refused
+        drop(lsm_community);
+        options.edge_history = false;
+        let lsm_community = LsmCommunity::open(options)?;
+        let intervals = lsm_community.read_out_edge_intervals(0)?;
+        assert_eq!(intervals, vec![second, edge_2]);
+        assert!(
+            lsm_community
+                .read_out_neighbor_in_window(0, 0, u64::MAX)
+                .is_err()
+        );
+        assert!(lsm_community.temporal_bfs(0, 0, u64::MAX).is_err());
+        assert!(
+            lsm_community
+                .temporal_reachability(0, 1, 0, u64::MAX)
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_temporal_bfs_respects_hop_order() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = "temporal".to_owned();
+        options.work_space_dir = temp_dir.path().to_string_lossy().into_owned();
+        options.edge_history = true;
+        let graph = CsrGraph::from_file("../data/example.graph")?;
+        let lsm_community = LsmCommunity::create(options, graph)?;
+        let base_vertices = lsm_community.vertex_count();
+        let vertices: Vec<VId> = (0..4)
+            .map(|_| lsm_community.insert_vertex())
+            .collect::<anyhow::Result<_>>()?;
+        let [a, b, c, d] = vertices[..] else {
+            unreachable!()
+        };
+        assert_eq!(a as usize, base_vertices);
+
+        // b -> c is gone before a -> b, c -> d comes after
+        insert_edges(&lsm_community, &[(b, c)])?;
+        std::thread::sleep(Duration::from_millis(2));
+        lsm_community.remove_edge(b, c)?;
+        insert_edges(&lsm_community, &[(a, b), (c, d)])?;
+        let (b_c, a_b, c_d) = (
+            latest_interval(&lsm_community, b, c)?,
+            latest_interval(&lsm_community, a, b)?,
+            latest_interval(&lsm_community, c, d)?,
+        );
+        assert!(b_c.valid_to < a_b.valid_from && a_b.valid_from < c_d.valid_from);
+
+        assert_eq!(
+            lsm_community.temporal_bfs(a, 0, u64::MAX)?,
+            vec![(b, a_b.valid_from)]
+        );
+        assert_eq!(
+            lsm_community.temporal_reachability(a, c, 0, u64::MAX)?,
+            None
+        );
+        assert_eq!(
+            lsm_community.temporal_bfs(b, 0, u64::MAX)?,
+            vec![(c, b_c.valid_from), (d, c_d.valid_from)]
+        );
+        // Leaving after the edge ended, or a window ending before the next edge
+        assert_eq!(
+            lsm_community.temporal_bfs(b, b_c.valid_to, u64::MAX)?,
+            vec![]
+        );
+        assert_eq!(
+            lsm_community.temporal_bfs(b, 0, c_d.valid_from)?,
+            vec![(c, b_c.valid_from)]
+        );
+        assert_eq!(
+            lsm_community.temporal_reachability(b, d, 0, u64::MAX)?,
+            Some(c_d.valid_from)
+        );
+        assert_eq!(lsm_community.temporal_reachability(d, d, 5, 6)?, Some(5));
+        assert!(lsm_community.temporal_bfs(VId::MAX, 0, u64::MAX).is_err());
+
+        // Edges of the base graph can be taken at any later time
+        insert_edges(&lsm_community, &[(d, 0)])?;
+        let d_0 = latest_interval(&lsm_community, d, 0)?.valid_from;
+        let reached = lsm_community.temporal_bfs(b, 0, u64::MAX)?;
+        assert!(reached.contains(&(0, d_0)));
+        let mut expected: Vec<VId> = lsm_community
+            .k_hop(0, u32::MAX, Direction::Out, None)
+            .into_iter()
+            .map(|(vid, _)| vid)
+            .collect();
+        expected.extend([c, d, 0]);
+        expected.sort_unstable();
+        expected.dedup();
+        let mut reached: Vec<VId> = reached.into_iter().map(|(vid, _)| vid).collect();
+        reached.sort_unstable();
+        assert_eq!(reached, expected);
+        Ok(())
+    }
+}
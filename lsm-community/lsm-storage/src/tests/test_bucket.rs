@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod test_bucket_disk_manager {
     use crate::bucket::disk_manager::{BktDiskManager, DiskManagerOptions};
+    use crate::bucket::io::IoBackend;
     use std::path::PathBuf;
     use tempfile::TempDir;
 
@@ -145,6 +146,24 @@ mod test_bucket_disk_manager {
         assert_eq!(results[3], expected_3);
     }
 
+    #[test]
+    fn test_read_batch_exceeding_queue_depth() {
+        let temp_dir = setup_test_dir();
+        let path = test_file_path(&temp_dir, "test_batch_read_deep.db");
+
+        // More requests than the default io_uring queue depth
+        let data: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        let manager = BktDiskManager::create(&path, &data).expect("Failed to create disk manager");
+
+        let requests: Vec<(u64, u64)> = (0..1000u64).map(|offset| (offset, 1)).collect();
+        let results = manager.read_batch(&requests).expect("Failed to batch read");
+
+        assert_eq!(results.len(), 1000);
+        for (offset, result) in results.iter().enumerate() {
+            assert_eq!(result, &vec![data[offset]]);
+        }
+    }
+
     #[test]
     fn test_write_batch() {
         let temp_dir = setup_test_dir();
@@ -214,6 +233,8 @@ mod test_bucket_disk_manager {
         let options = DiskManagerOptions {
             use_direct_io: true,
             queue_depth: 256,
+            read_only: false,
+            backend: IoBackend::default(),
         };
 
         let data = vec![7u8; 4096]; // Use page-aligned size
@@ -235,6 +256,326 @@ mod test_bucket_disk_manager {
         let verify_data = manager.read(0, 4096).expect("Failed to verify write");
         assert_eq!(verify_data, write_data);
     }
+
+    #[test]
+    fn test_direct_io_unaligned_access() {
+        let temp_dir = setup_test_dir();
+        let path = test_file_path(&temp_dir, "test_direct_io_unaligned.db");
+        let options = DiskManagerOptions {
+            use_direct_io: true,
+            ..Default::default()
+        };
+
+        // A file whose size is not a multiple of the alignment, like a bucket
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let manager = BktDiskManager::create_with_options(&path, &data, options)
+            .expect("Failed to create with Direct I/O");
+        assert_eq!(manager.size(), 10_000);
+
+        // Reads across an alignment boundary, a short tail block and the footer
+        assert_eq!(manager.read(4000, 200).unwrap(), data[4000..4200]);
+        assert_eq!(manager.read(8192, 10_000 - 8192).unwrap(), data[8192..]);
+        assert_eq!(manager.read(10_000 - 4, 4).unwrap(), data[10_000 - 4..]);
+        assert!(manager.read(9_990, 20).is_err());
+        let batch = manager
+            .read_batch(&[(0, 4096), (4096, 4096), (8192, 10_000 - 8192), (100, 3)])
+            .unwrap();
+        assert_eq!(batch[0], data[..4096]);
+        assert_eq!(batch[1], data[4096..8192]);
+        assert_eq!(batch[2], data[8192..]);
+        assert_eq!(batch[3], data[100..103]);
+
+        // Unaligned writes keep the bytes around them and the size of the file
+        manager.write(4090, &[1u8; 12]).unwrap();
+        manager.write(9_998, &[2u8; 2]).unwrap();
+        assert_eq!(manager.read(4088, 16).unwrap()[2..14], [1u8; 12]);
+        assert_eq!(manager.read(4088, 2).unwrap(), data[4088..4090]);
+        assert_eq!(
+            manager.read(9_996, 4).unwrap(),
+            [data[9_996], data[9_997], 2, 2]
+        );
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 10_000);
+    }
+
+    #[test]
+    fn test_direct_io_fallback() {
+        // tmpfs rejects O_DIRECT, the file falls back to buffered I/O
+        let Ok(temp_dir) = TempDir::new_in("/dev/shm") else {
+            return;
+        };
+        let path = test_file_path(&temp_dir, "test_direct_io_fallback.db");
+        let options = DiskManagerOptions {
+            use_direct_io: true,
+            ..Default::default()
+        };
+
+        let data = vec![9u8; 5000];
+        let manager = BktDiskManager::create_with_options(&path, &data, options)
+            .expect("Failed to create on tmpfs");
+        assert_eq!(manager.read(4095, 10).unwrap(), vec![9u8; 10]);
+        if cfg!(target_os = "linux") {
+            assert!(!manager.uses_direct_io());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_bucket_io {
+    use crate::bucket::disk_manager::{BktDiskManager, DiskManagerOptions};
+    use crate::bucket::io::{self, BucketIo, IoBackend, MemoryIo};
+    use std::io::ErrorKind;
+    use tempfile::TempDir;
+
+    const BACKENDS: [IoBackend; 4] = [
+        IoBackend::Uring,
+        IoBackend::Pread,
+        IoBackend::Mmap,
+        IoBackend::Memory,
+    ];
+
+    #[test]
+    fn test_backend_parity() {
+        let temp_dir = TempDir::new().unwrap();
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        for backend in BACKENDS {
+            let path = temp_dir.path().join(format!("{:?}.bkt", backend));
+            let options = DiskManagerOptions {
+                backend,
+                ..Default::default()
+            };
+            let manager = BktDiskManager::create_with_options(&path, &data, options).unwrap();
+            assert_eq!(manager.size(), 10_000, "{:?}", backend);
+            assert_eq!(path.exists(), backend != IoBackend::Memory);
+
+            assert_eq!(manager.read(4000, 200).unwrap(), data[4000..4200]);
+            assert!(manager.read(9_990, 20).is_err());
+            let batch = manager.read_batch(&[(0, 4096), (9_996, 4)]).unwrap();
+            assert_eq!(batch[0], data[..4096]);
+            assert_eq!(batch[1], data[9_996..]);
+
+            manager.write(4090, &[1u8; 12]).unwrap();
+            manager
+                .write_batch(&[(0, vec![2u8; 3]), (9_999, vec![3u8])])
+                .unwrap();
+            assert_eq!(manager.read(4090, 12).unwrap(), [1u8; 12]);
+            assert_eq!(manager.read(0, 4).unwrap(), [2, 2, 2, data[3]]);
+            assert_eq!(manager.read(9_998, 2).unwrap(), [data[9_998], 3]);
+
+            // Writes are seen after reopening
+            drop(manager);
+            let reopened = BktDiskManager::with_options(&path, options).unwrap();
+            assert_eq!(reopened.size(), 10_000, "{:?}", backend);
+            assert_eq!(reopened.read(4090, 12).unwrap(), [1u8; 12]);
+        }
+    }
+
+    #[test]
+    fn test_memory_backend() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("memory.bkt");
+        let options = DiskManagerOptions {
+            backend: IoBackend::Memory,
+            ..Default::default()
+        };
+
+        let error = BktDiskManager::with_options(&path, options).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+
+        let manager = BktDiskManager::create_with_options(&path, &[5u8; 100], options).unwrap();
+        assert!(!manager.uses_direct_io());
+        assert_eq!(MemoryIo::files_in(temp_dir.path()), vec![path.clone()]);
+
+        // A read-only handle shares the data but cannot write
+        let read_only = MemoryIo::open(&path, true).unwrap();
+        manager.write(98, &[6u8; 4]).unwrap();
+        assert_eq!(read_only.size(), 102);
+        let mut buf = [0u8; 8];
+        assert_eq!(read_only.read_at(96, &mut buf).unwrap(), 6);
+        assert_eq!(buf[..6], [5, 5, 6, 6, 6, 6]);
+        assert_eq!(
+            read_only.write_at(0, &[1]).unwrap_err().kind(),
+            ErrorKind::PermissionDenied
+        );
+
+        assert!(MemoryIo::remove(&path));
+        assert!(!MemoryIo::exists(&path));
+        assert_eq!(read_only.size(), 102);
+    }
+
+    #[test]
+    fn test_file_stamp_sees_replaced_files() {
+        let temp_dir = TempDir::new().unwrap();
+        for backend in [IoBackend::Pread, IoBackend::Memory] {
+            let path = temp_dir.path().join(format!("{:?}.bkt", backend));
+            let options = DiskManagerOptions {
+                backend,
+                ..Default::default()
+            };
+            let first = BktDiskManager::create_with_options(&path, &[1u8; 100], options).unwrap();
+            let stamp = io::file_stamp(&path, backend).unwrap();
+            assert_eq!(io::file_stamp(&path, backend).unwrap(), stamp);
+
+            // A file of the same size put in place of the old one has another stamp
+            let second = if backend == IoBackend::Memory {
+                BktDiskManager::create_with_options(&path, &[2u8; 100], options).unwrap()
+            } else {
+                let replacement = temp_dir.path().join("replacement.bkt");
+                let second =
+                    BktDiskManager::create_with_options(&replacement, &[2u8; 100], options)
+                        .unwrap();
+                std::fs::rename(&replacement, &path).unwrap();
+                second
+            };
+            let replaced = io::file_stamp(&path, backend).unwrap();
+            assert_ne!(replaced, stamp, "{:?}", backend);
+            drop((first, second));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_uring_batch_failing_part_way() {
+        use crate::bucket::io::UringIo;
+        use io_uring::{IoUring, opcode, types};
+        use std::sync::{Arc, Mutex};
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("uring.bkt");
+        std::fs::write(&path, [9u8; 100]).unwrap();
+        let options = DiskManagerOptions {
+            backend: IoBackend::Uring,
+            use_direct_io: false,
+            ..Default::default()
+        };
+        let ring = Arc::new(Mutex::new(IoUring::new(4).unwrap()));
+        let io = UringIo::with_ring(&path, options, ring.clone()).unwrap();
+
+        // The second operation of the first round fails, the later ones still complete
+        let error = io
+            .submit_all(6, |i| match i {
+                1 => opcode::Read::new(types::Fd(-1), std::ptr::null_mut(), 0).build(),
+                _ => opcode::Nop::new().build(),
+            })
+            .unwrap_err();
+        assert_eq!(error.raw_os_error(), Some(libc::EBADF));
+        assert!(ring.lock().unwrap().completion().is_empty());
+
+        // Another file on the ring gets its own results
+        let other = UringIo::with_ring(&path, options, ring).unwrap();
+        let mut buf = [0u8; 10];
+        assert_eq!(other.read_at(95, &mut buf).unwrap(), 5);
+        assert_eq!(buf[..5], [9u8; 5]);
+    }
+
+    #[test]
+    fn test_pool_batch_larger_than_queue() {
+        use crate::bucket::disk_manager::DiskManagerPool;
+
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("first.bkt");
+        let second = temp_dir.path().join("second.bkt");
+        std::fs::write(&first, (0..100u8).collect::<Vec<_>>()).unwrap();
+        std::fs::write(&second, (100..200u8).collect::<Vec<_>>()).unwrap();
+        let pool = DiskManagerPool::with_options(
+            vec![first.as_path(), second.as_path()],
+            DiskManagerOptions {
+                queue_depth: 4,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Ten reads over a queue of four, each one lands in its own buffer
+        let requests: Vec<(usize, u64, u64)> = (0..10).map(|i| (i % 2, i as u64 * 10, 5)).collect();
+        let read = pool.read_batch_multi(&requests).unwrap();
+        for (&(file_idx, offset, _), data) in requests.iter().zip(&read) {
+            let first_byte = file_idx as u8 * 100 + offset as u8;
+            assert_eq!(*data, (first_byte..first_byte + 5).collect::<Vec<_>>());
+        }
+
+        // A read past the end fails without disturbing the next batch
+        assert!(pool.read_batch_multi(&[(0, 0, 5), (1, 98, 5)]).is_err());
+        assert_eq!(
+            pool.read_batch_multi(&[(1, 0, 2)]).unwrap(),
+            vec![vec![100, 101]]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_mmap_backend_resize() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("mmap.bkt");
+        std::fs::write(&path, [7u8; 100]).unwrap();
+        let io = crate::bucket::io::MmapIo::open(
+            &path,
+            DiskManagerOptions {
+                backend: IoBackend::Mmap,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(!io.direct_io());
+
+        // Extending writes and truncation remap the file
+        io.write_at(100, &[8u8; 50]).unwrap();
+        assert_eq!(io.size(), 150);
+        let mut buf = [0u8; 4];
+        assert_eq!(io.read_at(98, &mut buf).unwrap(), 4);
+        assert_eq!(buf, [7, 7, 8, 8]);
+        io.set_len(10).unwrap();
+        assert_eq!(io.size(), 10);
+        assert_eq!(io.read_at(8, &mut buf).unwrap(), 2);
+        io.set_len(0).unwrap();
+        assert_eq!(io.read_at(0, &mut buf).unwrap(), 0);
+    }
+}
+
+#[cfg(test)]
+mod test_buffer_pool {
+    use crate::bucket::buffer_pool::{AlignedBufferPool, align_down, align_up};
+
+    #[test]
+    fn test_alignment_helpers() {
+        assert_eq!(align_up(0, 4096), 0);
+        assert_eq!(align_up(1, 4096), 4096);
+        assert_eq!(align_up(8192, 4096), 8192);
+        assert_eq!(align_down(8191, 4096), 4096);
+        assert_eq!(align_down(4096, 512), 4096);
+    }
+
+    #[test]
+    fn test_acquire_and_reuse() {
+        let pool = AlignedBufferPool::new(4096, 16 * 1024);
+
+        let address = {
+            let mut buffer = pool.acquire(100);
+            assert_eq!(buffer.len(), 100);
+            assert_eq!(buffer.as_ptr() as usize % 4096, 0);
+            buffer.fill(1);
+            buffer.as_ptr() as usize
+        };
+        assert_eq!(pool.pooled_bytes(), 4096);
+
+        // The smallest pooled buffer that fits is reused
+        {
+            let buffer = pool.acquire(4096);
+            assert_eq!(buffer.as_ptr() as usize, address);
+            assert_eq!(pool.pooled_bytes(), 0);
+            let larger = pool.acquire(5000);
+            assert_eq!(larger.len(), 5000);
+            assert_eq!(larger.as_ptr() as usize % 4096, 0);
+        }
+        assert_eq!(pool.pooled_bytes(), 4096 + 8192);
+
+        // Buffers beyond the pool limit are freed
+        drop((
+            pool.acquire(16 * 1024),
+            pool.acquire(4096),
+            pool.acquire(8192),
+        ));
+        assert!(pool.pooled_bytes() <= 16 * 1024);
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,9 @@
 #[cfg(test)]
 mod test_cache {
-    use crate::cache::CacheKey;
+    use std::sync::Arc;
+
+    use crate::block::Block;
+    use crate::cache::{CacheKey, new_block_cache};
 
     #[test]
     fn test_cache_key_roundtrip() {
@@ -38,4 +41,20 @@ mod test_cache {
         assert_ne!(key1, key4);
         assert_ne!(key2, key3);
     }
+
+    #[test]
+    fn test_block_cache_weighed_by_bytes() {
+        let block_size = 4096;
+        // Room for a handful of blocks only.
+        let cache = new_block_cache(4 * block_size as u64 + 1024);
+
+        for page_id in 0..64 {
+            let block = Block::new(vec![(page_id, 0)], vec![page_id + 1], block_size);
+            cache.insert(CacheKey::new(0, page_id), Arc::new(block));
+        }
+        cache.run_pending_tasks();
+
+        assert!(cache.weighted_size() <= 4 * block_size as u64 + 1024);
+        assert!(cache.entry_count() <= 4);
+    }
 }
@@ -0,0 +1,165 @@
+#[cfg(test)]
+mod test_catalog {
+    use crate::{
+        LsmCommunity, catalog::GraphCatalog, catalog::OpenMode, config::LsmCommunityStorageOptions,
+        graph::CsrGraph, types::VId,
+    };
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn sorted_edges(lsm_community: &LsmCommunity) -> anyhow::Result<Vec<(VId, VId)>> {
+        let mut edges = lsm_community.read_all_edges()?;
+        edges.sort_unstable();
+        Ok(edges)
+    }
+
+    #[test]
+    fn test_create_empty_graph() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let catalog = GraphCatalog::open(temp_dir.path())?;
+        assert!(catalog.list().is_empty());
+
+        let graph = catalog.create("social", LsmCommunityStorageOptions::default())?;
+        assert_eq!(graph.vertex_count(), 0);
+        assert_eq!(graph.options().graph_name, "social");
+        for _ in 0..3 {
+            graph.insert_vertex()?;
+        }
+        graph.insert_edge(0, 1)?;
+        graph.insert_edge(1, 2)?;
+        graph.insert_edge(2, 0)?;
+        graph.remove_edge(2, 0)?;
+        assert_eq!(sorted_edges(&graph)?, vec![(0, 1), (1, 2)]);
+        assert_eq!(catalog.info("social")?.open_mode, Some(OpenMode::ReadWrite));
+        drop(graph);
+
+        // The graph and its options survive a new catalog instance
+        drop(catalog);
+        let catalog = GraphCatalog::open(temp_dir.path())?;
+        let infos = catalog.list();
+        assert_eq!(infos.len(), 1);
+        assert_eq!(infos[0].name, "social");
+        assert_eq!(infos[0].bucket_count, 0);
+        assert!(infos[0].disk_size > 0);
+        assert_eq!(infos[0].open_mode, None);
+
+        let graph = catalog.open_graph("social", OpenMode::ReadWrite)?;
+        assert_eq!(graph.vertex_count(), 3);
+        assert_eq!(sorted_edges(&graph)?, vec![(0, 1), (1, 2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_rename_and_drop() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let catalog = GraphCatalog::open(temp_dir.path())?;
+        let options = LsmCommunityStorageOptions {
+            block_size: 8192,
+            ..Default::default()
+        };
+        let graph = catalog.import("example", "../data/example.graph", options)?;
+
+        let csr_graph = CsrGraph::from_file("../data/example.graph")?;
+        let mut expected_edges: Vec<(VId, VId)> = (0..csr_graph.num_vertices() as VId)
+            .flat_map(|src| csr_graph.get_neighbor_iter(src).map(move |dst| (src, dst)))
+            .collect();
+        expected_edges.sort_unstable();
+        assert_eq!(sorted_edges(&graph)?, expected_edges);
+        let info = catalog.info("example")?;
+        assert_eq!(info.options.block_size, 8192);
+        assert!(info.bucket_count > 0);
+
+        // A graph in use can be neither renamed nor dropped
+        assert!(catalog.rename("example", "renamed").is_err());
+        assert!(catalog.drop_graph("example").is_err());
+        drop(graph);
+
+        catalog.rename("example", "renamed")?;
+        assert!(!catalog.contains("example"));
+        assert!(!temp_dir.path().join("example").exists());
+        let graph = catalog.open_graph("renamed", OpenMode::ReadWrite)?;
+        assert_eq!(graph.options().graph_name, "renamed");
+        assert_eq!(graph.options().block_size, 8192);
+        assert_eq!(sorted_edges(&graph)?, expected_edges);
+        drop(graph);
+
+        catalog.drop_graph("renamed")?;
+        assert!(catalog.list().is_empty());
+        assert!(!temp_dir.path().join("renamed").exists());
+        assert!(catalog.open_graph("renamed", OpenMode::ReadOnly).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_mode() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let catalog = GraphCatalog::open(temp_dir.path())?;
+        drop(catalog.import(
+            "example",
+            "../data/example.graph",
+            LsmCommunityStorageOptions::default(),
+        )?);
+
+        let graph = catalog.open_graph("example", OpenMode::ReadOnly)?;
+        assert!(graph.is_read_only());
+        let edges = sorted_edges(&graph)?;
+        assert!(graph.insert_edge(0, 1).is_err());
+        assert!(graph.remove_edge(edges[0].0, edges[0].1).is_err());
+        assert!(graph.insert_vertex().is_err());
+        assert!(graph.put_vertex_property(0, b"value").is_err());
+        assert_eq!(sorted_edges(&graph)?, edges);
+
+        // The same mode shares the instance, the other mode waits for it to close
+        let shared = catalog.open_graph("example", OpenMode::ReadOnly)?;
+        assert!(Arc::ptr_eq(&graph, &shared));
+        assert!(catalog.open_graph("example", OpenMode::ReadWrite).is_err());
+        drop((graph, shared));
+
+        let graph = catalog.open_graph("example", OpenMode::ReadWrite)?;
+        assert!(!graph.is_read_only());
+        graph.insert_edge(0, 1)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_adopt_and_invalid_names() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let options = LsmCommunityStorageOptions {
+            graph_name: "built".to_owned(),
+            work_space_dir: temp_dir.path().to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        drop(LsmCommunity::create(
+            options.clone(),
+            CsrGraph::from_file("../data/example.graph")?,
+        )?);
+        assert!(LsmCommunity::create(options.clone(), CsrGraph::new()).is_err());
+
+        // Graphs built outside the catalog are adopted only with their options
+        let catalog = GraphCatalog::open(temp_dir.path())?;
+        assert!(!catalog.contains("built"));
+        assert!(catalog.open_graph("built", OpenMode::ReadOnly).is_err());
+        catalog.adopt("built", options.clone())?;
+        assert!(catalog.contains("built"));
+        assert!(catalog.adopt("built", options.clone()).is_err());
+        assert!(catalog.adopt("missing", options.clone()).is_err());
+        assert!(catalog.adopt("..", options.clone()).is_err());
+        assert_eq!(
+            catalog
+                .open_graph("built", OpenMode::ReadOnly)?
+                .vertex_count(),
+            13
+        );
+
+        let options = LsmCommunityStorageOptions::default();
+        assert!(catalog.create("built", options.clone()).is_err());
+        assert!(catalog.create("", options.clone()).is_err());
+        assert!(catalog.create("a/b", options.clone()).is_err());
+        assert!(catalog.create("..", options.clone()).is_err());
+        assert!(catalog.open_graph("missing", OpenMode::ReadWrite).is_err());
+        assert!(catalog.info("missing").is_err());
+        assert!(catalog.rename("missing", "other").is_err());
+        assert!(catalog.drop_graph("missing").is_err());
+        Ok(())
+    }
+}
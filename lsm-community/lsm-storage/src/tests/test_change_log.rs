@@ -0,0 +1,232 @@
+#[cfg(test)]
+mod test_change_log {
+    use crate::{
+        change_log::{ChangeEvent, ChangeKind},
+        comm_io::LsmCommunity,
+        config::LsmCommunityStorageOptions,
+        delta::{DeltaOpType, DeltaOperation},
+        external::ExternalStorage,
+        graph::CsrGraph,
+    };
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn options(temp_dir: &TempDir) -> LsmCommunityStorageOptions {
+        let mut options = LsmCommunityStorageOptions::default();
+        options.work_space_dir = temp_dir.path().to_string_lossy().into_owned();
+        options.graph_name = "changes".to_owned();
+        options.change_log.enabled = true;
+        options
+    }
+
+    /// The kind, vertex, neighbor and property name of each change.
+    fn summary(changes: &[ChangeEvent]) -> Vec<(ChangeKind, u32, Option<u32>, Option<&str>)> {
+        changes
+            .iter()
+            .map(|change| {
+                (
+                    change.kind,
+                    change.vertex_id,
+                    change.neighbor,
+                    change.property_name.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_change_log_records_mutations() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let storage = ExternalStorage::new(options(&temp_dir))?;
+        assert_eq!(storage.next_change_seq()?, 0);
+
+        storage.append_delta(1, DeltaOperation::new(10, DeltaOpType::AddNeighbor, 2))?;
+        storage.append_deltas_batch(
+            1,
+            &[
+                DeltaOperation::new(20, DeltaOpType::RemoveNeighbor, 2),
+                DeltaOperation::new(20, DeltaOpType::AddNeighbor, 3),
+            ],
+        )?;
+        storage.put_vertex_property(1, "name".to_owned(), b"one")?;
+        storage.remove_vertex_property(1, "name".to_owned())?;
+        storage.put_edge_property(1, 3, "weight".to_owned(), &[1])?;
+        storage.remove_edge_property(1, 3, "weight".to_owned())?;
+        storage.put_vertex_properties_batch("rank", [(1, [1u8]), (2, [2u8])])?;
+
+        let changes = storage.subscribe_changes(0)?.poll(100, Duration::ZERO)?;
+        assert_eq!(
+            summary(&changes),
+            vec![
+                (ChangeKind::AddEdge, 1, Some(2), None),
+                (ChangeKind::RemoveEdge, 1, Some(2), None),
+                (ChangeKind::AddEdge, 1, Some(3), None),
+                (ChangeKind::PutVertexProperty, 1, None, Some("name")),
+                (ChangeKind::RemoveVertexProperty, 1, None, Some("name")),
+                (ChangeKind::PutEdgeProperty, 1, Some(3), Some("weight")),
+                (ChangeKind::RemoveEdgeProperty, 1, Some(3), Some("weight")),
+                (ChangeKind::PutVertexProperty, 1, None, Some("rank")),
+                (ChangeKind::PutVertexProperty, 2, None, Some("rank")),
+            ]
+        );
+        assert!(changes.iter().map(|change| change.seq).eq(0..9));
+        assert!(changes.is_sorted_by_key(|change| change.timestamp));
+        // Changes of one write share its timestamp
+        assert_eq!(changes[1].timestamp, changes[2].timestamp);
+
+        // Polls resume where the previous one stopped
+        let mut subscription = storage.subscribe_changes(4)?;
+        assert_eq!(subscription.poll(2, Duration::ZERO)?, changes[4..6]);
+        assert_eq!(subscription.next_seq(), 6);
+        assert_eq!(subscription.poll(100, Duration::ZERO)?, changes[6..]);
+        assert!(subscription.poll(100, Duration::ZERO)?.is_empty());
+        drop(storage);
+
+        // Without the option nothing is logged, the sequence goes on once it is back
+        let mut disabled = options(&temp_dir);
+        disabled.change_log.enabled = false;
+        let storage = ExternalStorage::new(disabled)?;
+        assert!(storage.subscribe_changes(0).is_err());
+        storage.put_vertex_property(3, "name".to_owned(), b"three")?;
+        drop(storage);
+        assert!(subscription.poll(100, Duration::ZERO).is_err());
+        let storage = ExternalStorage::new(options(&temp_dir))?;
+        assert_eq!(storage.next_change_seq()?, 9);
+        storage.put_vertex_property(4, "name".to_owned(), b"four")?;
+        let mut subscription = storage.subscribe_changes(9)?;
+        assert_eq!(
+            summary(&subscription.poll(100, Duration::ZERO)?),
+            vec![(ChangeKind::PutVertexProperty, 4, None, Some("name"))]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_change_log_retention() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut retention = options(&temp_dir);
+        retention.change_log.max_changes = 3;
+        let storage = ExternalStorage::new(retention.clone())?;
+        for vertex_id in 0..10 {
+            storage.put_vertex_property(vertex_id, "name".to_owned(), b"")?;
+        }
+        assert_eq!(storage.trim_change_log()?, 7);
+        assert!(storage.subscribe_changes(6).is_err());
+        let mut subscription = storage.subscribe_changes(7)?;
+        assert_eq!(subscription.next_seq(), 7);
+
+        // A subscriber falling behind the retention gets an error, not a gap
+        for vertex_id in 10..15 {
+            storage.put_vertex_property(vertex_id, "name".to_owned(), b"")?;
+        }
+        assert_eq!(storage.trim_change_log()?, 12);
+        assert!(subscription.poll(100, Duration::ZERO).is_err());
+        drop(subscription);
+        drop(storage);
+
+        // Old changes go, the latest one is always retained
+        retention.change_log.max_changes = 0;
+        retention.change_log.retention_secs = 1;
+        let storage = ExternalStorage::new(retention)?;
+        std::thread::sleep(Duration::from_millis(1100));
+        storage.put_vertex_property(15, "name".to_owned(), b"")?;
+        assert_eq!(storage.trim_change_log()?, 15);
+        std::thread::sleep(Duration::from_millis(1100));
+        assert_eq!(storage.trim_change_log()?, 15);
+        let changes = storage.subscribe_changes(15)?.poll(100, Duration::ZERO)?;
+        assert_eq!(changes.len(), 1);
+        drop(storage);
+        assert_eq!(
+            ExternalStorage::new(options(&temp_dir))?.next_change_seq()?,
+            16
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_subscription_waits_for_changes() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let lsm_community = LsmCommunity::create(options(&temp_dir), CsrGraph::new())?;
+        let mut subscription = lsm_community.subscribe_changes(lsm_community.next_change_seq()?)?;
+        assert!(subscription.poll(10, Duration::from_millis(10))?.is_empty());
+
+        // The iterator blocks until a change comes
+        let writer = {
+            let lsm_community = lsm_community.clone();
+            std::thread::spawn(move || -> anyhow::Result<()> {
+                std::thread::sleep(Duration::from_millis(50));
+                lsm_community.insert_vertex()?;
+                lsm_community.insert_vertex()?;
+                lsm_community.insert_edge(0, 1)?;
+                lsm_community.remove_edge(0, 1)?;
+                lsm_community.put_edge_property(0, 1, b"")?;
+                Ok(())
+            })
+        };
+        let change = subscription.next().unwrap()?;
+        assert_eq!(
+            summary(&[change]),
+            vec![(ChangeKind::AddVertex, 0, None, None)]
+        );
+        writer.join().unwrap()?;
+        let changes = subscription.poll(100, Duration::from_secs(1))?;
+        assert_eq!(
+            summary(&changes),
+            vec![
+                (ChangeKind::AddVertex, 1, None, None),
+                (ChangeKind::AddEdge, 0, Some(1), None),
+                (ChangeKind::RemoveEdge, 0, Some(1), None),
+                (ChangeKind::PutEdgeProperty, 0, Some(1), Some("all")),
+            ]
+        );
+
+        // Closing the graph ends the iterator
+        drop(lsm_community);
+        assert!(subscription.next().is_none());
+        assert!(subscription.poll(1, Duration::ZERO).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_change_log_skips_expiries() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut options = options(&temp_dir);
+        options.edge_ttl.labels.insert("session".to_owned(), 1);
+        let lsm_community = LsmCommunity::create(options, CsrGraph::new())?;
+        lsm_community.insert_vertex()?;
+        lsm_community.insert_vertex()?;
+        let from_seq = lsm_community.next_change_seq()?;
+
+        // The edge and its label are logged in one write
+        lsm_community.insert_labeled_edge(0, 1, "session")?;
+        let changes = lsm_community
+            .subscribe_changes(from_seq)?
+            .poll(100, Duration::ZERO)?;
+        assert_eq!(
+            summary(&changes),
+            vec![
+                (ChangeKind::AddEdge, 0, Some(1), None),
+                (
+                    ChangeKind::PutEdgeProperty,
+                    0,
+                    Some(1),
+                    Some(LsmCommunity::EDGE_LABEL_PROPERTY)
+                ),
+            ]
+        );
+        assert_eq!(changes[0].timestamp, changes[1].timestamp);
+
+        // Neither the expiry nor the label removed with it are logged
+        std::thread::sleep(Duration::from_millis(1100));
+        lsm_community.expire_derived_indexes();
+        assert!(
+            lsm_community
+                .external_db
+                .get_edge_property(0, 1, LsmCommunity::EDGE_LABEL_PROPERTY.to_owned())?
+                .is_none()
+        );
+        lsm_community.external_db.delete_delta_log(0)?;
+        assert_eq!(lsm_community.next_change_seq()?, from_seq + 2);
+        Ok(())
+    }
+}
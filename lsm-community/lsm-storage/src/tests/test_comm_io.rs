@@ -19,7 +19,7 @@ mod test_lsm_comm_state {
 
         // Create storage state
         let (state, _, vertex_index) =
-            LsmCommunityStorageState::create_with_graph_file(graph, options);
+            LsmCommunityStorageState::create_with_graph_file(graph, options)?;
         let vc_list = vertex_index.get_virtual_community_list_for_test();
         println!("Virtual Community Count in vertex index: {}", vc_list.len());
         println!("Bucket Count in state: {}", state.buckets.len());
@@ -56,11 +56,17 @@ mod test_lsm_community_inner {
 #[cfg(test)]
 mod test_lsm_comm {
     use crate::{
-        comm_io::LsmCommunity, config::LsmCommunityStorageOptions, delta::DeltaOpType,
-        graph::CsrGraph, types::VId,
+        ReadOnlyError,
+        comm_io::LsmCommunity,
+        config::{IoBackend, LsmCommunityStorageOptions},
+        delta::{DeltaOpType, DeltaOperation},
+        graph::CsrGraph,
+        types::VId,
+        utils::generate_timestamp_micros,
     };
     use rand::{Rng, seq::IndexedRandom};
     use serial_test::serial;
+    use std::time::Duration;
 
     #[test]
     #[serial(lsm_community_example)]
@@ -275,6 +281,226 @@ mod test_lsm_comm {
         Ok(())
     }
 
+    #[test]
+    #[serial(lsm_community_example)]
+    fn test_lsm_comm_read_out_neighbors_batch() -> anyhow::Result<()> {
+        let graph_name = "example";
+
+        // Setup storage options
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = graph_name.to_owned();
+        let lsm_community = LsmCommunity::open(options)?;
+
+        // Batch with every vertex, a duplicate and an invalid vertex
+        let mut vertex_ids = lsm_community.get_all_vertex_id();
+        vertex_ids.push(0);
+        vertex_ids.push(VId::MAX);
+
+        let neighbor_lists = lsm_community.read_out_neighbors_batch(&vertex_ids)?;
+        assert_eq!(neighbor_lists.len(), vertex_ids.len());
+
+        for (vertex_id, neighbors) in vertex_ids.iter().zip(neighbor_lists.iter()) {
+            if *vertex_id == VId::MAX {
+                assert!(neighbors.is_empty());
+                continue;
+            }
+            let expected = lsm_community.read_out_neighbor_clone(*vertex_id)?;
+            assert_eq!(
+                neighbors, &expected,
+                "Neighbor mismatch for vertex {}",
+                vertex_id
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lsm_comm_open_read_only() -> anyhow::Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = "example".to_owned();
+        options.work_space_dir = temp_dir.path().to_string_lossy().into_owned();
+
+        // A missing graph is never created read-only
+        assert!(LsmCommunity::open_read_only(options.clone()).is_err());
+
+        let graph = CsrGraph::from_file("../data/example.graph")?;
+        let primary = LsmCommunity::create(options.clone(), graph)?;
+        let secondary = LsmCommunity::open_read_only(options)?;
+        assert!(secondary.is_read_only());
+        assert!(primary.catch_up().is_err());
+
+        let mut edges = primary.read_all_edges()?;
+        edges.sort_unstable();
+        let mut secondary_edges = secondary.read_all_edges()?;
+        secondary_edges.sort_unstable();
+        assert_eq!(secondary_edges, edges);
+
+        // Mutations fail with a typed error
+        let error = secondary.insert_edge(0, 1).unwrap_err();
+        let read_only_error = error.downcast_ref::<ReadOnlyError>().unwrap();
+        assert_eq!(read_only_error.graph_name, "example");
+        assert!(secondary.remove_edge(edges[0].0, edges[0].1).is_err());
+        assert!(secondary.insert_vertex().is_err());
+
+        // Commits of the primary show after a catch up
+        let (src, dst) = edges[0];
+        primary.remove_edge(src, dst)?;
+        assert!(secondary.read_out_neighbor_clone(src)?.contains(&dst));
+        secondary.catch_up()?;
+        assert_eq!(
+            secondary.read_out_neighbor_clone(src)?,
+            primary.read_out_neighbor_clone(src)?
+        );
+        assert!(!secondary.read_out_neighbor_clone(src)?.contains(&dst));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lsm_comm_edge_ttl() -> anyhow::Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let mut options = LsmCommunityStorageOptions::default();
+        options.graph_name = "example".to_owned();
+        options.work_space_dir = temp_dir.path().to_string_lossy().into_owned();
+        options.edge_ttl.labels.insert("session".to_owned(), 1);
+        options.edge_ttl.labels.insert("follow".to_owned(), 0);
+
+        let graph = CsrGraph::from_file("../data/example.graph")?;
+        let lsm_community = LsmCommunity::create(options.clone(), graph)?;
+        let vertex_ids = lsm_community.get_all_vertex_id();
+        let batch = lsm_community.read_out_neighbors_batch(&vertex_ids)?;
+        let (src, base_neighbors) = vertex_ids
+            .into_iter()
+            .zip(batch)
+            .find(|(_, neighbors)| !neighbors.is_empty())
+            .unwrap();
+        let mut new_neighbors = lsm_community
+            .get_all_vertex_id()
+            .into_iter()
+            .filter(|vid| !base_neighbors.contains(vid));
+        let (session, follow, short) = (
+            new_neighbors.next().unwrap(),
+            new_neighbors.next().unwrap(),
+            new_neighbors.next().unwrap(),
+        );
+
+        lsm_community.insert_labeled_edge(src, session, "session")?;
+        lsm_community.insert_labeled_edge(src, follow, "follow")?;
+        lsm_community.insert_edge_with_ttl(src, short, Duration::from_millis(200))?;
+        assert!(
+            lsm_community
+                .insert_edge_with_ttl(src, short, Duration::from_secs(1 << 24))
+                .is_err()
+        );
+        let neighbors = lsm_community.read_out_neighbor_clone(src)?;
+        assert!(
+            [session, follow, short]
+                .iter()
+                .all(|vid| neighbors.contains(vid))
+        );
+        assert_eq!(
+            lsm_community.get_edge_label(src, session)?.as_deref(),
+            Some("session")
+        );
+
+        // An expired add of an edge of the base graph removes it
+        let base_neighbor = base_neighbors[0];
+        lsm_community.external_db.append_delta(
+            src,
+            DeltaOperation::with_ttl(
+                generate_timestamp_micros() - 10_000_000,
+                DeltaOpType::AddNeighbor,
+                base_neighbor,
+                1,
+            ),
+        )?;
+        assert!(
+            !lsm_community
+                .read_out_neighbor_clone(src)?
+                .contains(&base_neighbor)
+        );
+
+        // Expired edges are gone from every read path, also after a reopen
+        std::thread::sleep(Duration::from_millis(1100));
+        let mut expected = base_neighbors[1..].to_vec();
+        expected.push(follow);
+        expected.sort_unstable();
+        assert_eq!(lsm_community.read_out_neighbor_clone(src)?, expected);
+        assert_eq!(lsm_community.read_out_neighbors_batch(&[src])?[0], expected);
+
+        // The label goes with its edge
+        assert_eq!(lsm_community.get_edge_label(src, session)?, None);
+        assert_eq!(
+            lsm_community.get_edge_label(src, follow)?.as_deref(),
+            Some("follow")
+        );
+        lsm_community.expire_derived_indexes();
+        assert!(
+            lsm_community
+                .external_db
+                .get_edge_property(src, session, LsmCommunity::EDGE_LABEL_PROPERTY.to_owned())?
+                .is_none()
+        );
+
+        // Compaction drops the expired edges outside the base graph, the one hiding an edge
+        // of the base graph stays
+        lsm_community.external_db.compact_delta_logs()?;
+        let mut logged: Vec<VId> = lsm_community
+            .external_db
+            .read_delta_log(src)?
+            .expect("Should keep the delta log")
+            .ops()
+            .iter()
+            .map(|op| op.neighbor)
+            .collect();
+        logged.sort_unstable();
+        let mut kept = vec![base_neighbor, follow];
+        kept.sort_unstable();
+        assert_eq!(logged, kept);
+        assert_eq!(lsm_community.read_out_neighbor_clone(src)?, expected);
+        assert_eq!(lsm_community.get_edge_label(src, session)?, None);
+        drop(lsm_community);
+        let lsm_community = LsmCommunity::open(options)?;
+        assert_eq!(lsm_community.read_out_neighbor_clone(src)?, expected);
+        assert_eq!(lsm_community.get_edge_label(src, session)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lsm_comm_io_backends() -> anyhow::Result<()> {
+        let graph = CsrGraph::from_file("../data/example.graph")?;
+        let mut expected_edges: Option<Vec<(VId, VId)>> = None;
+        for backend in [
+            IoBackend::Uring,
+            IoBackend::Pread,
+            IoBackend::Mmap,
+            IoBackend::Memory,
+        ] {
+            let temp_dir = tempfile::TempDir::new()?;
+            let mut options = LsmCommunityStorageOptions::default();
+            options.graph_name = "example".to_owned();
+            options.work_space_dir = temp_dir.path().to_string_lossy().into_owned();
+            options.io_backend = backend;
+
+            let mut edges =
+                LsmCommunity::create(options.clone(), graph.clone())?.read_all_edges()?;
+            edges.sort_unstable();
+            let expected = expected_edges.get_or_insert_with(|| edges.clone());
+            assert_eq!(&edges, expected, "Edge mismatch with {:?}", backend);
+
+            // The bucket files are found again on recovery
+            let mut recovered_edges = LsmCommunity::open(options)?.read_all_edges()?;
+            recovered_edges.sort_unstable();
+            assert_eq!(
+                &recovered_edges, expected,
+                "Recovery mismatch with {:?}",
+                backend
+            );
+        }
+        Ok(())
+    }
+
     #[test]
     #[serial(lsm_community_example)]
     fn test_lsm_comm_read_in_neighbor_clone() -> anyhow::Result<()> {
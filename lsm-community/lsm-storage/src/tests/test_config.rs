@@ -1,12 +1,20 @@
 #[cfg(test)]
 mod test_config {
-    use crate::config::{ConfigManager, LsmCommunityStorageOptions};
+    use crate::config::{
+        CompressionType, ConfigError, ConfigManager, ExternalStorageOptions, IoBackend,
+        LsmCommunityStorageOptions,
+    };
 
     #[test]
     fn test_load_default_config() {
         let config = LsmCommunityStorageOptions::default();
         assert_eq!(config.block_size, 1024 * 4);
         assert_eq!(config.num_mem_graph_limit, 3);
+        assert_eq!(config.memory_budget, 1 << 30);
+        assert!(!config.memory_rebalance);
+        assert!(!config.incremental_wcc);
+        assert!(!config.reachability_index);
+        assert!(config.external_storage.validate().is_ok());
     }
 
     #[test]
@@ -43,5 +51,160 @@ mod test_config {
 
         assert_eq!(loaded_config.block_size, 4096);
         assert_eq!(loaded_config.graph_name, "example");
+        assert_eq!(loaded_config.memory_budget, 2 << 30);
+        assert!(loaded_config.memory_rebalance);
+        assert!(!loaded_config.incremental_wcc);
+        assert!(!loaded_config.reachability_index);
+        assert_eq!(loaded_config.io_backend, IoBackend::Pread);
+        assert_eq!(loaded_config.edge_ttl.default_ttl_secs, 0);
+        assert_eq!(loaded_config.edge_ttl.ttl_secs(Some("session")), 86400);
+        assert_eq!(loaded_config.edge_ttl.ttl_secs(Some("follow")), 0);
+        assert_eq!(loaded_config.edge_ttl.ttl_secs(None), 0);
+        assert!(loaded_config.edge_history);
+        assert_eq!(loaded_config.edge_history_retention_secs, 2592000);
+        assert!(loaded_config.change_log.enabled);
+        assert_eq!(loaded_config.change_log.retention_secs, 604800);
+        assert_eq!(loaded_config.change_log.max_changes, 0);
+
+        // Unset column family fields keep their per-family defaults
+        let external_options = &loaded_config.external_storage;
+        assert_eq!(external_options.deltas.write_buffer_size, 64 * 1024 * 1024);
+        assert_eq!(external_options.deltas.max_write_buffer_number, 5);
+        assert_eq!(external_options.deltas.level_zero_compaction_trigger, 8);
+        assert_eq!(
+            external_options.giant_vertices.compression,
+            CompressionType::Zstd
+        );
+        assert_eq!(
+            external_options.edge_properties,
+            ExternalStorageOptions::default().edge_properties
+        );
+        assert!(!external_options.wal.sync);
+        assert!(external_options.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_external_storage_options() {
+        let mut options = ExternalStorageOptions::default();
+        assert_eq!(options.deltas.max_write_buffer_number, 5);
+        assert_eq!(options.edge_properties.compression, CompressionType::Lz4);
+        assert_eq!(options.vertex_properties.bloom_filter_bits, 10.0);
+
+        options.deltas.write_buffer_size = 1024;
+        assert!(matches!(
+            options.validate(),
+            Err(ConfigError::InvalidValue(_))
+        ));
+        options.deltas.write_buffer_size = 0;
+
+        options.edge_properties.level_zero_slowdown_trigger = 2;
+        assert!(options.validate().is_err());
+        options.edge_properties.level_zero_slowdown_trigger = 20;
+
+        options.vertex_properties.bloom_filter_bits = f64::NAN;
+        assert!(options.validate().is_err());
+        options.vertex_properties.bloom_filter_bits = 10.0;
+
+        options.wal.disable_wal = true;
+        assert!(options.validate().is_ok());
+        options.wal.sync = true;
+        assert!(options.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_storage_options() {
+        let mut options = LsmCommunityStorageOptions::default();
+        assert!(options.validate().is_ok());
+
+        // A block must hold a vertex of degree giant_vertex_boundary - 1
+        options.giant_vertex_boundary = 2048;
+        let error = options.validate().unwrap_err();
+        assert!(error.to_string().contains("degree 2047"), "{}", error);
+        options.block_size = 0;
+        options.derive_defaults();
+        assert_eq!(options.block_size, 16384);
+        assert!(options.validate().is_ok());
+
+        // Bucket offsets must fit the u32 footer
+        options.min_bucket_size = 5 << 30;
+        assert!(options.validate().is_err());
+        options.min_bucket_size = 0;
+        options.derive_defaults();
+        assert_eq!(options.min_bucket_size, 16384 * 2048);
+        assert!(options.validate().is_ok());
+
+        options.block_size = 5000;
+        assert!(options.validate().is_err());
+        options.block_size = 16384;
+        options.block_cache_capacity = options.memory_budget;
+        options.giant_cache_capacity = 1;
+        assert!(options.validate().is_err());
+        // The pinned caches must leave room for the memtables
+        options.giant_cache_capacity = 0;
+        assert!(options.validate().is_err());
+        options.block_cache_capacity = options.memory_budget - (4 << 20);
+        assert!(options.validate().is_ok());
+        options.block_cache_capacity = 0;
+        options.graph_name = "../other".to_owned();
+        assert!(options.validate().is_err());
+        options.graph_name = String::new();
+
+        // Every time-to-live fits a delta operation
+        options.edge_ttl.default_ttl_secs = 1 << 24;
+        assert!(options.validate().is_err());
+        options.edge_ttl.default_ttl_secs = 3600;
+        options
+            .edge_ttl
+            .labels
+            .insert("session".to_owned(), 1 << 30);
+        let error = options.validate().unwrap_err();
+        assert!(
+            error.to_string().contains("edge_ttl.labels.session"),
+            "{}",
+            error
+        );
+        options.edge_ttl.labels.insert("session".to_owned(), 60);
+        assert!(options.validate().is_ok());
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let variables = [
+            ("LSM_COMMUNITY_BLOCK_SIZE", "8192"),
+            ("LSM_COMMUNITY_GRAPH_NAME", "from_env"),
+            ("LSM_COMMUNITY_MEMORY_REBALANCE", "true"),
+            ("LSM_COMMUNITY_WAL_SYNC", " true "),
+            ("LSM_COMMUNITY_IO_BACKEND", "MMAP"),
+            ("LSM_COMMUNITY_EDGE_TTL_SECS", "600"),
+            ("LSM_COMMUNITY_CHANGE_LOG_RETENTION_SECS", "3600"),
+            ("BLOCK_SIZE", "1024"),
+        ];
+        let lookup = |name: &str| {
+            variables
+                .iter()
+                .find(|(variable, _)| *variable == name)
+                .map(|(_, value)| value.to_string())
+        };
+        let mut options: LsmCommunityStorageOptions =
+            ConfigManager::load_from_yaml("../config/example.yaml").unwrap();
+        options.apply_overrides(lookup).unwrap();
+        assert_eq!(options.block_size, 8192);
+        assert_eq!(options.graph_name, "from_env");
+        assert!(options.memory_rebalance);
+        assert!(options.external_storage.wal.sync);
+        assert_eq!(options.io_backend, IoBackend::Mmap);
+        assert_eq!(options.edge_ttl.default_ttl_secs, 600);
+        assert_eq!(options.edge_ttl.ttl_secs(Some("session")), 86400);
+        assert_eq!(options.change_log.retention_secs, 3600);
+        assert!(options.change_log.enabled);
+        // Values without a variable keep the YAML
+        assert_eq!(options.num_mem_graph_limit, 5);
+        assert_eq!(options.giant_vertex_boundary, 256);
+
+        let invalid =
+            |name: &str| (name == "LSM_COMMUNITY_MEMORY_BUDGET").then(|| "1GB".to_owned());
+        let error = options.apply_overrides(invalid).unwrap_err();
+        assert!(matches!(error, ConfigError::InvalidValue(_)));
+        assert!(error.to_string().contains("LSM_COMMUNITY_MEMORY_BUDGET"));
     }
 }
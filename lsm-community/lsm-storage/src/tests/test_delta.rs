@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test_delta {
-    use crate::delta::{DeltaLog, DeltaOpType, DeltaOperation};
+    use crate::delta::{DeltaLog, DeltaOpType, DeltaOperation, EdgeInterval};
+    use crate::types::VId;
+    use crate::utils::generate_timestamp_micros;
 
     #[test]
     fn test_delta_log_encode_decode() {
@@ -161,4 +163,215 @@ mod test_delta {
             "DeltaOperation should be 16 bytes for alignment"
         );
     }
+
+    #[test]
+    fn test_delta_operation_ttl() {
+        let op = DeltaOperation::with_ttl(1_000_000, DeltaOpType::AddNeighbor, 7, 60);
+        assert_eq!(op.get_op_type(), Some(DeltaOpType::AddNeighbor));
+        assert_eq!(op.ttl_secs(), 60);
+        assert_eq!(op.expires_at(), Some(61_000_000));
+        assert!(!op.is_expired(60_999_999));
+        assert!(op.is_expired(61_000_000));
+
+        // The time-to-live survives the encoding
+        let decoded = DeltaOperation::decode(&op.encode()).unwrap();
+        assert_eq!(decoded, op);
+
+        // No time-to-live never expires, too long ones are cut
+        let op = DeltaOperation::new(100, DeltaOpType::RemoveNeighbor, 7);
+        assert_eq!(op.ttl_secs(), 0);
+        assert!(!op.is_expired(u64::MAX));
+        let op = DeltaOperation::with_ttl(100, DeltaOpType::AddNeighbor, 7, u64::MAX);
+        assert_eq!(op.ttl_secs(), DeltaOperation::MAX_TTL_SECS);
+        assert_eq!(op.get_op_type(), Some(DeltaOpType::AddNeighbor));
+    }
+
+    #[test]
+    fn test_merge_expires_adds_into_removes() {
+        let now = generate_timestamp_micros();
+        let hour = 3600 * 1_000_000;
+        let base_log = DeltaLog::from_ops(vec![
+            DeltaOperation::new(now - 3 * hour, DeltaOpType::AddNeighbor, 1),
+            DeltaOperation::with_ttl(now - 2 * hour, DeltaOpType::AddNeighbor, 2, 3600),
+            DeltaOperation::with_ttl(now - hour, DeltaOpType::AddNeighbor, 3, 7200),
+        ]);
+        // The expired add of neighbor 1 overrides its older add
+        let operand = DeltaOperation::with_ttl(now - 2 * hour, DeltaOpType::AddNeighbor, 1, 60);
+
+        let merged = DeltaLog::merge_for_rocksdb(
+            Some(&base_log.encode()),
+            &[&DeltaOperation::encode_batch(&[operand])],
+        )
+        .unwrap();
+        let merged = DeltaLog::decode(&merged).unwrap();
+        let mut state: Vec<(VId, Option<DeltaOpType>)> = merged
+            .ops()
+            .iter()
+            .map(|op| (op.neighbor, op.get_op_type()))
+            .collect();
+        state.sort_unstable_by_key(|(neighbor, _)| *neighbor);
+        assert_eq!(
+            state,
+            vec![
+                (1, Some(DeltaOpType::RemoveNeighbor)),
+                (2, Some(DeltaOpType::RemoveNeighbor)),
+                (3, Some(DeltaOpType::AddNeighbor)),
+            ]
+        );
+        // The tombstone keeps the timestamp and time-to-live of the add
+        let tombstone = merged.ops().iter().find(|op| op.neighbor == 1).unwrap();
+        assert_eq!(*tombstone, operand.to_tombstone());
+        assert_eq!(tombstone.timestamp, operand.timestamp);
+        assert_eq!(tombstone.ttl_secs(), 60);
+
+        let mut log = base_log.clone();
+        assert_eq!(log.expire(now), 1);
+        assert_eq!(log.expire(now), 0);
+        assert_eq!(log.len(), 3);
+        assert_eq!(
+            log.ops()[1].get_op_type(),
+            Some(DeltaOpType::RemoveNeighbor)
+        );
+
+        // Compaction keeps an expired add that may hide an edge of the base graph
+        let expired_log = DeltaLog::from_ops(vec![operand]);
+        assert!(!DeltaLog::is_expired_for_rocksdb(&base_log.encode()));
+        assert!(!DeltaLog::is_expired_for_rocksdb(&expired_log.encode()));
+        assert!(DeltaLog::is_expired_for_rocksdb(&DeltaLog::new().encode()));
+        assert!(!DeltaLog::is_expired_for_rocksdb(&[1, 2]));
+    }
+
+    #[test]
+    fn test_merge_drops_operations_outside_base() {
+        let now = generate_timestamp_micros();
+        let hour = 3600 * 1_000_000;
+        let outside_add = DeltaOperation::with_ttl(now - 2 * hour, DeltaOpType::AddNeighbor, 1, 60)
+            .with_outside_base(true);
+        let outside_remove =
+            DeltaOperation::new(now - hour, DeltaOpType::RemoveNeighbor, 2).with_outside_base(true);
+        let base_remove = DeltaOperation::new(now - hour, DeltaOpType::RemoveNeighbor, 3);
+        assert!(outside_add.is_outside_base());
+        assert!(outside_add.to_tombstone().is_outside_base());
+        assert!(!base_remove.is_outside_base());
+        assert_eq!(
+            outside_add.with_outside_base(false).op_type,
+            DeltaOperation::with_ttl(now - 2 * hour, DeltaOpType::AddNeighbor, 1, 60).op_type
+        );
+        let decoded = DeltaOperation::decode(&outside_remove.encode()).unwrap();
+        assert_eq!(decoded, outside_remove);
+        assert_eq!(decoded.get_op_type(), Some(DeltaOpType::RemoveNeighbor));
+
+        // The remove of neighbor 2 overrides its add before it is dropped, the remove of
+        // neighbor 3 may hide an edge of the base graph
+        let base_log = DeltaLog::from_ops(vec![
+            DeltaOperation::new(now - 3 * hour, DeltaOpType::AddNeighbor, 2)
+                .with_outside_base(true),
+            base_remove,
+        ]);
+        let merged = DeltaLog::merge_for_rocksdb(
+            Some(&base_log.encode()),
+            &[&DeltaOperation::encode_batch(&[
+                outside_add,
+                outside_remove,
+            ])],
+        )
+        .unwrap();
+        let merged = DeltaLog::decode(&merged).unwrap();
+        assert_eq!(merged.ops(), &[base_remove]);
+        assert!(!DeltaLog::is_expired_for_rocksdb(&merged.encode()));
+
+        // A log with nothing left to hide is dropped by compaction
+        let expired_log = DeltaLog::from_ops(vec![outside_add, outside_remove]);
+        assert!(DeltaLog::is_expired_for_rocksdb(&expired_log.encode()));
+        let mut log = expired_log.clone();
+        assert_eq!(log.expire(now), 1);
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn test_edge_intervals_from_history() {
+        let interval = |neighbor, valid_from, valid_to| EdgeInterval {
+            neighbor,
+            valid_from,
+            valid_to,
+        };
+        let ops = vec![
+            DeltaOperation::new(30, DeltaOpType::AddNeighbor, 1),
+            DeltaOperation::new(10, DeltaOpType::AddNeighbor, 1),
+            DeltaOperation::new(20, DeltaOpType::RemoveNeighbor, 1),
+            DeltaOperation::new(40, DeltaOpType::RemoveNeighbor, 2),
+            DeltaOperation::new(50, DeltaOpType::AddNeighbor, 2),
+            // Expires at 2_000_060, back to absent
+            DeltaOperation::with_ttl(60, DeltaOpType::AddNeighbor, 3, 2),
+        ];
+        // Neighbor 2 is in the base graph, 4 has no operation
+        let intervals = EdgeInterval::from_history([2, 4], ops);
+        assert_eq!(
+            intervals,
+            vec![
+                interval(1, 10, 20),
+                interval(1, 30, EdgeInterval::OPEN_END),
+                interval(2, 0, 40),
+                interval(2, 50, EdgeInterval::OPEN_END),
+                interval(3, 60, 2_000_060),
+                interval(4, 0, EdgeInterval::OPEN_END),
+            ]
+        );
+        assert!(intervals[0].contains(10) && !intervals[0].contains(20));
+        assert!(intervals[0].overlaps(0, 11) && !intervals[0].overlaps(20, 30));
+
+        // An expired add of a base edge removes it, as does the tombstone it turns into
+        let ops = vec![DeltaOperation::new(5, DeltaOpType::RemoveNeighbor, 2)];
+        assert_eq!(
+            EdgeInterval::from_history([2], ops),
+            vec![interval(2, 0, 5)]
+        );
+        let add = DeltaOperation::with_ttl(5, DeltaOpType::AddNeighbor, 2, 1);
+        for op in [add, add.to_tombstone()] {
+            assert_eq!(
+                EdgeInterval::from_history([2], vec![op]),
+                vec![interval(2, 0, 1_000_005)]
+            );
+        }
+    }
+
+    #[test]
+    fn test_append_for_rocksdb_keeps_every_operation() {
+        let first = DeltaOperation::new(10, DeltaOpType::AddNeighbor, 1);
+        let second = DeltaOperation::new(20, DeltaOpType::RemoveNeighbor, 1);
+        let appended = DeltaLog::append_for_rocksdb(
+            Some(&DeltaOperation::encode_batch(&[first])),
+            &[&DeltaOperation::encode_batch(&[second])],
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            DeltaOperation::decode_batch(&appended).unwrap(),
+            vec![first, second]
+        );
+        assert!(!DeltaLog::is_history_expired_for_rocksdb(&appended, 0));
+    }
+
+    #[test]
+    fn test_append_for_rocksdb_drops_operations_past_retention() {
+        let now = generate_timestamp_micros();
+        let old = DeltaOperation::new(now - 20_000_000, DeltaOpType::AddNeighbor, 1);
+        let recent = DeltaOperation::new(now, DeltaOpType::RemoveNeighbor, 1);
+        let appended = DeltaLog::append_for_rocksdb(
+            Some(&DeltaOperation::encode_batch(&[old])),
+            &[&DeltaOperation::encode_batch(&[recent])],
+            10,
+        )
+        .unwrap();
+        assert_eq!(
+            DeltaOperation::decode_batch(&appended).unwrap(),
+            vec![recent]
+        );
+
+        // Only a history with nothing retained is dropped by compaction
+        assert!(!DeltaLog::is_history_expired_for_rocksdb(&appended, 10));
+        let old_only = DeltaOperation::encode_batch(&[old]);
+        assert!(DeltaLog::is_history_expired_for_rocksdb(&old_only, 10));
+        assert!(!DeltaLog::is_history_expired_for_rocksdb(&old_only, 0));
+    }
 }
@@ -1,10 +1,11 @@
 #[cfg(test)]
 mod tests {
     use crate::{
-        config::LsmCommunityStorageOptions,
-        delta::{DeltaOpType, DeltaOperation},
+        config::{CompressionType, LsmCommunityStorageOptions},
+        delta::{DeltaLog, DeltaOpType, DeltaOperation},
         external::ExternalStorage,
         types::VId,
+        utils::generate_timestamp_micros,
     };
     use rand::RngCore;
 
@@ -129,6 +130,143 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_delta_log_expiry() -> anyhow::Result<()> {
+        let (storage, _temp_dir) = create_test_storage()?;
+        let now = generate_timestamp_micros();
+        let hour = 3600 * 1_000_000;
+
+        storage.append_delta(
+            1,
+            DeltaOperation::new(now - hour, DeltaOpType::AddNeighbor, 10),
+        )?;
+        storage.append_delta(
+            1,
+            DeltaOperation::with_ttl(now - hour, DeltaOpType::AddNeighbor, 20, 60),
+        )?;
+        storage.append_delta(
+            1,
+            DeltaOperation::with_ttl(now, DeltaOpType::AddNeighbor, 30, 3600),
+        )?;
+        storage.append_delta(
+            2,
+            DeltaOperation::with_ttl(now - hour, DeltaOpType::AddNeighbor, 10, 1),
+        )?;
+
+        // Expired adds are read back as removes, before and after compaction
+        let adds = |log: DeltaLog| -> Vec<VId> {
+            let mut neighbors: Vec<VId> = log
+                .ops()
+                .iter()
+                .filter(|op| op.get_op_type() == Some(DeltaOpType::AddNeighbor))
+                .map(|op| op.neighbor)
+                .collect();
+            neighbors.sort_unstable();
+            neighbors
+        };
+        for _ in 0..2 {
+            let log = storage.read_delta_log(1)?.expect("Should have delta log");
+            assert_eq!(log.len(), 3);
+            assert_eq!(adds(log), vec![10, 30]);
+            let log = storage
+                .read_delta_log(2)?
+                .expect("Should keep the tombstone");
+            assert_eq!(
+                log.ops()[0].get_op_type(),
+                Some(DeltaOpType::RemoveNeighbor)
+            );
+            storage.compact_delta_logs()?;
+        }
+
+        // Expiries still to come are listed with their edge
+        let expiries = storage.delta_expiries(now)?;
+        assert_eq!(expiries, vec![(now + hour, 1, 30)]);
+        assert_eq!(storage.delta_expiries(0)?.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_delta_log_compaction_drops_operations_outside_base() -> anyhow::Result<()> {
+        let (storage, _temp_dir) = create_test_storage()?;
+        let now = generate_timestamp_micros();
+        let hour = 3600 * 1_000_000;
+
+        // An expired add and a remove of edges outside the base graph hide nothing
+        storage.append_delta(
+            1,
+            DeltaOperation::with_ttl(now - hour, DeltaOpType::AddNeighbor, 10, 60)
+                .with_outside_base(true),
+        )?;
+        storage.append_delta(
+            1,
+            DeltaOperation::new(now - hour, DeltaOpType::AddNeighbor, 20).with_outside_base(true),
+        )?;
+        storage.append_delta(
+            1,
+            DeltaOperation::new(now, DeltaOpType::RemoveNeighbor, 20).with_outside_base(true),
+        )?;
+        // The expired add of an edge of the base graph stays as a remove
+        storage.append_delta(
+            2,
+            DeltaOperation::with_ttl(now - hour, DeltaOpType::AddNeighbor, 10, 60),
+        )?;
+        assert!(
+            storage
+                .read_delta_log(1)?
+                .expect("Not compacted yet")
+                .is_empty()
+        );
+
+        storage.compact_delta_logs()?;
+        assert!(storage.read_delta_log(1)?.is_none());
+        let log = storage
+            .read_delta_log(2)?
+            .expect("Should keep the tombstone");
+        assert_eq!(
+            log.ops()[0].get_op_type(),
+            Some(DeltaOpType::RemoveNeighbor)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_append_delta_with_edge_property() -> anyhow::Result<()> {
+        let (storage, _temp_dir) = create_test_storage()?;
+        let op = DeltaOperation::new(100, DeltaOpType::AddNeighbor, 2);
+        storage.append_delta_with_edge_property(1, op, "label".to_owned(), b"follow")?;
+
+        let log = storage.read_delta_log(1)?.expect("Should have delta log");
+        assert_eq!(log.ops(), &[op]);
+        assert_eq!(
+            storage.get_edge_property(1, 2, "label".to_owned())?,
+            Some(b"follow".to_vec())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_edge_history_retention() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut options = LsmCommunityStorageOptions::default();
+        options.work_space_dir = temp_dir.path().to_str().unwrap().to_string();
+        options.graph_name = "hello".to_string();
+        options.edge_history = true;
+        options.edge_history_retention_secs = 60;
+        let storage = ExternalStorage::new(options)?;
+        let now = generate_timestamp_micros();
+        let hour = 3600 * 1_000_000;
+
+        let old_add = DeltaOperation::new(now - hour, DeltaOpType::AddNeighbor, 10);
+        let old_remove = DeltaOperation::new(now - hour + 1, DeltaOpType::RemoveNeighbor, 10);
+        let recent_add = DeltaOperation::new(now, DeltaOpType::AddNeighbor, 20);
+        storage.append_deltas_batch(1, &[old_add, old_remove])?;
+        storage.append_delta(1, recent_add)?;
+
+        // Operations past the retention are gone, the latest one of each edge is not
+        assert_eq!(storage.read_edge_history(1)?, vec![old_remove, recent_add]);
+        Ok(())
+    }
+
     fn create_test_storage() -> anyhow::Result<(ExternalStorage, TempDir)> {
         let temp_dir = TempDir::new()?;
         let mut options = LsmCommunityStorageOptions::default();
@@ -307,4 +445,49 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_tuned_column_families() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut options = LsmCommunityStorageOptions::default();
+        options.work_space_dir = temp_dir.path().to_str().unwrap().to_string();
+        options.graph_name = "tuned".to_string();
+        let external_options = &mut options.external_storage;
+        external_options.deltas.write_buffer_size = 4 * 1024 * 1024;
+        external_options.deltas.compression = CompressionType::Zstd;
+        external_options.vertex_properties.block_cache_size = 1024 * 1024;
+        external_options.edge_properties.bloom_filter_bits = 0.0;
+        external_options.wal.sync = true;
+
+        {
+            let storage = ExternalStorage::new(options.clone())?;
+            storage.append_delta(1, DeltaOperation::new(100, DeltaOpType::AddNeighbor, 2))?;
+            storage.put_vertex_property(1, "age".to_string(), b"30")?;
+            storage.put_edge_property(1, 2, "weight".to_string(), b"0.5")?;
+        }
+
+        // Synced writes survive a reopen
+        let storage = ExternalStorage::new(options.clone())?;
+        assert!(storage.read_delta_log(1)?.is_some());
+        assert_eq!(
+            storage.get_vertex_property(1, "age".to_string())?,
+            Some(b"30".to_vec())
+        );
+        assert_eq!(
+            storage.get_edge_property(1, 2, "weight".to_string())?,
+            Some(b"0.5".to_vec())
+        );
+        drop(storage);
+
+        // Invalid options are rejected at open time
+        options.external_storage.wal.disable_wal = true;
+        assert!(ExternalStorage::new(options.clone()).is_err());
+        options.external_storage.wal.disable_wal = false;
+        options
+            .external_storage
+            .giant_vertices
+            .max_write_buffer_number = 0;
+        assert!(ExternalStorage::new(options).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,277 @@
+#[cfg(test)]
+mod test_fault_injection {
+    use crate::bucket::disk_manager::BktDiskManager;
+    use crate::bucket::io::{FaultInjector, FaultPoint};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_failure_points() {
+        let temp_dir = TempDir::new().unwrap();
+        let injector = FaultInjector::attach(temp_dir.path());
+        let path = temp_dir.path().join("failure.bkt");
+        let manager = BktDiskManager::create(&path, &[1u8; 64]).unwrap();
+        assert!(!manager.uses_direct_io());
+
+        // The second read fails, the others go through
+        injector.fail(FaultPoint::Read, 1, 1);
+        assert!(manager.read(0, 8).is_ok());
+        assert!(manager.read(0, 8).is_err());
+        assert_eq!(manager.read(0, 8).unwrap(), [1u8; 8]);
+
+        injector.fail(FaultPoint::Open, 0, 1);
+        assert!(BktDiskManager::new(&path).is_err());
+        assert!(BktDiskManager::new(&path).is_ok());
+        FaultInjector::detach(temp_dir.path());
+    }
+
+    #[test]
+    fn test_short_write_and_bit_flip() {
+        let temp_dir = TempDir::new().unwrap();
+        let injector = FaultInjector::attach(temp_dir.path());
+        let path = temp_dir.path().join("torn.bkt");
+        let manager = BktDiskManager::create(&path, &[0u8; 16]).unwrap();
+
+        // Only the first 3 bytes of the torn write persist
+        injector.short_write(0, 3);
+        assert!(manager.write(4, &[9u8; 8]).is_err());
+        assert_eq!(
+            manager.read(0, 12).unwrap(),
+            [0, 0, 0, 0, 9, 9, 9, 0, 0, 0, 0, 0]
+        );
+
+        // Reads see the flipped bit, the file does not
+        injector.flip_bits(&path, 1, 0b100);
+        assert_eq!(manager.read(0, 2).unwrap(), [0, 4]);
+        assert_eq!(manager.read(2, 2).unwrap(), [0, 0]);
+        assert_eq!(std::fs::read(&path).unwrap()[1], 0);
+        FaultInjector::detach(temp_dir.path());
+    }
+
+    #[test]
+    fn test_crash_after_bytes() {
+        let temp_dir = TempDir::new().unwrap();
+        let injector = FaultInjector::attach(temp_dir.path());
+        let path = temp_dir.path().join("crash.bkt");
+
+        // The file is cut at the crash and nothing works until the restart
+        injector.crash_after_bytes(100);
+        assert!(BktDiskManager::create(&path, &[7u8; 1000]).is_err());
+        assert!(injector.is_crashed());
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 100);
+        assert!(BktDiskManager::new(&path).is_err());
+
+        injector.reset();
+        let manager = BktDiskManager::new(&path).unwrap();
+        assert_eq!(manager.size(), 100);
+        assert_eq!(injector.bytes_written(), 100);
+        FaultInjector::detach(temp_dir.path());
+    }
+}
+
+#[cfg(test)]
+mod test_crash_recovery {
+    use crate::{
+        bucket::io::{FaultInjector, FaultPoint},
+        comm_io::LsmCommunity,
+        config::LsmCommunityStorageOptions,
+        graph::CsrGraph,
+        types::VId,
+    };
+    use tempfile::TempDir;
+
+    fn options(temp_dir: &TempDir) -> LsmCommunityStorageOptions {
+        LsmCommunityStorageOptions {
+            graph_name: "example".to_owned(),
+            work_space_dir: temp_dir.path().to_string_lossy().into_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn graph_edges(graph: &CsrGraph) -> Vec<(VId, VId)> {
+        let mut edges: Vec<(VId, VId)> = (0..graph.num_vertices() as VId)
+            .flat_map(|src| graph.get_neighbor_iter(src).map(move |dst| (src, dst)))
+            .collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// The invariants of a recovered graph: it holds exactly the `expected` edges, read one
+    /// vertex at a time and in a batch.
+    fn check_invariants(lsm_community: &LsmCommunity, expected: &[(VId, VId)]) {
+        let mut edges = lsm_community.read_all_edges().unwrap();
+        edges.sort_unstable();
+        assert_eq!(edges, expected);
+
+        let vertex_ids = lsm_community.get_all_vertex_id();
+        let batch = lsm_community.read_out_neighbors_batch(&vertex_ids).unwrap();
+        for (vertex_id, neighbors) in vertex_ids.iter().zip(batch) {
+            let mut neighbors = neighbors;
+            neighbors.sort_unstable();
+            let expected_neighbors: Vec<VId> = expected
+                .iter()
+                .filter(|(src, _)| src == vertex_id)
+                .map(|(_, dst)| *dst)
+                .collect();
+            assert_eq!(neighbors, expected_neighbors, "Vertex {}", vertex_id);
+        }
+    }
+
+    #[test]
+    fn test_crash_while_building_buckets() {
+        let graph = CsrGraph::from_file("../data/example.graph").unwrap();
+        let expected = graph_edges(&graph);
+
+        // Bytes written by a build without faults
+        let clean_dir = TempDir::new().unwrap();
+        let injector = FaultInjector::attach(clean_dir.path());
+        LsmCommunity::create(options(&clean_dir), graph.clone()).unwrap();
+        let total_bytes = injector.bytes_written();
+        FaultInjector::detach(clean_dir.path());
+        assert!(total_bytes > 0);
+
+        for crash_point in [0, 1, 11, total_bytes / 2, total_bytes - 1] {
+            let temp_dir = TempDir::new().unwrap();
+            let injector = FaultInjector::attach(temp_dir.path());
+            injector.crash_after_bytes(crash_point);
+            assert!(
+                LsmCommunity::create(options(&temp_dir), graph.clone()).is_err(),
+                "Build survived a crash after {} bytes",
+                crash_point
+            );
+            assert!(injector.is_crashed());
+
+            // Restart, the torn buckets are rebuilt
+            injector.reset();
+            let lsm_community = LsmCommunity::create(options(&temp_dir), graph.clone()).unwrap();
+            check_invariants(&lsm_community, &expected);
+            drop(lsm_community);
+
+            // And the rebuilt graph recovers
+            let lsm_community = LsmCommunity::open(options(&temp_dir)).unwrap();
+            check_invariants(&lsm_community, &expected);
+            FaultInjector::detach(temp_dir.path());
+        }
+    }
+
+    #[test]
+    fn test_crash_while_saving_vertex_index() {
+        let graph = CsrGraph::from_file("../data/example.graph").unwrap();
+        let expected = graph_edges(&graph);
+        let temp_dir = TempDir::new().unwrap();
+        let lsm_community = LsmCommunity::create(options(&temp_dir), graph).unwrap();
+        let vertex_count = lsm_community.vertex_count();
+        drop(lsm_community);
+
+        for crash_point in [0, 1, 64] {
+            let lsm_community = LsmCommunity::open(options(&temp_dir)).unwrap();
+            let injector = FaultInjector::attach(temp_dir.path());
+            injector.crash_after_bytes(crash_point);
+            assert!(lsm_community.insert_vertex().is_err());
+            assert!(injector.is_crashed());
+            drop(lsm_community);
+            FaultInjector::detach(temp_dir.path());
+
+            // The torn index never replaced the saved one
+            let lsm_community = LsmCommunity::open(options(&temp_dir)).unwrap();
+            assert_eq!(lsm_community.vertex_count(), vertex_count);
+            check_invariants(&lsm_community, &expected);
+        }
+
+        // A save after the crashes goes through
+        let lsm_community = LsmCommunity::open(options(&temp_dir)).unwrap();
+        lsm_community.insert_vertex().unwrap();
+        drop(lsm_community);
+        let lsm_community = LsmCommunity::open(options(&temp_dir)).unwrap();
+        assert_eq!(lsm_community.vertex_count(), vertex_count + 1);
+    }
+
+    #[test]
+    fn test_torn_bucket_is_rejected() {
+        let graph = CsrGraph::from_file("../data/example.graph").unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        drop(LsmCommunity::create(options(&temp_dir), graph).unwrap());
+
+        // Cut a bucket file behind the back of the graph, the open fails without a panic
+        let graph_dir = temp_dir.path().join("example");
+        let bucket_path = std::fs::read_dir(&graph_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.to_string_lossy().ends_with(".bkt"))
+            .unwrap();
+        for len in [0, 7, 100] {
+            let file = std::fs::OpenOptions::new()
+                .write(true)
+                .open(&bucket_path)
+                .unwrap();
+            file.set_len(len).unwrap();
+            drop(file);
+            assert!(LsmCommunity::open(options(&temp_dir)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_read_faults_and_bit_flips() {
+        let graph = CsrGraph::from_file("../data/example.graph").unwrap();
+        let expected = graph_edges(&graph);
+        let temp_dir = TempDir::new().unwrap();
+        let injector = FaultInjector::attach(temp_dir.path());
+        drop(LsmCommunity::create(options(&temp_dir), graph).unwrap());
+
+        // A failed open leaves nothing behind
+        injector.fail(FaultPoint::Open, 0, 1);
+        assert!(LsmCommunity::open(options(&temp_dir)).is_err());
+
+        // Failed reads are errors and are not cached
+        let lsm_community = LsmCommunity::open(options(&temp_dir)).unwrap();
+        injector.fail(FaultPoint::Read, 0, u64::MAX);
+        let vertex_ids = lsm_community.get_all_vertex_id();
+        assert!(lsm_community.read_out_neighbors_batch(&vertex_ids).is_err());
+        injector.reset();
+        check_invariants(&lsm_community, &expected);
+        drop(lsm_community);
+
+        // A flipped bit in the footer of a bucket fails its checks on open
+        let bucket_path = std::fs::read_dir(temp_dir.path().join("example"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.to_string_lossy().ends_with(".bkt"))
+            .unwrap();
+        let len = std::fs::metadata(&bucket_path).unwrap().len();
+        for offset in [len - 1, len - 5, len - 13] {
+            injector.reset();
+            injector.flip_bits(&bucket_path, offset, 0x80);
+            assert!(
+                LsmCommunity::open(options(&temp_dir)).is_err(),
+                "Bit flip at {} of {} not detected",
+                offset,
+                len
+            );
+        }
+        injector.reset();
+        check_invariants(&LsmCommunity::open(options(&temp_dir)).unwrap(), &expected);
+        FaultInjector::detach(temp_dir.path());
+    }
+
+    #[test]
+    fn test_recover_mutations_after_crash() {
+        let graph = CsrGraph::from_file("../data/example.graph").unwrap();
+        let mut expected = graph_edges(&graph);
+        let temp_dir = TempDir::new().unwrap();
+        let lsm_community = LsmCommunity::create(options(&temp_dir), graph).unwrap();
+
+        // Mutations, then the process goes away without any shutdown step
+        let (src, dst) = expected[0];
+        lsm_community.remove_edge(src, dst).unwrap();
+        expected.remove(0);
+        let vertex_ids = lsm_community.get_all_vertex_id();
+        let (src, dst) = (vertex_ids[0], vertex_ids[vertex_ids.len() - 1]);
+        if !expected.contains(&(src, dst)) {
+            lsm_community.insert_edge(src, dst).unwrap();
+            expected.push((src, dst));
+            expected.sort_unstable();
+        }
+        drop(lsm_community);
+
+        check_invariants(&LsmCommunity::open(options(&temp_dir)).unwrap(), &expected);
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod test_merged_neighbor_iterator {
+    use std::sync::{Arc, RwLock};
+
+    use crate::{
+        block::builder::BlockBuilder,
+        delta::{DeltaLog, DeltaOpType, DeltaOperation},
+        iterator::{GlobalNeighborIterator, MergedNeighborIterator},
+        types::VId,
+    };
+
+    fn merge(base: Vec<VId>, ops: Vec<DeltaOperation>) -> Vec<VId> {
+        let delta = (!ops.is_empty()).then(|| DeltaLog::from_ops(ops));
+        MergedNeighborIterator::new(base.into_iter(), delta).collect()
+    }
+
+    #[test]
+    fn test_merged_without_delta() {
+        assert_eq!(merge(vec![1, 3, 5], vec![]), vec![1, 3, 5]);
+        assert_eq!(merge(vec![], vec![]), Vec::<VId>::new());
+    }
+
+    #[test]
+    fn test_merged_add_and_remove() {
+        let ops = vec![
+            DeltaOperation::new(1, DeltaOpType::AddNeighbor, 4),
+            DeltaOperation::new(2, DeltaOpType::RemoveNeighbor, 3),
+            DeltaOperation::new(3, DeltaOpType::AddNeighbor, 0),
+            DeltaOperation::new(4, DeltaOpType::AddNeighbor, 9),
+        ];
+        assert_eq!(merge(vec![1, 3, 5], ops), vec![0, 1, 4, 5, 9]);
+    }
+
+    #[test]
+    fn test_merged_last_write_wins() {
+        // Operations on the same neighbor, out of timestamp order in the log
+        let ops = vec![
+            DeltaOperation::new(30, DeltaOpType::AddNeighbor, 4),
+            DeltaOperation::new(10, DeltaOpType::AddNeighbor, 2),
+            DeltaOperation::new(20, DeltaOpType::RemoveNeighbor, 4),
+            DeltaOperation::new(40, DeltaOpType::RemoveNeighbor, 2),
+        ];
+        assert_eq!(merge(vec![1, 2], ops), vec![1, 4]);
+    }
+
+    #[test]
+    fn test_merged_collapses_duplicates() {
+        // Duplicate base neighbors are yielded once, or hidden entirely when removed
+        assert_eq!(merge(vec![1, 1, 2, 2, 2, 3], vec![]), vec![1, 2, 3]);
+
+        let ops = vec![
+            DeltaOperation::new(1, DeltaOpType::RemoveNeighbor, 2),
+            DeltaOperation::new(2, DeltaOpType::AddNeighbor, 3),
+        ];
+        assert_eq!(merge(vec![1, 2, 2, 3, 3], ops), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_merged_ignores_unknown_operations() {
+        let ops = vec![
+            DeltaOperation::new(1, DeltaOpType::AddNeighbor, 7),
+            DeltaOperation {
+                timestamp: 2,
+                neighbor: 7,
+                op_type: 9,
+            },
+            DeltaOperation {
+                timestamp: 3,
+                neighbor: 8,
+                op_type: 9,
+            },
+        ];
+        assert_eq!(merge(vec![8], ops), vec![7, 8]);
+    }
+
+    #[test]
+    fn test_global_iterator_merges_mem_neighbors() {
+        let mem_neighbors = Arc::new(RwLock::new(Arc::new(vec![2, 4, 6])));
+        let iter = GlobalNeighborIterator::new(Some(mem_neighbors.clone()), None, None);
+        // The iterator shares the list instead of copying it
+        assert_eq!(Arc::strong_count(&mem_neighbors.read().unwrap()), 2);
+        assert_eq!(iter.collect::<Vec<_>>(), vec![2, 4, 6]);
+        assert_eq!(Arc::strong_count(&mem_neighbors.read().unwrap()), 1);
+
+        let neighbors: Vec<VId> = GlobalNeighborIterator::new(None, None, None).collect();
+        assert!(neighbors.is_empty());
+    }
+
+    #[test]
+    fn test_global_iterator_sorts_legacy_block_list() {
+        let mut builder = BlockBuilder::new(1024);
+        assert!(builder.add_vertex(5, &[9, 3, 7]));
+        assert!(builder.add_vertex(6, &[1, 2]));
+        let (block, offsets) = builder.build();
+        let block = Arc::new(block);
+
+        // A block list written unsorted is merged in ascending order
+        let mem_neighbors = Arc::new(RwLock::new(Arc::new(vec![4, 8])));
+        let neighbors: Vec<VId> = GlobalNeighborIterator::new(
+            Some(mem_neighbors.clone()),
+            Some(block.clone()),
+            Some(offsets[&5] as usize),
+        )
+        .collect();
+        assert_eq!(neighbors, vec![3, 4, 7, 8, 9]);
+
+        // The mem graph list is a snapshot of the creation
+        let mut iter = GlobalNeighborIterator::new(
+            Some(mem_neighbors.clone()),
+            Some(block),
+            Some(offsets[&6] as usize),
+        );
+        assert_eq!(iter.next(), Some(1));
+        Arc::make_mut(&mut mem_neighbors.write().unwrap()).insert(0, 0);
+        assert_eq!(iter.collect::<Vec<_>>(), vec![2, 4, 8]);
+    }
+}
@@ -45,7 +45,7 @@ mod test_mem_graph {
         // Check the edge list
         let entry = mem_graph.map.get(&1).unwrap();
         let neighbors = entry.value().read().unwrap();
-        assert_eq!(*neighbors, vec![2]);
+        assert_eq!(**neighbors, vec![2]);
 
         // Size should include: VId (key) + VId (neighbor)
         let expected_size = std::mem::size_of::<VId>() * 2;
@@ -67,7 +67,7 @@ mod test_mem_graph {
         // Check neighbors
         let entry = mem_graph.map.get(&1).unwrap();
         let neighbors = entry.value().read().unwrap();
-        assert_eq!(*neighbors, vec![2, 3]);
+        assert_eq!(**neighbors, vec![2, 3]);
 
         // Size should increase by 2 VIds
         let expected_size = size_after_vertex + std::mem::size_of::<VId>() * 2;
@@ -87,7 +87,7 @@ mod test_mem_graph {
         // Check neighbors
         let entry = mem_graph.map.get(&1).unwrap();
         let neighbors = entry.value().read().unwrap();
-        assert_eq!(*neighbors, vec![2, 3, 4]);
+        assert_eq!(**neighbors, vec![2, 3, 4]);
 
         // Size: 1 VId (key) + 3 VIds (neighbors)
         let expected_size = std::mem::size_of::<VId>() * 4;
@@ -121,7 +121,7 @@ mod test_mem_graph {
         // Check vertex 3's neighbors
         let entry3 = mem_graph.map.get(&3).unwrap();
         let neighbors3 = entry3.value().read().unwrap();
-        assert_eq!(*neighbors3, vec![5]);
+        assert_eq!(**neighbors3, vec![5]);
 
         // Size: 3 VIds (keys) + 5 VIds (neighbors)
         let expected_size = std::mem::size_of::<VId>() * 8;
@@ -155,12 +155,12 @@ mod test_mem_graph {
         // Check vertex 1's neighbors
         let entry1 = mem_graph.map.get(&1).unwrap();
         let neighbors1 = entry1.value().read().unwrap();
-        assert_eq!(*neighbors1, vec![3, 4]);
+        assert_eq!(**neighbors1, vec![3, 4]);
 
         // Check vertex 2's neighbors
         let entry2 = mem_graph.map.get(&2).unwrap();
         let neighbors2 = entry2.value().read().unwrap();
-        assert_eq!(*neighbors2, vec![5]);
+        assert_eq!(**neighbors2, vec![5]);
 
         // Size should increase by 3 VIds (the 3 neighbors)
         let expected_size = size_after_vertices + std::mem::size_of::<VId>() * 3;
@@ -185,17 +185,17 @@ mod test_mem_graph {
         // Check vertex 1: should have [2, 4]
         let entry1 = mem_graph.map.get(&1).unwrap();
         let neighbors1 = entry1.value().read().unwrap();
-        assert_eq!(*neighbors1, vec![2, 4]);
+        assert_eq!(**neighbors1, vec![2, 4]);
 
         // Check vertex 2: should have [3]
         let entry2 = mem_graph.map.get(&2).unwrap();
         let neighbors2 = entry2.value().read().unwrap();
-        assert_eq!(*neighbors2, vec![3]);
+        assert_eq!(**neighbors2, vec![3]);
 
         // Check vertex 3: should have [5, 6]
         let entry3 = mem_graph.map.get(&3).unwrap();
         let neighbors3 = entry3.value().read().unwrap();
-        assert_eq!(*neighbors3, vec![5, 6]);
+        assert_eq!(**neighbors3, vec![5, 6]);
     }
 
     #[test]
@@ -210,7 +210,7 @@ mod test_mem_graph {
         // Should have 3 duplicate edges
         let entry = mem_graph.map.get(&1).unwrap();
         let neighbors = entry.value().read().unwrap();
-        assert_eq!(*neighbors, vec![2, 2, 2]);
+        assert_eq!(**neighbors, vec![2, 2, 2]);
 
         // Size should reflect all 3 edges
         let expected_size = std::mem::size_of::<VId>() * 4; // 1 key + 3 neighbors
@@ -229,7 +229,7 @@ mod test_mem_graph {
         assert!(mem_graph.map.contains_key(&large_id));
         let entry = mem_graph.map.get(&large_id).unwrap();
         let neighbors = entry.value().read().unwrap();
-        assert_eq!(*neighbors, vec![u32::MAX]);
+        assert_eq!(**neighbors, vec![u32::MAX]);
     }
 
     #[test]
@@ -241,7 +241,7 @@ mod test_mem_graph {
 
         let entry = mem_graph.map.get(&1).unwrap();
         let neighbors = entry.value().read().unwrap();
-        assert_eq!(*neighbors, vec![1]);
+        assert_eq!(**neighbors, vec![1]);
     }
 
     #[test]
@@ -289,7 +289,7 @@ mod test_mem_graph {
             for _ in 0..100 {
                 if let Some(entry) = mem_graph_clone2.map.get(&2) {
                     let neighbors = entry.value().read().unwrap();
-                    assert_eq!(*neighbors, vec![4]);
+                    assert_eq!(**neighbors, vec![4]);
                 }
             }
         });
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod test_memory {
+    use crate::config::LsmCommunityStorageOptions;
+    use crate::memory::{MIN_MEMTABLE_BYTES, MemoryBudget};
+
+    #[test]
+    fn test_partition_within_budget() {
+        let options = LsmCommunityStorageOptions {
+            memory_budget: 1 << 30,
+            ..Default::default()
+        };
+        let budget = MemoryBudget::from_options(&options);
+
+        assert!(budget.total() <= options.memory_budget);
+        assert!(budget.block_cache > budget.giant_cache);
+        assert!(budget.memtable > 0);
+        assert!(budget.rocksdb_cache > 0);
+    }
+
+    #[test]
+    fn test_partition_with_pinned_caches() {
+        let options = LsmCommunityStorageOptions {
+            memory_budget: 100 << 20,
+            block_cache_capacity: 40 << 20,
+            giant_cache_capacity: 20 << 20,
+            ..Default::default()
+        };
+        let budget = MemoryBudget::from_options(&options);
+
+        assert_eq!(budget.block_cache, 40 << 20);
+        assert_eq!(budget.giant_cache, 20 << 20);
+        // The remaining 40MB are split evenly between the RocksDB cache and memtables.
+        assert_eq!(budget.rocksdb_cache, 20 << 20);
+        assert_eq!(budget.memtable, 20 << 20);
+    }
+
+    #[test]
+    fn test_memtable_floor_within_budget() {
+        let options = LsmCommunityStorageOptions {
+            memory_budget: 10 << 20,
+            ..Default::default()
+        };
+        let budget = MemoryBudget::from_options(&options);
+
+        // The floor is taken out of the caches instead of exceeding the budget
+        assert_eq!(budget.memtable, MIN_MEMTABLE_BYTES);
+        assert!(budget.total() <= options.memory_budget);
+        assert!(budget.block_cache > budget.giant_cache);
+    }
+
+    #[test]
+    fn test_rebalance_moves_block_surplus() {
+        let options = LsmCommunityStorageOptions {
+            memory_budget: 1 << 30,
+            ..Default::default()
+        };
+        let budget = MemoryBudget::from_options(&options);
+        let rebalanced = budget.rebalance(&options, 16 << 20);
+
+        assert_eq!(rebalanced.block_cache, 16 << 20);
+        assert!(rebalanced.giant_cache > budget.giant_cache);
+        assert!(rebalanced.rocksdb_cache > budget.rocksdb_cache);
+        assert_eq!(rebalanced.memtable, budget.memtable);
+        assert_eq!(rebalanced.total(), budget.total());
+
+        // Nothing to hand out when the buckets exceed the block cache.
+        assert_eq!(budget.rebalance(&options, u64::MAX), budget);
+    }
+
+    #[test]
+    fn test_rebalance_keeps_pinned_caches() {
+        let pinned_block = LsmCommunityStorageOptions {
+            memory_budget: 1 << 30,
+            block_cache_capacity: 512 << 20,
+            ..Default::default()
+        };
+        let budget = MemoryBudget::from_options(&pinned_block);
+        assert_eq!(budget.rebalance(&pinned_block, 16 << 20), budget);
+
+        let pinned_giant = LsmCommunityStorageOptions {
+            memory_budget: 1 << 30,
+            giant_cache_capacity: 64 << 20,
+            ..Default::default()
+        };
+        let budget = MemoryBudget::from_options(&pinned_giant);
+        let rebalanced = budget.rebalance(&pinned_giant, 16 << 20);
+        assert_eq!(rebalanced.block_cache, 16 << 20);
+        assert_eq!(rebalanced.giant_cache, 64 << 20);
+        assert_eq!(rebalanced.total(), budget.total());
+    }
+}
@@ -25,6 +25,17 @@ pub type VertexList = Vec<VertexEntry>;
 // Page Id type;
 pub type PageId = u32;
 
+/// Direction of the edges followed by a traversal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Follow out edges (source -> target)
+    Out,
+    /// Follow in edges (target -> source)
+    In,
+    /// Follow edges in both directions
+    Both,
+}
+
 /// View of vertex list view;
 #[derive(Debug, Clone)]
 pub struct VertexListView {
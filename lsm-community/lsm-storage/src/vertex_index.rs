@@ -6,6 +6,8 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
+#[cfg(any(test, feature = "fault-injection"))]
+use crate::bucket::io::{FaultInjectionWriter, FaultInjector};
 use crate::{
     cache::CacheKey,
     graph::CsrGraph,
@@ -375,8 +377,32 @@ impl VertexIndex {
         Some(self.vertex_array[vertex_id as usize].is_giant())
     }
 
+    /// Get all vertex IDs in storage order.
+    ///
+    /// Normal vertices are ordered by virtual community (bucket), page and offset, giant
+    /// vertices follow in ID order. Reading vertices in this order keeps batched reads
+    /// within few buckets and pages.
+    pub fn storage_order(&self) -> Vec<VId> {
+        let mut order: Vec<VId> = (0..self.vertex_array.len() as VId).collect();
+        order.sort_by_key(|&vid| self.storage_key(vid));
+        order
+    }
+
+    /// Sort key of a vertex in storage order, see [`VertexIndex::storage_order`].
+    ///
+    /// Sorting any subset of the vertices by this key groups them by bucket and page.
+    pub fn storage_key(&self, vertex_id: VId) -> (u8, u16, u32, u16) {
+        match self.vertex_array[vertex_id as usize].as_normal() {
+            Some((virtual_comm_id, page_id, offset)) => (0, virtual_comm_id, page_id, offset),
+            None => (1, 0, 0, 0),
+        }
+    }
+
     /// Serialize the VertexIndex to a file with compression
     ///
+    /// The index is written to a temporary file renamed over `path` once synced, so that a
+    /// crash never leaves a truncated index behind.
+    ///
     /// # Arguments
     /// * `path` - File path to write to
     /// * `compression_level` - Zstd compression level (1-22, recommended: 3-6 for balance)
@@ -388,21 +414,13 @@ impl VertexIndex {
         path: P,
         compression_level: i32,
     ) -> std::io::Result<()> {
-        let path = path.as_ref();
-
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-
-        let file = File::create(path)?;
-        let buf_writer = BufWriter::with_capacity(8 * 1024 * 1024, file);
-        let mut encoder = zstd::stream::write::Encoder::new(buf_writer, compression_level)?;
-
-        bincode::serialize_into(&mut encoder, self)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-
-        encoder.finish()?;
-        Ok(())
+        write_file_atomically(path.as_ref(), |writer| {
+            let mut encoder = zstd::stream::write::Encoder::new(writer, compression_level)?;
+            bincode::serialize_into(&mut encoder, self)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            encoder.finish()?;
+            Ok(())
+        })
     }
 
     /// Deserialize the VertexIndex from a file
@@ -423,20 +441,10 @@ impl VertexIndex {
 
     /// Serialize without compression (faster but larger files)
     pub fn serialize_to_file_uncompressed<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
-        let path = path.as_ref();
-
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-
-        let file = File::create(path)?;
-        let mut buf_writer = BufWriter::with_capacity(8 * 1024 * 1024, file);
-
-        bincode::serialize_into(&mut buf_writer, self)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-
-        buf_writer.flush()?;
-        Ok(())
+        write_file_atomically(path.as_ref(), |writer| {
+            bincode::serialize_into(writer, self)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+        })
     }
 
     /// Deserialize without compression
@@ -450,3 +458,36 @@ impl VertexIndex {
         Ok(vertex_index)
     }
 }
+
+/// Write a file through `write` into a temporary file next to `path`, then sync it and
+/// rename it into place.
+///
+/// Every save has a temporary file of its own, so saves of the same index running at once
+/// never write into each other's file. Under a fault injector, the temporary file is
+/// written through it.
+pub(crate) fn write_file_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut dyn Write) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(dir)?;
+
+    let temp_file = tempfile::NamedTempFile::new_in(dir)?;
+    #[cfg(any(test, feature = "fault-injection"))]
+    let stream: Box<dyn Write + '_> = match FaultInjector::find(path) {
+        Some(injector) => Box::new(FaultInjectionWriter::new(temp_file.as_file(), injector)?),
+        None => Box::new(temp_file.as_file()),
+    };
+    #[cfg(not(any(test, feature = "fault-injection")))]
+    let stream = temp_file.as_file();
+    let mut buf_writer = BufWriter::with_capacity(8 * 1024 * 1024, stream);
+    write(&mut buf_writer)?;
+    buf_writer.flush()?;
+    drop(buf_writer);
+    temp_file.as_file().sync_all()?;
+    temp_file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
@@ -217,4 +217,164 @@ public class RustJNI {
      * @return Array containing community count followed by (vertex handle, community ID) pairs
      */
     public native long[] getCommunities(long graphHandle);
+
+    /**
+     * Get all vertices within k hops of the given vertex together with their hop distance
+     * Data format: If m vertices are found, returns 2m values
+     * vertexHandle_1, hops_1, vertexHandle_2, hops_2, ..., vertexHandle_m, hops_m
+     * (the starting vertex is not included, vertices are ordered by hop distance)
+     * @param graphHandle Native database handle
+     * @param vertexHandle Native handle of the starting vertex
+     * @param k Maximum number of hops
+     * @param direction Edges to follow: 0 = out, 1 = in, 2 = both
+     * @param limit Maximum number of returned vertices, 0 or negative for no limit
+     * @return Array containing pairs of (vertex handle, hop distance)
+     */
+    public native long[] getKHopVertices(long graphHandle, long vertexHandle, int k, int direction, long limit);
+
+    /**
+     * Get the ego network of the given vertex: all edges among the vertices within k hops of it
+     * (edges are followed in both directions to find the vertices)
+     * @param graphHandle Native database handle
+     * @param vertexHandle Native handle of the center vertex
+     * @param k Radius of the ego network in hops
+     * @return Array of native edge handles
+     */
+    public native long[] getEgoNetworkEdges(long graphHandle, long vertexHandle, int k);
+
+    /**
+     * Store the weight of an edge as a named edge property, used by {@link #getShortestPath}
+     * @param graphHandle Native database handle
+     * @param edgeHandle Native edge handle
+     * @param propertyName Name of the weight property
+     * @param weight Non-negative weight of the edge
+     */
+    public native void putEdgeWeight(long graphHandle, long edgeHandle, String propertyName, double weight);
+
+    /**
+     * Find the weighted shortest path between two vertices
+     * Data format: If the path has n vertices, returns n + 1 values
+     * costBits, vertexHandle_1, vertexHandle_2, ..., vertexHandle_n
+     * (costBits: total cost, decode with Double.longBitsToDouble; vertexHandle_1 is the source
+     * and vertexHandle_n the target). Returns an empty array if the target is unreachable.
+     * Edges without the weight property are not traversed.
+     * @param graphHandle Native database handle
+     * @param srcVertexHandle Native handle of the source vertex
+     * @param dstVertexHandle Native handle of the target vertex
+     * @param weightProperty Name of the edge weight property, null or empty for unit weights
+     * @param algorithm 0 = Dijkstra, 1 = bidirectional Dijkstra (builds the in-neighbor lists
+     *                  of the graph again after an edge update)
+     * @return Array containing the path cost followed by the vertex handles of the path
+     */
+    public native long[] getShortestPath(long graphHandle, long srcVertexHandle, long dstVertexHandle, String weightProperty, int algorithm);
+
+    // ------------------------------ Catalog Operations ------------------------------
+
+    /**
+     * Open the graph catalog of a workspace directory
+     * Graphs found in the workspace but not in its catalog file are not listed
+     * @param workSpaceDir Workspace directory, created if missing
+     * @return Native catalog handle, to be released with {@link #closeCatalog}
+     */
+    public native long openCatalog(String workSpaceDir);
+
+    /**
+     * Release a catalog, graphs opened through it stay open until {@link #closeDB}
+     * @param catalogHandle Native catalog handle
+     */
+    public native void closeCatalog(long catalogHandle);
+
+    /**
+     * List the names of the graphs in a catalog
+     * @param catalogHandle Native catalog handle
+     * @return Graph names in name order
+     */
+    public native String[] listGraphs(long catalogHandle);
+
+    /**
+     * Create an empty graph in a catalog and open it read-write
+     * @param catalogHandle Native catalog handle
+     * @param graphName Name of the new graph, a plain directory name
+     * @return Native database handle, to be released with {@link #closeDB}
+     */
+    public native long createGraph(long catalogHandle, String graphName);
+
+    /**
+     * Create a graph in a catalog from a graph file and open it read-write
+     * @param catalogHandle Native catalog handle
+     * @param graphName Name of the new graph, a plain directory name
+     * @param graphPath Path of the graph file
+     * @return Native database handle, to be released with {@link #closeDB}
+     */
+    public native long importGraph(long catalogHandle, String graphName, String graphPath);
+
+    /**
+     * Open a graph of a catalog, opening it again in the same mode returns the same graph
+     * @param catalogHandle Native catalog handle
+     * @param graphName Name of the graph
+     * @param readOnly Reject every mutation on the returned handle
+     * @return Native database handle, to be released with {@link #closeDB}
+     */
+    public native long openGraph(long catalogHandle, String graphName, boolean readOnly);
+
+    /**
+     * Rename a closed graph of a catalog
+     * @param catalogHandle Native catalog handle
+     * @param graphName Name of the graph
+     * @param newGraphName New name of the graph, a plain directory name
+     */
+    public native void renameGraph(long catalogHandle, String graphName, String newGraphName);
+
+    /**
+     * Drop a closed graph of a catalog with all its files
+     * @param catalogHandle Native catalog handle
+     * @param graphName Name of the graph
+     */
+    public native void dropGraph(long catalogHandle, String graphName);
+
+    /**
+     * Describe a graph of a catalog
+     * Data format: JSON object with the name, options, creation time (created_at), bucket count,
+     * size on disk (disk_size) and open mode (open_mode, null if closed) of the graph
+     * @param catalogHandle Native catalog handle
+     * @param graphName Name of the graph
+     * @return JSON description of the graph
+     */
+    public native String getGraphInfo(long catalogHandle, String graphName);
+
+    // ------------------------------ Change Log Operations ------------------------------
+
+    /**
+     * Get the sequence number of the next change of a graph
+     * Requires the change_log option of the graph
+     * @param graphHandle Native database handle
+     * @return Sequence number to subscribe from for the changes made from now on
+     */
+    public native long getNextChangeSeq(long graphHandle);
+
+    /**
+     * Subscribe to the changes of a graph
+     * Expired edges and the labels removed with them are not reported
+     * @param graphHandle Native database handle
+     * @param fromSeq Sequence number of the first change returned, not yet trimmed by the retention
+     * @return Native subscription handle, to be released with {@link #closeSubscription}
+     */
+    public native long subscribeChanges(long graphHandle, long fromSeq);
+
+    /**
+     * Poll the next changes of a subscription
+     * Data format: JSON array of the changes in sequence order, each an object with its seq,
+     * timestamp, kind, vertex_id, neighbor and property_name
+     * @param subscriptionHandle Native subscription handle
+     * @param maxChanges Maximum number of changes returned
+     * @param timeoutMillis How long to wait if no change is available, 0 returns at once
+     * @return JSON array of the changes, empty if none came before the timeout
+     */
+    public native String pollChanges(long subscriptionHandle, int maxChanges, long timeoutMillis);
+
+    /**
+     * Release a subscription
+     * @param subscriptionHandle Native subscription handle
+     */
+    public native void closeSubscription(long subscriptionHandle);
 }
\ No newline at end of file